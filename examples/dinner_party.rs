@@ -171,15 +171,6 @@ fn main() {
         narrative_fn: NarrativeFunction::Alliance,
         metadata: HashMap::new(),
     };
-    print_scene(
-        1,
-        "Small Talk",
-        &["Margaret", "Robert"],
-        &mut engine,
-        &event1,
-        &world,
-    );
-
     // --- Scene 2: A Whispered Alliance (Eleanor and Robert align) ---
     let event2 = Event {
         event_type: "whispered_aside".to_string(),
@@ -203,15 +194,6 @@ fn main() {
         narrative_fn: NarrativeFunction::Alliance,
         metadata: HashMap::new(),
     };
-    print_scene(
-        2,
-        "A Whispered Aside",
-        &["Eleanor", "Robert"],
-        &mut engine,
-        &event2,
-        &world,
-    );
-
     // --- Scene 3: Tension Builds (Confrontation — tense, rising) ---
     let event3 = Event {
         event_type: "tension_rises".to_string(),
@@ -235,15 +217,6 @@ fn main() {
         narrative_fn: NarrativeFunction::Confrontation,
         metadata: HashMap::new(),
     };
-    print_scene(
-        3,
-        "Tension Builds",
-        &["Eleanor", "Margaret"],
-        &mut engine,
-        &event3,
-        &world,
-    );
-
     // --- Scene 4: The Accusation (Confrontation — tense, high stakes) ---
     let event4 = Event {
         event_type: "accusation".to_string(),
@@ -267,15 +240,6 @@ fn main() {
         narrative_fn: NarrativeFunction::Confrontation,
         metadata: HashMap::new(),
     };
-    print_scene(
-        4,
-        "The Accusation",
-        &["Eleanor", "James"],
-        &mut engine,
-        &event4,
-        &world,
-    );
-
     // --- Scene 5: The Revelation (James's secret comes out) ---
     let event5 = Event {
         event_type: "confession".to_string(),
@@ -299,15 +263,6 @@ fn main() {
         narrative_fn: NarrativeFunction::Revelation,
         metadata: HashMap::new(),
     };
-    print_scene(
-        5,
-        "The Revelation",
-        &["James", "Margaret"],
-        &mut engine,
-        &event5,
-        &world,
-    );
-
     // --- Scene 6: Comic Relief (Robert breaks the tension) ---
     let event6 = Event {
         event_type: "comic_relief".to_string(),
@@ -331,15 +286,6 @@ fn main() {
         narrative_fn: NarrativeFunction::ComicRelief,
         metadata: HashMap::new(),
     };
-    print_scene(
-        6,
-        "The Aftermath",
-        &["Robert", "Eleanor"],
-        &mut engine,
-        &event6,
-        &world,
-    );
-
     // --- Scene 7: Betrayal (Margaret realizes James and Eleanor) ---
     let event7 = Event {
         event_type: "betrayal_realized".to_string(),
@@ -363,28 +309,34 @@ fn main() {
         narrative_fn: NarrativeFunction::Betrayal,
         metadata: HashMap::new(),
     };
-    print_scene(
-        7,
-        "The Betrayal",
-        &["Margaret", "James"],
-        &mut engine,
-        &event7,
-        &world,
-    );
+    // --- Narrate the whole evening in one pass, sharing the repetition context ---
+    let scenes: [(u32, &str, &[&str]); 7] = [
+        (1, "Small Talk", &["Margaret", "Robert"]),
+        (2, "A Whispered Aside", &["Eleanor", "Robert"]),
+        (3, "Tension Builds", &["Eleanor", "Margaret"]),
+        (4, "The Accusation", &["Eleanor", "James"]),
+        (5, "The Revelation", &["James", "Margaret"]),
+        (6, "The Aftermath", &["Robert", "Eleanor"]),
+        (7, "The Betrayal", &["Margaret", "James"]),
+    ];
+    let events = [event1, event2, event3, event4, event5, event6, event7];
+    match engine.narrate_sequence(&events, &world) {
+        Ok(passages) => {
+            for ((&(number, title, participants), event), text) in
+                scenes.iter().zip(&events).zip(&passages)
+            {
+                print_scene(number, title, participants, event, text);
+            }
+        }
+        Err(e) => println!("[Generation error: {}]", e),
+    }
 
     println!("========================================");
     println!("   FIN");
     println!("========================================");
 }
 
-fn print_scene(
-    number: u32,
-    title: &str,
-    participants: &[&str],
-    engine: &mut NarrativeEngine,
-    event: &Event,
-    world: &WorldState<'_>,
-) {
+fn print_scene(number: u32, title: &str, participants: &[&str], event: &Event, text: &str) {
     println!("--- Scene {}: {} ---", number, title);
     println!(
         "[{} | {} | {}]",
@@ -394,10 +346,7 @@ fn print_scene(
     );
     println!();
 
-    match engine.narrate(event, world) {
-        Ok(text) => println!("{}", text),
-        Err(e) => println!("[Generation error: {}]", e),
-    }
+    println!("{}", text);
 
     println!();
     println!();
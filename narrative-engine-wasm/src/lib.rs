@@ -31,7 +31,7 @@ mod data {
 // ---------------------------------------------------------------------------
 // JSON helper types for communication across the WASM boundary
 // ---------------------------------------------------------------------------
-#[derive(serde::Deserialize)]
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
 struct EventInput {
     subject_id: u64,
     object_id: Option<u64>,
@@ -56,56 +56,36 @@ struct ScenarioInfo {
     entities: Vec<EntityInfo>,
 }
 
-// ---------------------------------------------------------------------------
-// Conversion helpers
-// ---------------------------------------------------------------------------
-fn parse_mood(s: &str) -> Mood {
-    match s.to_lowercase().as_str() {
-        "neutral" => Mood::Neutral,
-        "tense" => Mood::Tense,
-        "warm" => Mood::Warm,
-        "dread" => Mood::Dread,
-        "euphoric" => Mood::Euphoric,
-        "somber" => Mood::Somber,
-        "chaotic" => Mood::Chaotic,
-        "intimate" => Mood::Intimate,
-        _ => Mood::Neutral,
-    }
-}
-
-fn parse_stakes(s: &str) -> Stakes {
-    match s.to_lowercase().as_str() {
-        "trivial" => Stakes::Trivial,
-        "low" => Stakes::Low,
-        "medium" => Stakes::Medium,
-        "high" => Stakes::High,
-        "critical" => Stakes::Critical,
-        _ => Stakes::Medium,
-    }
+#[derive(serde::Serialize)]
+struct LabeledVariant {
+    seed: u64,
+    text: String,
 }
 
-fn parse_narrative_fn(s: &str) -> NarrativeFunction {
-    match s.to_lowercase().as_str() {
-        "revelation" => NarrativeFunction::Revelation,
-        "escalation" => NarrativeFunction::Escalation,
-        "confrontation" => NarrativeFunction::Confrontation,
-        "betrayal" => NarrativeFunction::Betrayal,
-        "alliance" => NarrativeFunction::Alliance,
-        "discovery" => NarrativeFunction::Discovery,
-        "loss" => NarrativeFunction::Loss,
-        "comic_relief" => NarrativeFunction::ComicRelief,
-        "foreshadowing" => NarrativeFunction::Foreshadowing,
-        "status_change" => NarrativeFunction::StatusChange,
-        other => NarrativeFunction::Custom(other.to_string()),
-    }
+/// Everything needed to reproduce a narration, for bug reports.
+#[derive(serde::Serialize)]
+struct NarrationTrace {
+    genre: String,
+    event: EventInput,
+    narrative_fn: String,
+    seed: u64,
+    generation_count: u64,
+    output: Option<String>,
+    error: Option<String>,
 }
 
-fn pronouns_label(p: &Pronouns) -> &'static str {
+// ---------------------------------------------------------------------------
+// Conversion helpers
+// ---------------------------------------------------------------------------
+fn pronouns_label(p: &Pronouns) -> String {
     match p {
-        Pronouns::SheHer => "she/her",
-        Pronouns::HeHim => "he/him",
-        Pronouns::TheyThem => "they/them",
-        Pronouns::ItIts => "it/its",
+        Pronouns::SheHer => "she/her".to_string(),
+        Pronouns::HeHim => "he/him".to_string(),
+        Pronouns::TheyThem => "they/them".to_string(),
+        Pronouns::ItIts => "it/its".to_string(),
+        Pronouns::Custom {
+            subject, object, ..
+        } => format!("{}/{}", subject, object),
     }
 }
 
@@ -247,6 +227,11 @@ pub struct NarrativeDemo {
     engine: NarrativeEngine,
     entities: HashMap<EntityId, Entity>,
     genre: String,
+    last_trace: Option<NarrationTrace>,
+    /// Seed handed to the next labeled variant.
+    next_variant_seed: u64,
+    /// Built-in functions the genre's grammar can generate.
+    supported: Vec<String>,
 }
 
 #[wasm_bindgen]
@@ -274,6 +259,11 @@ impl NarrativeDemo {
 
         let grammars = GrammarSet::parse_ron(grammar_src)
             .map_err(|e| JsError::new(&format!("Grammar parse error: {e}")))?;
+        let supported = grammars
+            .supported_functions()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
 
         let mut voices = VoiceRegistry::new();
         voices
@@ -296,6 +286,9 @@ impl NarrativeDemo {
             engine,
             entities,
             genre: genre.to_string(),
+            last_trace: None,
+            next_variant_seed: seed,
+            supported,
         })
     }
 
@@ -319,9 +312,28 @@ impl NarrativeDemo {
         let world = WorldState {
             entities: &self.entities,
         };
-        self.engine
-            .narrate(&event, &world)
-            .map_err(|e| JsError::new(&format!("Narration error: {e}")))
+        let narrative_fn = self.engine.resolve_narrative_fn(&event);
+        let generation_count = self.engine.generation_count();
+        let result = self.engine.narrate(&event, &world);
+
+        self.last_trace = Some(NarrationTrace {
+            genre: self.genre.clone(),
+            event: input,
+            narrative_fn: narrative_fn.name().to_string(),
+            seed: self.engine.seed(),
+            generation_count,
+            output: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+
+        result.map_err(|e| JsError::new(&format!("Narration error: {e}")))
+    }
+
+    /// Return a JSON trace of the last `narrate` call (event input, resolved
+    /// function, seed, generation count, and output) for bug reports.
+    /// Returns `"null"` if nothing has been narrated yet.
+    pub fn last_trace(&self) -> String {
+        serde_json::to_string(&self.last_trace).unwrap_or_else(|_| "null".to_string())
     }
 
     /// Generate multiple variants for the same event. Returns a JSON array of strings.
@@ -340,6 +352,48 @@ impl NarrativeDemo {
             .map_err(|e| JsError::new(&format!("Serialization error: {e}")))
     }
 
+    /// Generate `count` variants, each from its own seed. Returns a JSON
+    /// array of `{"seed": n, "text": "..."}`; pass a seed to
+    /// `narrate_variants_seeded` to reproduce that variant. Successive calls
+    /// hand out fresh seeds.
+    pub fn narrate_labeled_variants(
+        &mut self,
+        event_json: &str,
+        count: usize,
+    ) -> Result<String, JsError> {
+        let input: EventInput = serde_json::from_str(event_json)
+            .map_err(|e| JsError::new(&format!("Invalid event JSON: {e}")))?;
+        let event = self.build_event(&input);
+        let world = WorldState {
+            entities: &self.entities,
+        };
+        let mut variants = Vec::with_capacity(count);
+        for _ in 0..count {
+            let seed = self.next_variant_seed;
+            self.next_variant_seed = seed.wrapping_add(1);
+            let text = self
+                .engine
+                .narrate_seeded(&event, seed, &world)
+                .map_err(|e| JsError::new(&format!("Narration error: {e}")))?;
+            variants.push(LabeledVariant { seed, text });
+        }
+        serde_json::to_string(&variants)
+            .map_err(|e| JsError::new(&format!("Serialization error: {e}")))
+    }
+
+    /// Reproduce a variant from `narrate_labeled_variants` by its seed.
+    pub fn narrate_variants_seeded(&self, event_json: &str, seed: u64) -> Result<String, JsError> {
+        let input: EventInput = serde_json::from_str(event_json)
+            .map_err(|e| JsError::new(&format!("Invalid event JSON: {e}")))?;
+        let event = self.build_event(&input);
+        let world = WorldState {
+            entities: &self.entities,
+        };
+        self.engine
+            .narrate_seeded(&event, seed, &world)
+            .map_err(|e| JsError::new(&format!("Narration error: {e}")))
+    }
+
     /// Return a JSON description of the current scenario (genre + entities).
     pub fn get_scenario(&self) -> Result<String, JsError> {
         let entities: Vec<EntityInfo> = self
@@ -348,7 +402,7 @@ impl NarrativeDemo {
             .map(|e| EntityInfo {
                 id: e.id.0,
                 name: e.name.clone(),
-                pronouns: pronouns_label(&e.pronouns).to_string(),
+                pronouns: pronouns_label(&e.pronouns),
                 tags: e.tags.iter().cloned().collect(),
                 voice_id: e.voice_id.map(|v| v.0),
             })
@@ -401,24 +455,7 @@ impl NarrativeDemo {
     /// Return JSON array of narrative functions that have grammar rules
     /// in the current genre. Only these will produce output without error.
     pub fn supported_functions(&self) -> String {
-        let fns: &[&str] = match self.genre.as_str() {
-            "social_drama" => &[
-                "revelation",
-                "confrontation",
-                "betrayal",
-                "alliance",
-                "comic_relief",
-            ],
-            "survival_thriller" => &[
-                "escalation",
-                "discovery",
-                "loss",
-                "foreshadowing",
-                "status_change",
-            ],
-            _ => &[],
-        };
-        serde_json::to_string(fns).unwrap_or_else(|_| "[]".to_string())
+        serde_json::to_string(&self.supported).unwrap_or_else(|_| "[]".to_string())
     }
 
     /// Reset the engine with a new seed (same genre).
@@ -426,6 +463,8 @@ impl NarrativeDemo {
         let new_demo = NarrativeDemo::new(&self.genre.clone(), seed)?;
         self.engine = new_demo.engine;
         self.entities = new_demo.entities;
+        self.last_trace = None;
+        self.next_variant_seed = seed;
         Ok(())
     }
 }
@@ -443,6 +482,8 @@ impl NarrativeDemo {
                 role: "object".to_string(),
             });
         }
+        // Unknown names parse as Custom, so this can't fail
+        let Ok(narrative_fn) = input.narrative_fn.parse::<NarrativeFunction>();
 
         Event {
             event_type: input
@@ -451,11 +492,83 @@ impl NarrativeDemo {
                 .unwrap_or_else(|| input.narrative_fn.clone()),
             participants,
             location: None,
-            mood: parse_mood(&input.mood),
-            stakes: parse_stakes(&input.stakes),
+            mood: input.mood.parse().unwrap_or(Mood::Neutral),
+            stakes: input.stakes.parse().unwrap_or(Stakes::Medium),
             outcome: None,
-            narrative_fn: parse_narrative_fn(&input.narrative_fn),
+            narrative_fn,
             metadata: HashMap::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_trace_records_event_and_output() {
+        let mut demo = NarrativeDemo::new("social_drama", 42).unwrap();
+        assert_eq!(demo.last_trace(), "null");
+
+        let text = demo
+            .narrate(
+                r#"{"subject_id": 1, "object_id": 2, "mood": "tense", "stakes": "high", "narrative_fn": "confrontation"}"#,
+            )
+            .unwrap();
+
+        let trace: serde_json::Value = serde_json::from_str(&demo.last_trace()).unwrap();
+        assert_eq!(trace["event"]["mood"], "tense");
+        assert_eq!(trace["narrative_fn"], "confrontation");
+        assert_eq!(trace["seed"], 42);
+        assert_eq!(trace["generation_count"], 0);
+        assert_eq!(trace["output"], text.as_str());
+    }
+
+    #[test]
+    fn supported_functions_come_from_grammar() {
+        let demo = NarrativeDemo::new("social_drama", 1).unwrap();
+        let fns: Vec<String> = serde_json::from_str(&demo.supported_functions()).unwrap();
+        assert_eq!(
+            fns,
+            [
+                "revelation",
+                "confrontation",
+                "betrayal",
+                "alliance",
+                "comic_relief"
+            ]
+        );
+
+        let demo = NarrativeDemo::new("survival_thriller", 1).unwrap();
+        let fns: Vec<String> = serde_json::from_str(&demo.supported_functions()).unwrap();
+        assert_eq!(
+            fns,
+            [
+                "escalation",
+                "discovery",
+                "loss",
+                "foreshadowing",
+                "status_change"
+            ]
+        );
+    }
+
+    #[test]
+    fn labeled_variants_reproduce_from_seed() {
+        let mut demo = NarrativeDemo::new("social_drama", 7).unwrap();
+        let event_json = r#"{"subject_id": 1, "object_id": 2, "mood": "tense", "stakes": "high", "narrative_fn": "confrontation"}"#;
+
+        let json = demo.narrate_labeled_variants(event_json, 4).unwrap();
+        let variants: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(variants.len(), 4);
+        for variant in &variants {
+            let seed = variant["seed"].as_u64().unwrap();
+            let text = variant["text"].as_str().unwrap();
+            assert!(!text.is_empty());
+            assert_eq!(
+                demo.narrate_variants_seeded(event_json, seed).unwrap(),
+                text
+            );
+        }
+    }
+}
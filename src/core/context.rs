@@ -1,8 +1,11 @@
 /// Narrative context — anti-repetition tracking and pronoun management.
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::schema::entity::Pronouns;
 
 /// A sliding window of recently generated passages for repetition detection.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NarrativeContext {
     /// Recent passages (most recent last).
     passages: Vec<String>,
@@ -14,6 +17,16 @@ pub struct NarrativeContext {
     word_counts: HashMap<String, usize>,
     /// Entity mention counts for pronoun decisions.
     pub entity_mentions: HashMap<String, usize>,
+    /// Recently selected entry alternatives as (rule name, alternative index).
+    recent_alternatives: Vec<(String, usize)>,
+    /// Words that never count as significant for repetition tracking.
+    stopwords: HashSet<String>,
+    /// Occurrences across the window at which a word counts as overused.
+    overuse_threshold: usize,
+    /// Minimum character length for a word to count as significant.
+    min_significant_len: usize,
+    /// Sentence-length standard deviation below which text is monotonous.
+    monotony_stddev: f64,
 }
 
 impl Default for NarrativeContext {
@@ -22,6 +35,47 @@ impl Default for NarrativeContext {
     }
 }
 
+/// Tunable settings for repetition tracking.
+#[derive(Debug, Clone)]
+pub struct ContextConfig {
+    /// Maximum number of passages to track.
+    pub window_size: usize,
+    /// Words excluded from significant-word counting (lowercase).
+    pub stopwords: HashSet<String>,
+    /// Occurrences across the window (including the candidate) at which
+    /// a significant word is flagged as overused (default 4).
+    pub overuse_threshold: usize,
+    /// Minimum character length for a word to count as significant
+    /// (default 5).
+    pub min_significant_len: usize,
+    /// Sentence-length standard deviation below which recent passages
+    /// are flagged as structurally monotonous (default 2.0).
+    pub monotony_stddev: f64,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            window_size: 10,
+            stopwords: STOPWORDS.iter().map(|w| w.to_string()).collect(),
+            overuse_threshold: 4,
+            min_significant_len: 5,
+            monotony_stddev: 2.0,
+        }
+    }
+}
+
+/// A snapshot of what the repetition tracker currently holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextStats {
+    /// Passages in the window.
+    pub tracked_passages: usize,
+    /// Distinct sentence openings in the window.
+    pub unique_openings: usize,
+    /// Most frequent significant words, highest count first (at most 10).
+    pub top_words: Vec<(String, usize)>,
+}
+
 /// An issue detected by repetition checking.
 #[derive(Debug, Clone, PartialEq)]
 pub enum RepetitionIssue {
@@ -33,6 +87,10 @@ pub enum RepetitionIssue {
     StructuralMonotony,
 }
 
+/// Passages back within which a named entity counts as recently mentioned
+/// for pronoun substitution.
+const PRONOUN_WINDOW: usize = 2;
+
 /// Stopwords that don't count as "significant" for repetition tracking.
 const STOPWORDS: &[&str] = &[
     "the", "a", "an", "and", "or", "but", "in", "on", "at", "to", "for", "of", "with", "by",
@@ -45,12 +103,25 @@ const STOPWORDS: &[&str] = &[
 
 impl NarrativeContext {
     pub fn new(window_size: usize) -> Self {
+        Self::with_config(ContextConfig {
+            window_size,
+            ..ContextConfig::default()
+        })
+    }
+
+    /// Create a context from explicit settings.
+    pub fn with_config(config: ContextConfig) -> Self {
         Self {
             passages: Vec::new(),
-            window_size,
+            window_size: config.window_size,
             recent_openings: Vec::new(),
             word_counts: HashMap::new(),
             entity_mentions: HashMap::new(),
+            recent_alternatives: Vec::new(),
+            stopwords: config.stopwords,
+            overuse_threshold: config.overuse_threshold,
+            min_significant_len: config.min_significant_len,
+            monotony_stddev: config.monotony_stddev,
         }
     }
 
@@ -75,6 +146,53 @@ impl NarrativeContext {
         self.rebuild_word_counts();
     }
 
+    /// Summarize the current window for debugging variety.
+    pub fn stats(&self) -> ContextStats {
+        let unique_openings: HashSet<&String> = self.recent_openings.iter().collect();
+        let mut top_words: Vec<(String, usize)> = self
+            .word_counts
+            .iter()
+            .map(|(word, count)| (word.clone(), *count))
+            .collect();
+        // Ties broken alphabetically so the list is stable
+        top_words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_words.truncate(10);
+        ContextStats {
+            tracked_passages: self.passages.len(),
+            unique_openings: unique_openings.len(),
+            top_words,
+        }
+    }
+
+    /// Forget all tracked passages, openings, word counts, entity mentions,
+    /// and alternative selections. Window size, stopwords, and thresholds
+    /// are kept.
+    pub fn clear(&mut self) {
+        self.passages.clear();
+        self.recent_openings.clear();
+        self.word_counts.clear();
+        self.entity_mentions.clear();
+        self.recent_alternatives.clear();
+    }
+
+    /// Record which alternative was selected for an entry rule.
+    pub fn record_alternative(&mut self, rule_name: &str, index: usize) {
+        self.recent_alternatives
+            .push((rule_name.to_string(), index));
+        if self.recent_alternatives.len() > self.window_size {
+            self.recent_alternatives.remove(0);
+        }
+    }
+
+    /// The most recently selected alternative index for a rule, if any.
+    pub fn last_alternative(&self, rule_name: &str) -> Option<usize> {
+        self.recent_alternatives
+            .iter()
+            .rev()
+            .find(|(name, _)| name == rule_name)
+            .map(|(_, index)| *index)
+    }
+
     /// Check a candidate passage for repetition issues.
     pub fn check_repetition(&self, candidate: &str) -> Vec<RepetitionIssue> {
         let mut issues = Vec::new();
@@ -86,11 +204,12 @@ impl NarrativeContext {
         }
 
         // Check overused words (combining existing counts with candidate)
-        let candidate_words = extract_significant_words(candidate);
+        let candidate_words =
+            extract_significant_words(candidate, &self.stopwords, self.min_significant_len);
         for word in &candidate_words {
             let existing = self.word_counts.get(word.as_str()).copied().unwrap_or(0);
             let total = existing + 1;
-            if total >= 4 {
+            if total >= self.overuse_threshold {
                 issues.push(RepetitionIssue::OverusedWord {
                     word: word.clone(),
                     count: total,
@@ -114,7 +233,7 @@ impl NarrativeContext {
                 let stddev = variance.sqrt();
 
                 // If standard deviation is very low, sentences are monotonously uniform
-                if stddev < 2.0 && mean > 3.0 {
+                if stddev < self.monotony_stddev && mean > 3.0 {
                     issues.push(RepetitionIssue::StructuralMonotony);
                 }
             }
@@ -123,10 +242,40 @@ impl NarrativeContext {
         issues
     }
 
+    /// Replace sentence-initial repeat mentions of an entity's name with
+    /// its subject pronoun ("Margaret sat. Margaret rose." → "Margaret sat.
+    /// She rose."). A mention is a repeat if the name already appeared
+    /// earlier in `text` or in the last couple of recorded passages.
+    ///
+    /// Names are left alone when another entity in `entities` shares the
+    /// same pronouns, and possessives (`Margaret's`) are never replaced.
+    pub fn substitute_pronouns(&self, text: &str, entities: &[(&str, &Pronouns)]) -> String {
+        let recent = &self.passages[self.passages.len().saturating_sub(PRONOUN_WINDOW)..];
+        let mut result = text.to_string();
+        for (name, pronouns) in entities {
+            if name.is_empty() {
+                continue;
+            }
+            let ambiguous = entities
+                .iter()
+                .any(|(other, other_pronouns)| other != name && other_pronouns == pronouns);
+            if ambiguous {
+                continue;
+            }
+            let mentioned = recent
+                .iter()
+                .any(|passage| !name_occurrences(passage, name).is_empty());
+            result = replace_repeat_mentions(&result, name, pronouns.subject(), mentioned);
+        }
+        result
+    }
+
     fn rebuild_word_counts(&mut self) {
         self.word_counts.clear();
         for passage in &self.passages {
-            for word in extract_significant_words(passage) {
+            for word in
+                extract_significant_words(passage, &self.stopwords, self.min_significant_len)
+            {
                 *self.word_counts.entry(word).or_default() += 1;
             }
         }
@@ -142,14 +291,58 @@ fn extract_opening(text: &str) -> String {
         .join(" ")
 }
 
-/// Extract "significant" words: length > 4, not a stopword.
-fn extract_significant_words(text: &str) -> Vec<String> {
+/// Byte offsets of whole-word occurrences of `name` in `text`.
+fn name_occurrences(text: &str, name: &str) -> Vec<usize> {
+    text.match_indices(name)
+        .filter(|(start, _)| {
+            let before = text[..*start].chars().next_back();
+            let after = text[start + name.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+        .map(|(start, _)| start)
+        .collect()
+}
+
+/// Swap sentence-initial, non-possessive mentions of `name` for `pronoun`,
+/// skipping the first mention unless `mentioned` is already set.
+fn replace_repeat_mentions(text: &str, name: &str, pronoun: &str, mut mentioned: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for start in name_occurrences(text, name) {
+        let end = start + name.len();
+        let sentence_start = text[..start]
+            .trim_end()
+            .chars()
+            .next_back()
+            .is_none_or(|c| matches!(c, '.' | '!' | '?'));
+        let possessive = text[end..].starts_with(['\'', '\u{2019}']);
+        if mentioned && sentence_start && !possessive {
+            result.push_str(&text[last..start]);
+            let mut chars = pronoun.chars();
+            if let Some(first) = chars.next() {
+                result.extend(first.to_uppercase());
+                result.push_str(chars.as_str());
+            }
+            last = end;
+        }
+        mentioned = true;
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+/// Extract "significant" words: at least `min_len` characters, not a stopword.
+fn extract_significant_words(
+    text: &str,
+    stopwords: &HashSet<String>,
+    min_len: usize,
+) -> Vec<String> {
     text.split_whitespace()
         .map(|w| {
             w.trim_matches(|c: char| !c.is_alphanumeric())
                 .to_lowercase()
         })
-        .filter(|w| w.len() > 4 && !STOPWORDS.contains(&w.as_str()))
+        .filter(|w| w.chars().count() >= min_len && !stopwords.contains(w))
         .collect()
 }
 
@@ -165,6 +358,158 @@ fn sentence_lengths(text: &str) -> Vec<f64> {
 mod tests {
     use super::*;
 
+    fn extract_significant_words_default(text: &str) -> Vec<String> {
+        let config = ContextConfig::default();
+        extract_significant_words(text, &config.stopwords, config.min_significant_len)
+    }
+
+    #[test]
+    fn lower_overuse_threshold_flags_sooner() {
+        let is_overused = |issues: &[RepetitionIssue]| {
+            issues.iter().any(|i| {
+                matches!(i, RepetitionIssue::OverusedWord { word, count: 2 } if word == "lantern")
+            })
+        };
+
+        let mut ctx = NarrativeContext::default();
+        ctx.record("A lantern swung overhead.");
+        assert!(!is_overused(&ctx.check_repetition("The lantern guttered.")));
+
+        let mut ctx = NarrativeContext::with_config(ContextConfig {
+            overuse_threshold: 2,
+            ..ContextConfig::default()
+        });
+        ctx.record("A lantern swung overhead.");
+        assert!(is_overused(&ctx.check_repetition("The lantern guttered.")));
+    }
+
+    #[test]
+    fn min_significant_len_and_monotony_stddev() {
+        let mut ctx = NarrativeContext::with_config(ContextConfig {
+            min_significant_len: 4,
+            ..ContextConfig::default()
+        });
+        ctx.record("The room was cold.");
+        assert_eq!(ctx.word_counts.get("room"), Some(&1));
+        assert_eq!(ctx.word_counts.get("cold"), Some(&1));
+
+        // Zero tolerance never flags monotony
+        let mut ctx = NarrativeContext::with_config(ContextConfig {
+            monotony_stddev: 0.0,
+            ..ContextConfig::default()
+        });
+        ctx.record("She looked at the door.");
+        ctx.record("He turned to the wall.");
+        ctx.record("They walked to the car.");
+        let issues = ctx.check_repetition("She moved to the room.");
+        assert!(!issues.contains(&RepetitionIssue::StructuralMonotony));
+    }
+
+    #[test]
+    fn custom_stopwords_change_significance() {
+        let mut config = ContextConfig::default();
+        config.stopwords.insert("chandelier".to_string());
+        config.stopwords.remove("every");
+        let mut ctx = NarrativeContext::with_config(config);
+
+        for _ in 0..3 {
+            ctx.record("Every chandelier glittered.");
+        }
+        assert!(!ctx.word_counts.contains_key("chandelier"));
+        assert_eq!(ctx.word_counts.get("every"), Some(&3));
+
+        let issues = ctx.check_repetition("Every chandelier dimmed.");
+        assert!(issues.contains(&RepetitionIssue::OverusedWord {
+            word: "every".to_string(),
+            count: 4,
+        }));
+        assert!(!issues.iter().any(
+            |i| matches!(i, RepetitionIssue::OverusedWord { word, .. } if word == "chandelier")
+        ));
+    }
+
+    #[test]
+    fn repeat_mention_becomes_pronoun() {
+        let margaret = [("Margaret", &Pronouns::SheHer)];
+        let mut ctx = NarrativeContext::default();
+        assert_eq!(
+            ctx.substitute_pronouns(
+                "Margaret stood. Margaret's glass fell. Margaret left.",
+                &margaret
+            ),
+            "Margaret stood. Margaret's glass fell. She left."
+        );
+
+        // A mention in a recent passage counts too
+        ctx.record("Margaret set down her glass.");
+        assert_eq!(
+            ctx.substitute_pronouns("Margaret left the room.", &margaret),
+            "She left the room."
+        );
+    }
+
+    #[test]
+    fn same_pronoun_entities_keep_names() {
+        let mut ctx = NarrativeContext::default();
+        ctx.record("Margaret set down her glass.");
+        let text = "Margaret stood. Eleanor watched. Margaret left.";
+        let entities = [
+            ("Margaret", &Pronouns::SheHer),
+            ("Eleanor", &Pronouns::SheHer),
+        ];
+        assert_eq!(ctx.substitute_pronouns(text, &entities), text);
+
+        // Different pronouns are unambiguous
+        let entities = [
+            ("Margaret", &Pronouns::SheHer),
+            ("Eleanor", &Pronouns::HeHim),
+        ];
+        assert_eq!(
+            ctx.substitute_pronouns(text, &entities),
+            "She stood. Eleanor watched. She left."
+        );
+    }
+
+    #[test]
+    fn clear_forgets_history_but_keeps_config() {
+        let mut ctx = NarrativeContext::with_config(ContextConfig {
+            window_size: 3,
+            overuse_threshold: 2,
+            ..ContextConfig::default()
+        });
+        ctx.record("The evening was quiet.");
+        ctx.record_alternative("greeting", 1);
+        ctx.clear();
+
+        assert!(ctx.check_repetition("The evening was loud.").is_empty());
+        assert_eq!(ctx.last_alternative("greeting"), None);
+        assert_eq!(ctx.window_size, 3);
+
+        ctx.record("A lantern swung.");
+        assert!(!ctx.check_repetition("One lantern fell.").is_empty());
+    }
+
+    #[test]
+    fn stats_reflect_recorded_passages() {
+        let mut ctx = NarrativeContext::default();
+        assert_eq!(ctx.stats().tracked_passages, 0);
+        assert!(ctx.stats().top_words.is_empty());
+
+        ctx.record("The silence was deafening in the silence.");
+        ctx.record("The silence held.");
+        ctx.record("A candle guttered.");
+        let stats = ctx.stats();
+        assert_eq!(stats.tracked_passages, 3);
+        assert_eq!(stats.unique_openings, 3);
+        assert_eq!(stats.top_words[0], ("silence".to_string(), 3));
+        assert_eq!(stats.top_words[1], ("candle".to_string(), 1));
+
+        for i in 0..12 {
+            ctx.record(&format!("Word{i:02} appeared."));
+        }
+        assert_eq!(ctx.stats().top_words.len(), 10);
+    }
+
     #[test]
     fn context_default() {
         let ctx = NarrativeContext::default();
@@ -241,6 +586,20 @@ mod tests {
             .any(|i| matches!(i, RepetitionIssue::StructuralMonotony)));
     }
 
+    #[test]
+    fn last_alternative_tracks_most_recent() {
+        let mut ctx = NarrativeContext::new(2);
+        assert_eq!(ctx.last_alternative("greeting"), None);
+        ctx.record_alternative("greeting", 0);
+        ctx.record_alternative("farewell", 1);
+        ctx.record_alternative("greeting", 2);
+        assert_eq!(ctx.last_alternative("greeting"), Some(2));
+        // Window of 2 has evicted nothing for farewell yet
+        assert_eq!(ctx.last_alternative("farewell"), Some(1));
+        ctx.record_alternative("greeting", 1);
+        assert_eq!(ctx.last_alternative("farewell"), None);
+    }
+
     #[test]
     fn extract_opening_works() {
         assert_eq!(extract_opening("The evening was quiet."), "the evening was");
@@ -250,7 +609,8 @@ mod tests {
 
     #[test]
     fn significant_words_filter() {
-        let words = extract_significant_words("The quick brown silence filled the empty room.");
+        let words =
+            extract_significant_words_default("The quick brown silence filled the empty room.");
         assert!(words.contains(&"quick".to_string()));
         assert!(words.contains(&"brown".to_string()));
         assert!(words.contains(&"silence".to_string()));
@@ -259,4 +619,31 @@ mod tests {
         assert!(!words.contains(&"the".to_string()));
         assert!(!words.contains(&"room".to_string())); // only 4 chars
     }
+
+    #[test]
+    fn unicode_opening_lowercased() {
+        assert_eq!(
+            extract_opening("Élodie ÉCOUTA attentivement."),
+            "élodie écouta attentivement."
+        );
+        assert_eq!(extract_opening("Ώρα ΓΙΑ δράση"), "ώρα για δράση");
+    }
+
+    #[test]
+    fn significant_words_count_characters_not_bytes() {
+        // "café" and "éclat"-style words: byte length exceeds char count
+        let words = extract_significant_words_default("Le café était prêt, déjà froid.");
+        assert!(!words.contains(&"café".to_string())); // 4 chars, 5 bytes
+        assert!(!words.contains(&"prêt".to_string())); // 4 chars, 5 bytes
+        assert!(words.contains(&"était".to_string())); // 5 chars
+        assert!(words.contains(&"froid".to_string()));
+
+        // Non-Latin script: each Cyrillic letter is 2 bytes
+        let words = extract_significant_words_default("Тихо было в доме.");
+        assert!(!words.contains(&"тихо".to_string())); // 4 chars, 8 bytes
+        assert!(!words.contains(&"было".to_string()));
+        assert!(words.is_empty());
+        let words = extract_significant_words_default("Тишина.");
+        assert_eq!(words, vec!["тишина".to_string()]);
+    }
 }
@@ -2,14 +2,16 @@
 use rand::distributions::WeightedIndex;
 use rand::prelude::Distribution;
 use rand::rngs::StdRng;
+use rand::Rng;
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 
-use crate::core::markov::MarkovModel;
+use crate::core::markov::{BlendPart, MarkovBlender, MarkovError, MarkovModel, TokenBias};
 use crate::schema::entity::{Entity, Value};
+use crate::schema::narrative_fn::NarrativeFunction;
 
 const MAX_EXPANSION_DEPTH: u32 = 20;
 
@@ -21,29 +23,165 @@ pub enum GrammarError {
     Io(#[from] std::io::Error),
     #[error("RON deserialization error: {0}")]
     Ron(#[from] ron::error::SpannedError),
+    #[error("RON serialization error: {0}")]
+    RonSerialize(#[from] ron::Error),
+    #[error("JSON deserialization error: {0}")]
+    Json(#[from] serde_json::Error),
     #[error("rule not found: {0}")]
     RuleNotFound(String),
     #[error("max expansion depth ({0}) exceeded")]
     MaxDepthExceeded(u32),
+    #[error("recursive rule cycle: {}", .0.join(" -> "))]
+    RecursiveCycle(Vec<String>),
     #[error("no matching alternatives for rule '{0}'")]
     NoAlternatives(String),
     #[error("entity binding not found for role: {0}")]
     EntityBindingNotFound(String),
     #[error("entity field not found: {0}")]
     EntityFieldNotFound(String),
+    #[error("entity field '{0}' is a map; name a subkey, e.g. {{entity.{0}.key}}")]
+    EntityFieldIsMap(String),
+    #[error("entity field '{0}' is not an integer")]
+    EntityFieldNotInteger(String),
     #[error("markov generation error: {0}")]
     MarkovError(String),
 }
 
+/// What a `{markov:corpus:tag}` segment expands to when no model is loaded
+/// for it (and the voice binds none).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkovMissingPolicy {
+    /// Emit `[markov:corpus:tag]`, keeping the gap visible in output.
+    #[default]
+    Placeholder,
+    /// Emit nothing, dropping the spacing before the segment.
+    Empty,
+    /// Fail with `GrammarError::MarkovError`.
+    Error,
+}
+
 /// Accumulated state during grammar expansion.
 pub struct SelectionContext<'a> {
     pub tags: FxHashSet<String>,
     pub entity_bindings: HashMap<String, &'a Entity>,
     pub depth: u32,
+    /// Rules currently being expanded, outermost first.
+    pub expansion_stack: Vec<String>,
+    /// When `Some`, every rule expansion is recorded here in the order rules
+    /// are entered. See `GrammarSet::expand_traced`.
+    pub trace: Option<Vec<TraceEntry>>,
     /// Optional voice grammar weight overrides (rule_name → multiplier).
     pub voice_weights: Option<&'a HashMap<String, f32>>,
     /// Loaded Markov models keyed by corpus_id.
     pub markov_models: HashMap<String, &'a MarkovModel>,
+    /// Combined event intensity (0.0–1.0) used by `intensity_scale` alternatives.
+    pub intensity: f32,
+    /// Temporary properties layered over the subject's own properties for
+    /// `{entity.field}` lookups. Overlay entries take precedence.
+    pub subject_overlay: HashMap<String, Value>,
+    /// Entry-rule alternatives to avoid (rule name → alternative index).
+    /// Only consulted for the top-level rule of an expansion.
+    pub avoid_alternatives: HashMap<String, usize>,
+    /// Alternatives chosen for top-level rules during expansion, in order.
+    pub entry_selections: Vec<(String, usize)>,
+    /// Vocabulary bias applied to `{markov:...}` fills, typically from the
+    /// resolved voice.
+    pub markov_bias: Option<TokenBias<'a>>,
+    /// The voice's loaded Markov bindings. When non-empty, `{markov:...}`
+    /// fills draw from these (blended if several) instead of the named corpus.
+    pub markov_bindings: Vec<BoundCorpus<'a>>,
+    /// Use a name instead of a possessive or object pronoun when the last
+    /// entity mentioned with the same pronouns was someone else.
+    pub disambiguate_pronouns: bool,
+    /// Entities referred to so far, in order, tracked while
+    /// `disambiguate_pronouns` is set.
+    pub mentions: Vec<&'a Entity>,
+    /// Never pick the alternative a rule chose the last time it was
+    /// expanded with this context (unless it is the only one available).
+    pub avoid_immediate_repeat: bool,
+    /// Most recent alternative chosen per rule, tracked while
+    /// `avoid_immediate_repeat` is set.
+    pub last_alternatives: HashMap<String, usize>,
+    /// When `Some`, the text of pure alternatives is memoized here and
+    /// reused on later selections of the same alternative.
+    pub expansion_cache: Option<ExpansionCache>,
+    /// How `{markov:...}` segments without a loaded model expand.
+    pub markov_missing: MarkovMissingPolicy,
+    /// Rules with an alternative that can finish expanding, computed on the
+    /// first rule re-entry and reused for the rest of this context's
+    /// expansions. Reset to `None` before expanding a different grammar.
+    pub terminating_rules: Option<FxHashSet<String>>,
+}
+
+/// Memoized expansions of pure alternatives, keyed by rule name and
+/// alternative index.
+///
+/// An alternative is pure when it contains only literals, tag conditionals
+/// and filters or articles over those — nothing that reads entities, draws
+/// from the RNG or expands another rule. Its text then depends only on the
+/// context tags, so the cache empties itself whenever the tag count changes.
+/// Call `clear` after replacing tags without changing their count.
+#[derive(Debug, Clone, Default)]
+pub struct ExpansionCache {
+    tag_count: usize,
+    entries: HashMap<String, HashMap<usize, String>>,
+}
+
+impl ExpansionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop every memoized expansion.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of memoized alternatives.
+    pub fn len(&self) -> usize {
+        self.entries.values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&mut self, tag_count: usize, rule_name: &str, index: usize) -> Option<&String> {
+        if self.tag_count != tag_count {
+            self.entries.clear();
+            self.tag_count = tag_count;
+        }
+        self.entries.get(rule_name)?.get(&index)
+    }
+
+    fn insert(&mut self, rule_name: &str, index: usize, text: String) {
+        self.entries
+            .entry(rule_name.to_string())
+            .or_default()
+            .insert(index, text);
+    }
+}
+
+/// One rule expansion recorded by `GrammarSet::expand_traced`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEntry {
+    pub rule_name: String,
+    /// Index of the alternative selected within the rule.
+    pub alt_index: usize,
+    /// Nesting depth; the top-level rule is 0.
+    pub depth: u32,
+    /// The text this rule expanded to.
+    pub output: String,
+}
+
+/// A voice's binding to a loaded Markov model.
+#[derive(Debug, Clone)]
+pub struct BoundCorpus<'a> {
+    pub model: &'a MarkovModel,
+    /// Blend weight relative to the voice's other bindings.
+    pub weight: f32,
+    /// Tags to fall back on when the model lacks the template's tag.
+    pub tags: &'a [String],
 }
 
 impl<'a> Default for SelectionContext<'a> {
@@ -58,11 +196,32 @@ impl<'a> SelectionContext<'a> {
             tags: FxHashSet::default(),
             entity_bindings: HashMap::new(),
             depth: 0,
+            expansion_stack: Vec::new(),
+            trace: None,
             voice_weights: None,
             markov_models: HashMap::new(),
+            intensity: 0.0,
+            subject_overlay: HashMap::new(),
+            avoid_alternatives: HashMap::new(),
+            entry_selections: Vec::new(),
+            markov_bias: None,
+            markov_bindings: Vec::new(),
+            disambiguate_pronouns: false,
+            mentions: Vec::new(),
+            avoid_immediate_repeat: false,
+            last_alternatives: HashMap::new(),
+            expansion_cache: None,
+            markov_missing: MarkovMissingPolicy::default(),
+            terminating_rules: None,
         }
     }
 
+    /// Enable memoization of pure alternatives. See `ExpansionCache`.
+    pub fn with_expansion_cache(mut self) -> Self {
+        self.expansion_cache = Some(ExpansionCache::new());
+        self
+    }
+
     pub fn with_tags(mut self, tags: impl IntoIterator<Item = String>) -> Self {
         self.tags.extend(tags);
         self
@@ -77,6 +236,16 @@ impl<'a> SelectionContext<'a> {
         self.markov_models.insert(corpus_id.to_string(), model);
         self
     }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_subject_property(mut self, key: &str, value: Value) -> Self {
+        self.subject_overlay.insert(key.to_string(), value);
+        self
+    }
 }
 
 /// A segment of a parsed template.
@@ -88,10 +257,74 @@ pub enum TemplateSegment {
     RuleRef(String),
     /// Reference to a Markov generator: `{markov:corpus:tag}`.
     MarkovRef { corpus: String, tag: String },
-    /// Entity field interpolation: `{entity.field}`.
-    EntityField { field: String },
-    /// Pronoun-aware entity reference: `{subject}`, `{object}`, `{possessive}`.
+    /// Entity field interpolation: `{entity.field}` (subject) or
+    /// `{role.field}` for a specific role binding, e.g. `{object.name}`.
+    EntityField { role: Option<String>, field: String },
+    /// Pronoun-aware entity reference: `{subject}`, `{object}`, `{possessive}`, `{reflexive}`.
+    /// Suffixed role forms such as `{witness_reflexive}` parse as `RuleRef`
+    /// and resolve to pronouns at expand time.
     PronounRef { role: String },
+    /// Inline alternation: `{a|b|{rule}}`. One branch is chosen uniformly.
+    InlineChoice(Vec<Template>),
+    /// Indefinite article agreement: `{a:object.name}` expands the inner
+    /// segment, then prefixes "a" or "an" to match its first word.
+    Article(Box<TemplateSegment>),
+    /// A segment with a post-expansion filter: `{rule|>cap}`.
+    Filtered(Box<TemplateSegment>, SegmentFilter),
+    /// Tag-dependent text: `{?mood:tense: The air tightened.|Nothing stirred.}`
+    /// expands `then` if `tag` is in the context, else `otherwise` (or nothing).
+    Conditional {
+        tag: String,
+        then: Template,
+        otherwise: Option<Template>,
+    },
+    /// Count-aware noun: `{plural:entity.count:raptor:raptors}` reads an
+    /// integer field and emits `singular` for exactly 1, else `plural`.
+    Plural {
+        role: Option<String>,
+        field: String,
+        singular: String,
+        plural: String,
+    },
+}
+
+/// A transform applied to a segment's expanded text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SegmentFilter {
+    /// `cap`: uppercase the first alphabetic character.
+    Cap,
+}
+
+impl SegmentFilter {
+    /// Look up a filter by its template name.
+    pub fn from_name(name: &str) -> Option<SegmentFilter> {
+        match name {
+            "cap" => Some(Self::Cap),
+            _ => None,
+        }
+    }
+
+    /// The filter's template name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cap => "cap",
+        }
+    }
+
+    fn apply(&self, text: &str) -> String {
+        match self {
+            Self::Cap => match text.char_indices().find(|(_, c)| c.is_alphabetic()) {
+                Some((i, c)) => {
+                    let mut out = String::with_capacity(text.len());
+                    out.push_str(&text[..i]);
+                    out.extend(c.to_uppercase());
+                    out.push_str(&text[i + c.len_utf8()..]);
+                    out
+                }
+                None => text.to_string(),
+            },
+        }
+    }
 }
 
 /// A parsed template — a sequence of segments.
@@ -107,7 +340,13 @@ impl Template {
     /// - `{rule_name}` → `RuleRef`
     /// - `{markov:corpus:tag}` → `MarkovRef`
     /// - `{entity.field}` → `EntityField`
-    /// - `{subject}` / `{object}` / `{possessive}` → `PronounRef`
+    /// - `{subject}` / `{object}` / `{possessive}` / `{reflexive}` → `PronounRef`
+    /// - `{role_possessive}` / `{role_reflexive}` / `{role_object}` → `RuleRef`,
+    ///   narrated as that role's pronoun unless a rule has the same name
+    /// - `{a|b|{rule}}` → `InlineChoice` (branches may nest braces)
+    /// - `{a:segment}` → `Article` wrapping any of the above but a choice
+    /// - `{segment|>cap}` → `Filtered`, applying a named filter
+    /// - `{?tag:then|otherwise}` → `Conditional` (`||` for a literal `|`)
     /// - `{{` → literal `{`
     /// - Everything else → `Literal`
     pub fn parse(input: &str) -> Result<Template, GrammarError> {
@@ -132,15 +371,13 @@ impl Template {
                     literal_buf.clear();
                 }
 
-                // Find the closing brace
+                // Find the matching closing brace
                 let start = i + 1;
                 let mut depth = 1;
                 let mut end = start;
                 while end < len {
                     if chars[end] == '{' {
-                        return Err(GrammarError::TemplateParse(
-                            "nested braces are not allowed".to_string(),
-                        ));
+                        depth += 1;
                     }
                     if chars[end] == '}' {
                         depth -= 1;
@@ -160,7 +397,36 @@ impl Template {
                     return Err(GrammarError::TemplateParse("empty braces".to_string()));
                 }
 
-                segments.push(Self::parse_segment(&content)?);
+                if let Some(rest) = content.strip_prefix('?') {
+                    segments.push(parse_conditional(rest)?);
+                } else if let Some((head, filter)) = split_filter(&content)? {
+                    segments.push(TemplateSegment::Filtered(
+                        Box::new(Self::parse_segment(head)?),
+                        filter,
+                    ));
+                } else if let Some(branches) = split_inline_choice(&content) {
+                    let templates = branches
+                        .iter()
+                        .map(|branch| {
+                            if branch.is_empty() {
+                                Err(GrammarError::TemplateParse(format!(
+                                    "empty branch in inline choice '{{{}}}'",
+                                    content
+                                )))
+                            } else {
+                                Template::parse(branch)
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    segments.push(TemplateSegment::InlineChoice(templates));
+                } else if content.contains('{') {
+                    // Nesting is only allowed inside inline choice branches
+                    return Err(GrammarError::TemplateParse(
+                        "nested braces are not allowed".to_string(),
+                    ));
+                } else {
+                    segments.push(Self::parse_segment(&content)?);
+                }
                 i = end + 1;
             } else if chars[i] == '}' {
                 // Escaped closing brace
@@ -185,10 +451,121 @@ impl Template {
         Ok(Template { segments })
     }
 
+    /// Reconstruct template source text that parses back to these segments.
+    ///
+    /// Literal braces are re-escaped as `{{` / `}}`, so
+    /// `Template::parse(&t.to_source())` yields `t` again.
+    pub fn to_source(&self) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                TemplateSegment::Literal(text) => {
+                    out.push_str(&text.replace('{', "{{").replace('}', "}}"));
+                }
+                TemplateSegment::RuleRef(name) => {
+                    out.push_str(&format!("{{{}}}", name));
+                }
+                TemplateSegment::MarkovRef { corpus, tag } => {
+                    out.push_str(&format!("{{markov:{}:{}}}", corpus, tag));
+                }
+                TemplateSegment::EntityField { role, field } => {
+                    let role = role.as_deref().unwrap_or("entity");
+                    out.push_str(&format!("{{{}.{}}}", role, field));
+                }
+                TemplateSegment::PronounRef { role } => {
+                    out.push_str(&format!("{{{}}}", role));
+                }
+                TemplateSegment::InlineChoice(branches) => {
+                    let branches: Vec<String> = branches.iter().map(|b| b.to_source()).collect();
+                    out.push_str(&format!("{{{}}}", branches.join("|")));
+                }
+                TemplateSegment::Article(inner) => {
+                    let inner = Template {
+                        segments: vec![(**inner).clone()],
+                    }
+                    .to_source();
+                    out.push_str(&format!("{{a:{}", &inner[1..]));
+                }
+                TemplateSegment::Conditional {
+                    tag,
+                    then,
+                    otherwise,
+                } => {
+                    out.push_str(&format!("{{?{}:{}", tag, conditional_branch_source(then)));
+                    if let Some(otherwise) = otherwise {
+                        out.push('|');
+                        out.push_str(&conditional_branch_source(otherwise));
+                    }
+                    out.push('}');
+                }
+                TemplateSegment::Plural {
+                    role,
+                    field,
+                    singular,
+                    plural,
+                } => {
+                    let role = role.as_deref().unwrap_or("entity");
+                    out.push_str(&format!(
+                        "{{plural:{}.{}:{}:{}}}",
+                        role, field, singular, plural
+                    ));
+                }
+                TemplateSegment::Filtered(inner, filter) => {
+                    let inner = Template {
+                        segments: vec![(**inner).clone()],
+                    }
+                    .to_source();
+                    out.push_str(&format!(
+                        "{}|>{}}}",
+                        &inner[..inner.len() - 1],
+                        filter.name()
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// Names of all rules referenced by this template, including inside
+    /// inline choice branches.
+    pub fn rule_refs(&self) -> Vec<&str> {
+        let mut refs = Vec::new();
+        for segment in &self.segments {
+            collect_rule_refs(segment, &mut refs);
+        }
+        refs
+    }
+
+    /// Heuristic check for a literal brace that was meant to be escaped.
+    ///
+    /// Returns a hint when `source` fails to parse on a brace, or parses
+    /// to a rule reference whose name could never be a rule (spaces or
+    /// punctuation), e.g. `"Use { here"` or `"a {curly} remark, {like this}"`.
+    pub fn lint_braces(source: &str) -> Option<String> {
+        match Template::parse(source) {
+            Err(GrammarError::TemplateParse(msg)) if msg.contains("brace") => Some(format!(
+                "{} in \"{}\" (write {{{{ or }}}} for a literal brace)",
+                msg, source
+            )),
+            Err(_) => None,
+            Ok(template) => template.segments.iter().find_map(|segment| match segment {
+                TemplateSegment::RuleRef(name)
+                    if !name.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+                {
+                    Some(format!(
+                        "'{{{}}}' in \"{}\" is not a valid rule name (write {{{{ or }}}} for a literal brace)",
+                        name, source
+                    ))
+                }
+                _ => None,
+            }),
+        }
+    }
+
     fn parse_segment(content: &str) -> Result<TemplateSegment, GrammarError> {
         // Check for pronoun refs
         match content {
-            "subject" | "object" | "possessive" => {
+            "subject" | "object" | "possessive" | "reflexive" => {
                 return Ok(TemplateSegment::PronounRef {
                     role: content.to_string(),
                 });
@@ -196,6 +573,41 @@ impl Template {
             _ => {}
         }
 
+        // Check for article agreement: a:<segment>
+        if let Some(rest) = content.strip_prefix("a:") {
+            if rest.is_empty() {
+                return Err(GrammarError::TemplateParse(
+                    "empty article target in '{a:}'".to_string(),
+                ));
+            }
+            return Ok(TemplateSegment::Article(Box::new(Self::parse_segment(
+                rest,
+            )?)));
+        }
+
+        // Check for plural noun: plural:role.field:singular:plural
+        if let Some(rest) = content.strip_prefix("plural:") {
+            let parts: Vec<&str> = rest.split(':').collect();
+            if let [count, singular, plural] = parts[..] {
+                if !singular.is_empty() && !plural.is_empty() {
+                    if let Ok(TemplateSegment::EntityField { role, field }) =
+                        Self::parse_segment(count)
+                    {
+                        return Ok(TemplateSegment::Plural {
+                            role,
+                            field,
+                            singular: singular.to_string(),
+                            plural: plural.to_string(),
+                        });
+                    }
+                }
+            }
+            return Err(GrammarError::TemplateParse(format!(
+                "invalid plural '{{{}}}': expected plural:entity.field:singular:plural",
+                content
+            )));
+        }
+
         // Check for markov ref: markov:corpus:tag
         if let Some(rest) = content.strip_prefix("markov:") {
             let parts: Vec<&str> = rest.splitn(2, ':').collect();
@@ -218,25 +630,203 @@ impl Template {
                     "empty entity field name".to_string(),
                 ));
             }
+            check_format_spec(content, field)?;
             return Ok(TemplateSegment::EntityField {
+                role: None,
                 field: field.to_string(),
             });
         }
 
+        // Check for role-qualified field: object.name
+        if let Some((role, field)) = content.split_once('.') {
+            if !role.is_empty() && !field.is_empty() && is_identifier(role) {
+                check_format_spec(content, field)?;
+                return Ok(TemplateSegment::EntityField {
+                    role: Some(role.to_string()),
+                    field: field.to_string(),
+                });
+            }
+        }
+
         // Default: rule reference
         Ok(TemplateSegment::RuleRef(content.to_string()))
     }
 }
 
+fn collect_rule_refs<'t>(segment: &'t TemplateSegment, refs: &mut Vec<&'t str>) {
+    match segment {
+        TemplateSegment::RuleRef(name) => refs.push(name.as_str()),
+        TemplateSegment::Article(inner) | TemplateSegment::Filtered(inner, _) => {
+            collect_rule_refs(inner, refs)
+        }
+        TemplateSegment::InlineChoice(branches) => {
+            for branch in branches {
+                refs.extend(branch.rule_refs());
+            }
+        }
+        TemplateSegment::Conditional {
+            then, otherwise, ..
+        } => {
+            refs.extend(then.rule_refs());
+            if let Some(otherwise) = otherwise {
+                refs.extend(otherwise.rule_refs());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parse `tag:then|otherwise` (the content of `{?...}`).
+///
+/// The tag runs to the last `:` before the first space or brace, so
+/// `mood:tense: The air...` has tag `mood:tense`; one space after the
+/// colon is dropped as a separator. The first top-level `|` separates the branches and
+/// `||` stands for a literal `|`. Braces inside a branch nest as usual.
+fn parse_conditional(content: &str) -> Result<TemplateSegment, GrammarError> {
+    let head_len = content
+        .find(|c: char| c.is_whitespace() || c == '{')
+        .unwrap_or(content.len());
+    let Some(colon) = content[..head_len].rfind(':') else {
+        return Err(GrammarError::TemplateParse(format!(
+            "conditional '{{?{}}}' needs a tag followed by ':'",
+            content
+        )));
+    };
+    let tag = &content[..colon];
+    if tag.is_empty() {
+        return Err(GrammarError::TemplateParse(format!(
+            "empty tag in conditional '{{?{}}}'",
+            content
+        )));
+    }
+
+    let body = &content[colon + 1..];
+    let body = body.strip_prefix(' ').unwrap_or(body);
+    let mut branches = vec![String::new()];
+    let mut depth = 0;
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '|' if depth == 0 => {
+                if chars.peek() == Some(&'|') {
+                    chars.next();
+                } else {
+                    branches.push(String::new());
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        let Some(branch) = branches.last_mut() else {
+            return Err(GrammarError::TemplateParse(format!(
+                "conditional '{{?{}}}' has no branch",
+                content
+            )));
+        };
+        branch.push(c);
+    }
+    if branches.len() > 2 {
+        return Err(GrammarError::TemplateParse(format!(
+            "conditional '{{?{}}}' has more than one '|' (use '||' for a literal pipe)",
+            content
+        )));
+    }
+
+    let mut branches = branches.into_iter();
+    let then = Template::parse(&branches.next().unwrap_or_default())?;
+    let otherwise = branches.next().map(|b| Template::parse(&b)).transpose()?;
+    Ok(TemplateSegment::Conditional {
+        tag: tag.to_string(),
+        then,
+        otherwise,
+    })
+}
+
+/// Source for one conditional branch, with literal pipes doubled.
+fn conditional_branch_source(branch: &Template) -> String {
+    branch
+        .segments
+        .iter()
+        .map(|segment| {
+            let source = Template {
+                segments: vec![segment.clone()],
+            }
+            .to_source();
+            match segment {
+                TemplateSegment::Literal(_) => source.replace('|', "||"),
+                _ => source,
+            }
+        })
+        .collect()
+}
+
+/// Split `{segment|>filter}` content into the segment and its filter.
+///
+/// `|>` never appears in an inline choice, so `{nodded|cap}` stays a
+/// choice and every `|>` must name a known filter on a single segment.
+fn split_filter(content: &str) -> Result<Option<(&str, SegmentFilter)>, GrammarError> {
+    if content.contains('{') {
+        return Ok(None);
+    }
+    let Some((head, name)) = content.rsplit_once("|>") else {
+        return Ok(None);
+    };
+    if head.is_empty() || head.contains('|') {
+        return Err(GrammarError::TemplateParse(format!(
+            "filter '{}' needs a single segment before '|>' in '{{{}}}'",
+            name, content
+        )));
+    }
+    match SegmentFilter::from_name(name) {
+        Some(filter) => Ok(Some((head, filter))),
+        None => Err(GrammarError::TemplateParse(format!(
+            "unknown filter '{}' in '{{{}}}'",
+            name, content
+        ))),
+    }
+}
+
+/// Split brace content on top-level `|`. Returns `None` when there is
+/// no top-level pipe, i.e. the content is not an inline choice.
+fn split_inline_choice(content: &str) -> Option<Vec<String>> {
+    let mut branches = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for c in content.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '|' if depth == 0 => {
+                branches.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if branches.is_empty() {
+        return None;
+    }
+    branches.push(current);
+    Some(branches)
+}
+
 /// A weighted text alternative within a grammar rule.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Alternative {
     pub weight: u32,
     pub template: Template,
+    /// Scales the weight by `1.0 + intensity_scale * ctx.intensity`.
+    /// Positive values favour this alternative in dramatic contexts,
+    /// negative values in muted ones.
+    #[serde(default)]
+    pub intensity_scale: f32,
 }
 
 /// A single grammar rule with tag preconditions and weighted alternatives.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GrammarRule {
     pub name: String,
     pub requires: Vec<String>,
@@ -251,12 +841,15 @@ pub struct GrammarSet {
 }
 
 // RON deserialization helpers — the RON format uses a different shape
-// than the internal types, so we need intermediate structs.
+// than the internal types, so we need intermediate structs. JSON grammars
+// share the same shape.
 
 #[derive(Debug, Deserialize)]
 struct RonAlternative {
     weight: u32,
     text: String,
+    #[serde(default)]
+    intensity_scale: f32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -278,6 +871,24 @@ impl GrammarSet {
     /// Parse a grammar set from a RON string.
     pub fn parse_ron(input: &str) -> Result<GrammarSet, GrammarError> {
         let raw: HashMap<String, RonRule> = ron::from_str(input)?;
+        Self::from_raw(raw)
+    }
+
+    /// Load a grammar set from a JSON file.
+    pub fn load_from_json(path: &Path) -> Result<GrammarSet, GrammarError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse_json(&contents)
+    }
+
+    /// Parse a grammar set from a JSON string: an object of rule name →
+    /// `{ "requires": [...], "excludes": [...], "alternatives": [{ "weight", "text" }] }`,
+    /// the same shape as the RON format.
+    pub fn parse_json(input: &str) -> Result<GrammarSet, GrammarError> {
+        let raw: HashMap<String, RonRule> = serde_json::from_str(input)?;
+        Self::from_raw(raw)
+    }
+
+    fn from_raw(raw: HashMap<String, RonRule>) -> Result<GrammarSet, GrammarError> {
         let mut rules = HashMap::new();
 
         for (name, ron_rule) in raw {
@@ -287,6 +898,7 @@ impl GrammarSet {
                 alternatives.push(Alternative {
                     weight: alt.weight,
                     template,
+                    intensity_scale: alt.intensity_scale,
                 });
             }
             rules.insert(
@@ -303,6 +915,62 @@ impl GrammarSet {
         Ok(GrammarSet { rules })
     }
 
+    /// Run `Template::lint_braces` over every alternative in a RON grammar
+    /// without parsing the templates, so files that fail to load can
+    /// still be diagnosed. Returns `(rule_name, hint)` pairs sorted by rule.
+    pub fn lint_ron_braces(input: &str) -> Result<Vec<(String, String)>, GrammarError> {
+        let raw: HashMap<String, RonRule> = ron::from_str(input)?;
+        let mut hints: Vec<(String, String)> = raw
+            .into_iter()
+            .flat_map(|(name, rule)| {
+                rule.alternatives.into_iter().filter_map(move |alt| {
+                    Template::lint_braces(&alt.text).map(|hint| (name.clone(), hint))
+                })
+            })
+            .collect();
+        hints.sort();
+        Ok(hints)
+    }
+
+    /// Serialize to the RON `Rule(...)` format accepted by `parse_ron`,
+    /// with rules sorted by name. Template text is rebuilt from segments.
+    pub fn to_ron_pretty(&self) -> Result<String, GrammarError> {
+        let mut names: Vec<&String> = self.rules.keys().collect();
+        names.sort();
+
+        let mut out = String::from("{\n");
+        for name in names {
+            let rule = &self.rules[name];
+            out.push_str(&format!("    {}: Rule(\n", ron::to_string(name)?));
+            out.push_str(&format!(
+                "        requires: {},\n",
+                ron_string_list(&rule.requires)?
+            ));
+            out.push_str(&format!(
+                "        excludes: {},\n",
+                ron_string_list(&rule.excludes)?
+            ));
+            out.push_str("        alternatives: [\n");
+            for alt in &rule.alternatives {
+                let text = ron::to_string(&alt.template.to_source())?;
+                if alt.intensity_scale != 0.0 {
+                    out.push_str(&format!(
+                        "            (weight: {}, text: {}, intensity_scale: {:?}),\n",
+                        alt.weight, text, alt.intensity_scale
+                    ));
+                } else {
+                    out.push_str(&format!(
+                        "            (weight: {}, text: {}),\n",
+                        alt.weight, text
+                    ));
+                }
+            }
+            out.push_str("        ],\n    ),\n");
+        }
+        out.push_str("}\n");
+        Ok(out)
+    }
+
     /// Merge another grammar set into this one. Rules from `other`
     /// override rules in `self` with the same name.
     pub fn merge(&mut self, other: GrammarSet) {
@@ -311,6 +979,261 @@ impl GrammarSet {
         }
     }
 
+    /// Remove a single rule, returning it if it existed.
+    pub fn remove_rule(&mut self, name: &str) -> Option<GrammarRule> {
+        self.rules.remove(name)
+    }
+
+    /// Remove every rule belonging to a narrative function: `{fn}_opening`,
+    /// `{fn}`, `{fn}_body`, and `{fn}_closing`. Returns the removed rules.
+    ///
+    /// Other rules may still reference the removed ones; check
+    /// `dangling_references` afterwards.
+    pub fn remove_function(&mut self, narrative_fn: &NarrativeFunction) -> Vec<GrammarRule> {
+        let name = narrative_fn.name();
+        [
+            format!("{}_opening", name),
+            name.to_string(),
+            format!("{}_body", name),
+            format!("{}_closing", name),
+        ]
+        .iter()
+        .filter_map(|rule_name| self.rules.remove(rule_name))
+        .collect()
+    }
+
+    /// Whether the entry rule for `narrative_fn` can generate, i.e.
+    /// whether the engine can narrate it at all. As when narrating,
+    /// `{fn}_opening` is used if present and bare `{fn}` otherwise; the
+    /// rule needs at least one alternative.
+    pub fn can_narrate(&self, narrative_fn: &NarrativeFunction) -> bool {
+        self.entry_rule(narrative_fn).is_some()
+    }
+
+    /// Names of the built-in narrative functions the grammar can narrate
+    /// (see `can_narrate`), in declaration order.
+    pub fn supported_functions(&self) -> Vec<&str> {
+        NarrativeFunction::BUILTIN
+            .iter()
+            .filter_map(|narrative_fn| self.entry_rule(narrative_fn))
+            .map(|key| key.strip_suffix("_opening").unwrap_or(key))
+            .collect()
+    }
+
+    /// Name of the entry rule narrating `narrative_fn`, if it has any
+    /// alternatives.
+    fn entry_rule(&self, narrative_fn: &NarrativeFunction) -> Option<&str> {
+        let name = narrative_fn.name();
+        let (key, rule) = self
+            .rules
+            .get_key_value(&format!("{}_opening", name))
+            .or_else(|| self.rules.get_key_value(name))?;
+        (!rule.alternatives.is_empty()).then_some(key.as_str())
+    }
+
+    /// `(rule, missing)` pairs for every reference to a rule that doesn't
+    /// exist, sorted.
+    ///
+    /// `subject`, `object` and the participant `roles` given here, bare or
+    /// with a pronoun suffix (e.g. `{witness}`, `{witness_object}`), name a
+    /// bound entity rather than a rule and are not reported.
+    pub fn dangling_references(&self, roles: &[&str]) -> Vec<(String, String)> {
+        let mut dangling: Vec<(String, String)> = self
+            .rules
+            .iter()
+            .flat_map(|(name, rule)| {
+                rule.alternatives
+                    .iter()
+                    .flat_map(|alt| alt.template.rule_refs())
+                    .filter(|r| !self.rules.contains_key(*r) && !is_known_role_ref(r, roles))
+                    .map(move |r| (name.clone(), r.to_string()))
+            })
+            .collect();
+        dangling.sort();
+        dangling.dedup();
+        dangling
+    }
+
+    /// Reference cycles that expansion can never escape, each as a path
+    /// that starts and ends on the same rule (e.g. `[a, b, c, a]`), sorted.
+    ///
+    /// A rule with at least one alternative that can finish expanding is
+    /// never part of a reported cycle, even if its other alternatives recurse.
+    /// Dangling references are treated as terminating; see
+    /// `dangling_references`.
+    pub fn recursive_cycles(&self) -> Vec<Vec<String>> {
+        let terminating = self.terminating_rules();
+        let mut stuck: Vec<&str> = self
+            .rules
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !terminating.contains(name))
+            .collect();
+        stuck.sort();
+
+        let mut cycles = Vec::new();
+        let mut done = FxHashSet::default();
+        for start in &stuck {
+            let mut stack = Vec::new();
+            self.find_cycles(start, &terminating, &mut stack, &mut done, &mut cycles);
+        }
+        cycles.sort();
+        cycles.dedup();
+        cycles
+    }
+
+    /// Rules with at least one alternative that can finish expanding.
+    fn terminating_rules(&self) -> FxHashSet<&str> {
+        // Fixpoint: a rule terminates if some alternative only references
+        // rules already known to terminate
+        let mut terminating: FxHashSet<&str> = FxHashSet::default();
+        loop {
+            let mut changed = false;
+            for (name, rule) in &self.rules {
+                if !terminating.contains(name.as_str())
+                    && rule
+                        .alternatives
+                        .iter()
+                        .any(|alt| self.segments_terminate(&alt.template.segments, &terminating))
+                {
+                    terminating.insert(name);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        terminating
+    }
+
+    fn segments_terminate(
+        &self,
+        segments: &[TemplateSegment],
+        terminating: &FxHashSet<&str>,
+    ) -> bool {
+        segments.iter().all(|segment| match segment {
+            TemplateSegment::RuleRef(name) => {
+                terminating.contains(name.as_str()) || !self.rules.contains_key(name)
+            }
+            TemplateSegment::InlineChoice(branches) => branches
+                .iter()
+                .any(|branch| self.segments_terminate(&branch.segments, terminating)),
+            TemplateSegment::Article(inner) | TemplateSegment::Filtered(inner, _) => {
+                self.segments_terminate(std::slice::from_ref(inner.as_ref()), terminating)
+            }
+            TemplateSegment::Conditional {
+                then, otherwise, ..
+            } => {
+                // Either branch may be taken depending on tags
+                self.segments_terminate(&then.segments, terminating)
+                    || otherwise
+                        .as_ref()
+                        .is_none_or(|o| self.segments_terminate(&o.segments, terminating))
+            }
+            _ => true,
+        })
+    }
+
+    /// Depth-first walk over non-terminating rules, recording a cycle
+    /// whenever an edge leads back onto the current path.
+    fn find_cycles<'s>(
+        &'s self,
+        name: &'s str,
+        terminating: &FxHashSet<&str>,
+        stack: &mut Vec<&'s str>,
+        done: &mut FxHashSet<&'s str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if let Some(pos) = stack.iter().position(|r| *r == name) {
+            // Rotate so the lexically smallest rule leads, for stable output
+            let cycle = &stack[pos..];
+            let min = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+            let mut path: Vec<String> = cycle[min..]
+                .iter()
+                .chain(&cycle[..min])
+                .map(|r| r.to_string())
+                .collect();
+            path.push(path[0].clone());
+            cycles.push(path);
+            return;
+        }
+        if done.contains(name) {
+            return;
+        }
+        let Some(rule) = self.rules.get(name) else {
+            return;
+        };
+
+        let mut refs: Vec<&str> = rule
+            .alternatives
+            .iter()
+            .flat_map(|alt| alt.template.rule_refs())
+            .filter(|r| self.rules.contains_key(*r) && !terminating.contains(r))
+            .collect();
+        refs.sort();
+        refs.dedup();
+
+        stack.push(name);
+        for next in refs {
+            self.find_cycles(next, terminating, stack, done, cycles);
+        }
+        stack.pop();
+        done.insert(name);
+    }
+
+    /// Rules not reachable by following references from any of `entries`,
+    /// sorted. Entry rules that exist are always reachable.
+    pub fn unreachable_rules(&self, entries: &[&str]) -> Vec<String> {
+        let mut reachable: FxHashSet<&str> = FxHashSet::default();
+        let mut pending: Vec<&str> = entries
+            .iter()
+            .copied()
+            .filter(|e| self.rules.contains_key(*e))
+            .collect();
+        while let Some(name) = pending.pop() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            if let Some(rule) = self.rules.get(name) {
+                for alt in &rule.alternatives {
+                    pending.extend(alt.template.rule_refs());
+                }
+            }
+        }
+
+        let mut unreachable: Vec<String> = self
+            .rules
+            .keys()
+            .filter(|name| !reachable.contains(name.as_str()))
+            .cloned()
+            .collect();
+        unreachable.sort();
+        unreachable
+    }
+
+    /// Count how many rules list each tag in `requires`.
+    pub fn requirement_histogram(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for rule in self.rules.values() {
+            for tag in &rule.requires {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    /// Count how many rules list each tag in `excludes`.
+    pub fn exclusion_histogram(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for rule in self.rules.values() {
+            for tag in &rule.excludes {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+        counts
+    }
+
     /// Find all rules whose `requires` tags are a subset of the context's
     /// active tags, and whose `excludes` tags have no intersection.
     pub fn find_matching_rules<'a, 'b>(
@@ -329,6 +1252,21 @@ impl GrammarSet {
             .collect()
     }
 
+    /// The most specific matching rule: the one with the most `requires`
+    /// tags, ties broken by higher total alternative weight, then by name.
+    /// Lets authors pair a generic fallback with specific overrides.
+    pub fn find_best_rule<'a, 'b>(&'a self, ctx: &SelectionContext<'b>) -> Option<&'a GrammarRule> {
+        let total_weight =
+            |rule: &GrammarRule| -> u32 { rule.alternatives.iter().map(|alt| alt.weight).sum() };
+        self.find_matching_rules(ctx).into_iter().min_by(|a, b| {
+            b.requires
+                .len()
+                .cmp(&a.requires.len())
+                .then_with(|| total_weight(b).cmp(&total_weight(a)))
+                .then_with(|| a.name.cmp(&b.name))
+        })
+    }
+
     /// Expand a named rule into text using the given context and RNG.
     pub fn expand(
         &self,
@@ -340,6 +1278,24 @@ impl GrammarSet {
             return Err(GrammarError::MaxDepthExceeded(MAX_EXPANSION_DEPTH));
         }
 
+        // Re-entering a rule is fine while some rule on the loop has an
+        // alternative that exits (bounded by MAX_EXPANSION_DEPTH); a loop
+        // with no exit can never finish, so fail fast with its path
+        if let Some(start) = ctx.expansion_stack.iter().position(|r| r == rule_name) {
+            let terminating = ctx.terminating_rules.get_or_insert_with(|| {
+                self.terminating_rules()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect()
+            });
+            let path = &ctx.expansion_stack[start..];
+            if !path.iter().any(|r| terminating.contains(r.as_str())) {
+                let mut path = path.to_vec();
+                path.push(rule_name.to_string());
+                return Err(GrammarError::RecursiveCycle(path));
+            }
+        }
+
         let rule = self
             .rules
             .get(rule_name)
@@ -355,65 +1311,393 @@ impl GrammarSet {
         }
 
         // Select alternative by weighted random, with voice weight multipliers
-        let alt = select_alternative(&rule.alternatives, rule_name, ctx.voice_weights, rng)?;
+        let is_entry = ctx.depth == 0;
+        let avoid = if is_entry {
+            ctx.avoid_alternatives.get(rule_name).copied()
+        } else {
+            None
+        };
+        let avoid = avoid.or_else(|| {
+            ctx.avoid_immediate_repeat
+                .then(|| ctx.last_alternatives.get(rule_name).copied())
+                .flatten()
+        });
+        let index = select_alternative(
+            &rule.alternatives,
+            rule_name,
+            ctx.voice_weights,
+            ctx.intensity,
+            avoid,
+            rng,
+        )?;
+        if is_entry {
+            ctx.entry_selections.push((rule_name.to_string(), index));
+        }
+        if ctx.avoid_immediate_repeat {
+            ctx.last_alternatives.insert(rule_name.to_string(), index);
+        }
+        let alt = &rule.alternatives[index];
+
+        // Record entry now so the trace lists parents before children
+        let trace_slot = ctx.trace.as_mut().map(|trace| {
+            trace.push(TraceEntry {
+                rule_name: rule_name.to_string(),
+                alt_index: index,
+                depth: ctx.depth,
+                output: String::new(),
+            });
+            trace.len() - 1
+        });
+
+        // Pure alternatives replay memoized text; selection above still ran,
+        // so the RNG advances exactly as it would without the cache
+        let cacheable =
+            ctx.expansion_cache.is_some() && alt.template.segments.iter().all(is_pure_segment);
+        if cacheable {
+            let tag_count = ctx.tags.len();
+            let cached = ctx
+                .expansion_cache
+                .as_mut()
+                .and_then(|cache| cache.get(tag_count, rule_name, index).cloned());
+            if let Some(text) = cached {
+                if let (Some(slot), Some(trace)) = (trace_slot, ctx.trace.as_mut()) {
+                    trace[slot].output = text.clone();
+                }
+                return Ok(text);
+            }
+        }
 
         // Expand template segments
         ctx.depth += 1;
+        ctx.expansion_stack.push(rule_name.to_string());
+        let output = self.expand_segments(&alt.template.segments, ctx, rng);
+        ctx.expansion_stack.pop();
+        ctx.depth -= 1;
+
+        if let (true, Some(cache), Ok(text)) = (cacheable, ctx.expansion_cache.as_mut(), &output) {
+            cache.insert(rule_name, index, text.clone());
+        }
+
+        if let (Some(slot), Some(trace), Ok(text)) = (trace_slot, ctx.trace.as_mut(), &output) {
+            trace[slot].output = text.clone();
+        }
+        output
+    }
+
+    /// Like `expand`, but also appends a `TraceEntry` to `trace` for every
+    /// rule entered, parents before children.
+    pub fn expand_traced(
+        &self,
+        rule_name: &str,
+        ctx: &mut SelectionContext<'_>,
+        rng: &mut StdRng,
+        trace: &mut Vec<TraceEntry>,
+    ) -> Result<String, GrammarError> {
+        let outer = ctx.trace.replace(Vec::new());
+        let result = self.expand(rule_name, ctx, rng);
+        trace.extend(std::mem::replace(&mut ctx.trace, outer).unwrap_or_default());
+        result
+    }
+
+    fn expand_segments(
+        &self,
+        segments: &[TemplateSegment],
+        ctx: &mut SelectionContext<'_>,
+        rng: &mut StdRng,
+    ) -> Result<String, GrammarError> {
         let mut output = String::new();
 
-        for segment in &alt.template.segments {
+        for (i, segment) in segments.iter().enumerate() {
             match segment {
                 TemplateSegment::Literal(text) => {
                     output.push_str(text);
                 }
                 TemplateSegment::RuleRef(name) => {
-                    let expanded = self.expand(name, ctx, rng)?;
-                    output.push_str(&expanded);
+                    // A participant role with no rule of that name narrates
+                    // as the bound entity's name, or its pronoun when suffixed
+                    if !self.rules.contains_key(name) {
+                        if let Some(&entity) = ctx.entity_bindings.get(name) {
+                            output.push_str(&entity.name);
+                            note_mention(ctx, entity);
+                            continue;
+                        }
+                        if is_role_pronoun(name) {
+                            output.push_str(&resolve_pronoun(ctx, name)?);
+                            continue;
+                        }
+                    }
+                    let expanded = self.expand(name, ctx, rng)?;
+                    output.push_str(&expanded);
                 }
                 TemplateSegment::MarkovRef { corpus, tag } => {
-                    if let Some(model) = ctx.markov_models.get(corpus.as_str()) {
-                        match model.generate(rng, Some(tag), 5, 15) {
-                            Ok(text) => output.push_str(&text),
-                            Err(e) => {
-                                // Fall back to untagged generation
-                                match model.generate(rng, None, 5, 15) {
-                                    Ok(text) => output.push_str(&text),
-                                    Err(_) => {
-                                        return Err(GrammarError::MarkovError(format!(
-                                            "markov generation failed for {}:{}: {}",
-                                            corpus, tag, e
-                                        )));
-                                    }
-                                }
+                    let fill = match fill_markov(ctx, corpus, tag, rng)? {
+                        Some(fill) => fill,
+                        None => match ctx.markov_missing {
+                            MarkovMissingPolicy::Placeholder => {
+                                format!("[markov:{}:{}]", corpus, tag)
+                            }
+                            MarkovMissingPolicy::Empty => String::new(),
+                            MarkovMissingPolicy::Error => {
+                                return Err(GrammarError::MarkovError(format!(
+                                    "no model loaded for {}:{}",
+                                    corpus, tag
+                                )))
                             }
+                        },
+                    };
+                    if fill.trim().is_empty() {
+                        // Degenerate or empty fill — drop it along with the spacing before it
+                        let trimmed = output.trim_end().len();
+                        output.truncate(trimmed);
+                        // "frowned, and {markov}." would otherwise end on "and."
+                        let ends_sentence = matches!(
+                            segments.get(i + 1),
+                            Some(TemplateSegment::Literal(next))
+                                if next.starts_with(['.', '!', '?', ',', ';', ':'])
+                        );
+                        if ends_sentence {
+                            drop_trailing_conjunction(&mut output);
                         }
                     } else {
-                        // No model loaded — emit placeholder
-                        output.push_str(&format!("[markov:{}:{}]", corpus, tag));
+                        output.push_str(&fill);
                     }
                 }
-                TemplateSegment::EntityField { field } => {
-                    output.push_str(&resolve_entity_field(ctx, field)?);
+                TemplateSegment::EntityField { role, field } => {
+                    output.push_str(&resolve_entity_field(ctx, role.as_deref(), field)?);
+                    if field == "name" {
+                        let entity = bound_entity(ctx, role.as_deref())?;
+                        note_mention(ctx, entity);
+                    }
                 }
                 TemplateSegment::PronounRef { role } => {
                     output.push_str(&resolve_pronoun(ctx, role)?);
                 }
+                TemplateSegment::InlineChoice(branches) => {
+                    let branch = &branches[rng.gen_range(0..branches.len())];
+                    output.push_str(&self.expand_segments(&branch.segments, ctx, rng)?);
+                }
+                TemplateSegment::Article(inner) => {
+                    // The article depends on the expanded text, so expand first
+                    let text =
+                        self.expand_segments(std::slice::from_ref(inner.as_ref()), ctx, rng)?;
+                    if !text.trim().is_empty() {
+                        output.push_str(indefinite_article(&text));
+                        output.push(' ');
+                    }
+                    output.push_str(&text);
+                }
+                TemplateSegment::Conditional {
+                    tag,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if ctx.tags.contains(tag) {
+                        Some(then)
+                    } else {
+                        otherwise.as_ref()
+                    };
+                    if let Some(branch) = branch {
+                        output.push_str(&self.expand_segments(&branch.segments, ctx, rng)?);
+                    }
+                }
+                TemplateSegment::Plural {
+                    role,
+                    field,
+                    singular,
+                    plural,
+                } => {
+                    output.push_str(resolve_plural(
+                        ctx,
+                        role.as_deref(),
+                        field,
+                        singular,
+                        plural,
+                    )?);
+                }
+                TemplateSegment::Filtered(inner, filter) => {
+                    let text =
+                        self.expand_segments(std::slice::from_ref(inner.as_ref()), ctx, rng)?;
+                    output.push_str(&filter.apply(&text));
+                }
             }
         }
 
-        ctx.depth -= 1;
         Ok(output)
     }
 }
 
-/// Select a weighted alternative, optionally applying voice weight multipliers.
-fn select_alternative<'a>(
-    alts: &'a [Alternative],
+/// Remove a conjunction ending `output` (and the comma or spacing before
+/// it), left hanging by an empty fill just ahead of punctuation.
+fn drop_trailing_conjunction(output: &mut String) {
+    const CONJUNCTIONS: &[&str] = &["and", "but", "or", "yet", "so"];
+
+    let Some((head, last)) = output.rsplit_once(' ') else {
+        return;
+    };
+    if CONJUNCTIONS.contains(&last) {
+        let kept = head.trim_end().trim_end_matches(',').len();
+        output.truncate(kept);
+    }
+}
+
+/// Generate a `{markov:corpus:tag}` fill. The voice's bindings take
+/// precedence over the named corpus; returns `None` if neither is loaded.
+fn fill_markov(
+    ctx: &SelectionContext<'_>,
+    corpus: &str,
+    tag: &str,
+    rng: &mut StdRng,
+) -> Result<Option<String>, GrammarError> {
+    let bias = ctx.markov_bias.as_ref();
+    let fill = match ctx.markov_bindings.as_slice() {
+        [] => match ctx.markov_models.get(corpus) {
+            Some(model) => generate_with_fallback(model, Some(tag), bias, rng),
+            None => return Ok(None),
+        },
+        [binding] => {
+            generate_with_fallback(binding.model, Some(binding_tag(binding, tag)), bias, rng)
+        }
+        bindings => {
+            let parts: Vec<BlendPart<'_>> = bindings
+                .iter()
+                .map(|b| BlendPart {
+                    model: b.model,
+                    weight: b.weight,
+                    tag: Some(binding_tag(b, tag)),
+                })
+                .collect();
+            match MarkovBlender::generate_parts(&parts, rng, 5, 15, bias) {
+                Ok(text) => Ok(text),
+                Err(e @ MarkovError::NgramMismatch { .. }) => Err(e),
+                // Fall back to untagged generation, as a single binding does
+                Err(e) => {
+                    let untagged: Vec<BlendPart<'_>> = parts
+                        .iter()
+                        .map(|part| BlendPart { tag: None, ..*part })
+                        .collect();
+                    MarkovBlender::generate_parts(&untagged, rng, 5, 15, bias).map_err(|_| e)
+                }
+            }
+        }
+    };
+    fill.map(Some).map_err(|e| {
+        GrammarError::MarkovError(format!(
+            "markov generation failed for {}:{}: {}",
+            corpus, tag, e
+        ))
+    })
+}
+
+/// The tag a bound corpus draws from: the template's tag if its model has
+/// it, else the first of the binding's own tags that it has.
+fn binding_tag<'a>(binding: &BoundCorpus<'a>, tag: &'a str) -> &'a str {
+    if binding.model.tagged_transitions.contains_key(tag) {
+        return tag;
+    }
+    binding
+        .tags
+        .iter()
+        .find(|t| binding.model.tagged_transitions.contains_key(t.as_str()))
+        .map_or(tag, |t| t.as_str())
+}
+
+/// Generate from `tag`, falling back to untagged generation.
+fn generate_with_fallback(
+    model: &MarkovModel,
+    tag: Option<&str>,
+    bias: Option<&TokenBias<'_>>,
+    rng: &mut StdRng,
+) -> Result<String, MarkovError> {
+    match model.generate_biased(rng, tag, 5, 15, bias) {
+        Ok(text) => Ok(text),
+        Err(e) => model.generate_biased(rng, None, 5, 15, bias).map_err(|_| e),
+    }
+}
+
+/// "a" or "an" for the word starting `text`, by sound where a simple rule
+/// can tell: "an hour", "a unicorn", "an FBI agent". Anything unclear
+/// gets "a".
+fn indefinite_article(text: &str) -> &'static str {
+    const SILENT_H: &[&str] = &["hour", "honest", "honor", "honour", "heir"];
+    const CONSONANT_VOWELS: &[&str] = &[
+        "unic", "unif", "unio", "uniq", "unit", "univ", "use", "usu", "uti", "eu",
+    ];
+
+    let word: String = text
+        .trim_start()
+        .chars()
+        .skip_while(|c| !c.is_alphanumeric())
+        .take_while(|c| c.is_alphanumeric())
+        .collect();
+    let Some(first) = word.chars().next() else {
+        return "a";
+    };
+
+    // Acronyms are read letter by letter: "an MRI", "a UFO"
+    if word.len() > 1 && word.chars().all(|c| c.is_ascii_uppercase()) {
+        return if "AEFHILMNORSX".contains(first) {
+            "an"
+        } else {
+            "a"
+        };
+    }
+
+    let lower = word.to_lowercase();
+    if SILENT_H.iter().any(|w| lower.starts_with(w)) {
+        return "an";
+    }
+    if CONSONANT_VOWELS.iter().any(|p| lower.starts_with(p)) || lower == "one" || lower == "once" {
+        return "a";
+    }
+    if "aeiou".contains(first.to_ascii_lowercase()) {
+        "an"
+    } else {
+        "a"
+    }
+}
+
+/// Format a list of strings as a RON array: `["a", "b"]`.
+fn ron_string_list(items: &[String]) -> Result<String, GrammarError> {
+    let quoted = items
+        .iter()
+        .map(ron::to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("[{}]", quoted.join(", ")))
+}
+
+/// Whether a segment's expansion depends only on the context tags: no
+/// entity lookups, rule references or RNG draws.
+fn is_pure_segment(segment: &TemplateSegment) -> bool {
+    match segment {
+        TemplateSegment::Literal(_) => true,
+        TemplateSegment::Article(inner) | TemplateSegment::Filtered(inner, _) => {
+            is_pure_segment(inner)
+        }
+        TemplateSegment::Conditional {
+            then, otherwise, ..
+        } => then
+            .segments
+            .iter()
+            .chain(otherwise.iter().flat_map(|t| t.segments.iter()))
+            .all(is_pure_segment),
+        _ => false,
+    }
+}
+
+/// Select a weighted alternative, optionally applying voice weight multipliers
+/// and per-alternative intensity scaling. Returns the chosen index.
+///
+/// If `avoid` names an alternative, it is excluded as long as another
+/// alternative remains selectable.
+fn select_alternative(
+    alts: &[Alternative],
     rule_name: &str,
     voice_weights: Option<&HashMap<String, f32>>,
+    intensity: f32,
+    avoid: Option<usize>,
     rng: &mut StdRng,
-) -> Result<&'a Alternative, GrammarError> {
-    let weights: Vec<f64> = alts
+) -> Result<usize, GrammarError> {
+    let mut weights: Vec<f64> = alts
         .iter()
         .map(|alt| {
             let base = alt.weight as f64;
@@ -421,61 +1705,327 @@ fn select_alternative<'a>(
                 .and_then(|vw| vw.get(rule_name))
                 .copied()
                 .unwrap_or(1.0) as f64;
-            (base * multiplier).max(0.0)
+            let scale = 1.0 + alt.intensity_scale as f64 * intensity as f64;
+            (base * multiplier * scale).max(0.0)
         })
         .collect();
 
+    if let Some(avoid) = avoid.filter(|&i| i < weights.len()) {
+        let others: f64 = weights
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != avoid)
+            .map(|(_, w)| w)
+            .sum();
+        if others > 0.0 {
+            weights[avoid] = 0.0;
+        }
+    }
+
     let dist = WeightedIndex::new(&weights)
         .map_err(|_| GrammarError::NoAlternatives(rule_name.to_string()))?;
-    Ok(&alts[dist.sample(rng)])
+    Ok(dist.sample(rng))
 }
 
 /// Look up an entity field from context bindings.
-fn resolve_entity_field(ctx: &SelectionContext<'_>, field: &str) -> Result<String, GrammarError> {
-    // Try to find the field in any bound entity's properties, or check name
-    // First check the "subject" binding, then any binding
-    let entity = ctx
-        .entity_bindings
-        .get("subject")
-        .or_else(|| ctx.entity_bindings.values().next())
-        .ok_or_else(|| GrammarError::EntityBindingNotFound("subject".to_string()))?;
+///
+/// With a `role`, only that binding is consulted. Without one, the
+/// "subject" binding is used, falling back to any binding.
+fn resolve_entity_field(
+    ctx: &SelectionContext<'_>,
+    role: Option<&str>,
+    field: &str,
+) -> Result<String, GrammarError> {
+    let entity = bound_entity(ctx, role)?;
 
     if field == "name" {
         return Ok(entity.name.clone());
     }
 
-    match entity.properties.get(field) {
-        Some(Value::String(s)) => Ok(s.clone()),
-        Some(Value::Float(f)) => Ok(format!("{}", f)),
-        Some(Value::Int(i)) => Ok(format!("{}", i)),
-        Some(Value::Bool(b)) => Ok(format!("{}", b)),
+    // Optional format spec after a colon (`composure:.1%`), else a
+    // suffix hint: `gold#,` groups thousands, `pct%` renders a 0..1
+    // float as a percentage.
+    let (key, hint) = if let Some((key, spec)) = field.split_once(':') {
+        let hint = NumberFormat::parse_spec(spec).ok_or_else(|| {
+            GrammarError::TemplateParse(format!("unknown format spec '{}'", spec))
+        })?;
+        (key, hint)
+    } else if let Some(key) = field.strip_suffix("#,") {
+        (key, NumberFormat::Grouped)
+    } else if let Some(key) = field.strip_suffix('%') {
+        (key, NumberFormat::Percent(0))
+    } else {
+        (field, NumberFormat::Plain)
+    };
+
+    match lookup_property(ctx, role, entity, key) {
+        Some(value) => format_value(value, hint, key),
+        None => Err(GrammarError::EntityFieldNotFound(key.to_string())),
+    }
+}
+
+/// The entity bound to `role`, or without one the "subject" binding,
+/// falling back to any binding.
+fn bound_entity<'a>(
+    ctx: &SelectionContext<'a>,
+    role: Option<&str>,
+) -> Result<&'a Entity, GrammarError> {
+    match role {
+        Some(role) => ctx
+            .entity_bindings
+            .get(role)
+            .copied()
+            .ok_or_else(|| GrammarError::EntityBindingNotFound(role.to_string())),
+        None => ctx
+            .entity_bindings
+            .get("subject")
+            .or_else(|| ctx.entity_bindings.values().next())
+            .copied()
+            .ok_or_else(|| GrammarError::EntityBindingNotFound("subject".to_string())),
+    }
+}
+
+/// A property of `entity`, preferring the metadata overlay for the
+/// subject. A dotted key not stored verbatim walks into nested maps.
+fn lookup_property<'a>(
+    ctx: &'a SelectionContext<'_>,
+    role: Option<&str>,
+    entity: &'a Entity,
+    key: &str,
+) -> Option<&'a Value> {
+    // The metadata overlay only ever describes the subject
+    let get = |name: &str| {
+        match role {
+            None | Some("subject") => ctx.subject_overlay.get(name),
+            Some(_) => None,
+        }
+        .or_else(|| entity.properties.get(name))
+    };
+    get(key).or_else(|| {
+        let mut parts = key.split('.');
+        let root = get(parts.next()?)?;
+        parts.try_fold(root, |value, part| match value {
+            Value::Map(map) => map.get(part),
+            _ => None,
+        })
+    })
+}
+
+/// Pick the singular or plural noun for `{plural:role.field:one:many}`
+/// from an integer property; only exactly 1 is singular.
+fn resolve_plural<'t>(
+    ctx: &SelectionContext<'_>,
+    role: Option<&str>,
+    field: &str,
+    singular: &'t str,
+    plural: &'t str,
+) -> Result<&'t str, GrammarError> {
+    let entity = bound_entity(ctx, role)?;
+    match lookup_property(ctx, role, entity, field) {
+        Some(Value::Int(1)) => Ok(singular),
+        Some(Value::Int(_)) => Ok(plural),
+        Some(_) => Err(GrammarError::EntityFieldNotInteger(field.to_string())),
         None => Err(GrammarError::EntityFieldNotFound(field.to_string())),
     }
 }
 
+/// Render a property value for a template. Lists join their elements
+/// with commas; maps can't be rendered directly.
+fn format_value(value: &Value, hint: NumberFormat, key: &str) -> Result<String, GrammarError> {
+    match (value, hint) {
+        (Value::String(s), _) => Ok(s.clone()),
+        (Value::Bool(b), _) => Ok(format!("{}", b)),
+        (Value::Int(i), NumberFormat::Plain | NumberFormat::Integer) => Ok(format!("{}", i)),
+        (Value::Int(i), NumberFormat::Grouped) => Ok(group_thousands(&i.to_string())),
+        (Value::Int(i), NumberFormat::Percent(0)) => Ok(format!("{}%", i)),
+        (Value::Int(i), NumberFormat::Percent(decimals)) => {
+            Ok(format!("{:.*}%", decimals, *i as f64))
+        }
+        (Value::Int(i), NumberFormat::Fixed(decimals)) => Ok(format!("{:.*}", decimals, *i as f64)),
+        (Value::Float(f), NumberFormat::Plain) => Ok(format!("{}", f)),
+        (Value::Float(f), NumberFormat::Grouped) => Ok(group_thousands(&f.to_string())),
+        (Value::Float(f), NumberFormat::Percent(0)) => {
+            Ok(format!("{}%", (f * 100.0).round() as i64))
+        }
+        (Value::Float(f), NumberFormat::Percent(decimals)) => {
+            Ok(format!("{:.*}%", decimals, f * 100.0))
+        }
+        (Value::Float(f), NumberFormat::Fixed(decimals)) => Ok(format!("{:.*}", decimals, f)),
+        (Value::Float(f), NumberFormat::Integer) => Ok(format!("{}", f.round() as i64)),
+        (Value::List(items), _) => Ok(items
+            .iter()
+            .map(|item| format_value(item, hint, key))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", ")),
+        (Value::Map(_), _) => Err(GrammarError::EntityFieldIsMap(key.to_string())),
+    }
+}
+
+/// Formatting hint parsed from the end of an `{entity.field}` name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberFormat {
+    Plain,
+    Grouped,
+    /// Percentage with this many decimals; floats are scaled by 100.
+    Percent(usize),
+    /// Fixed number of decimals.
+    Fixed(usize),
+    /// Rounded to a whole number.
+    Integer,
+}
+
+impl NumberFormat {
+    /// Most decimals a `.N` or `.N%` spec may ask for.
+    const MAX_DECIMALS: usize = 9;
+
+    /// Parse a format spec from `{entity.field:spec}`: `.N` for N
+    /// decimals (at most `MAX_DECIMALS`), `.N%` or `%` for a percentage,
+    /// `int` for a whole number.
+    fn parse_spec(spec: &str) -> Option<Self> {
+        match spec {
+            "%" => Some(Self::Percent(0)),
+            "int" => Some(Self::Integer),
+            _ => {
+                let decimals = spec.strip_prefix('.')?;
+                let (digits, percent) = match decimals.strip_suffix('%') {
+                    Some(digits) => (digits, true),
+                    None => (decimals, false),
+                };
+                let decimals: usize = digits.parse().ok()?;
+                if decimals > Self::MAX_DECIMALS {
+                    return None;
+                }
+                Some(if percent {
+                    Self::Percent(decimals)
+                } else {
+                    Self::Fixed(decimals)
+                })
+            }
+        }
+    }
+}
+
+/// Reject an unknown `:spec` on an entity field at parse time.
+fn check_format_spec(content: &str, field: &str) -> Result<(), GrammarError> {
+    match field.split_once(':') {
+        Some((_, spec)) if NumberFormat::parse_spec(spec).is_none() => {
+            Err(GrammarError::TemplateParse(format!(
+                "unknown format spec '{}' in '{{{}}}' (expected .N, .N%, %, or int, with N at most {})",
+                spec,
+                content,
+                NumberFormat::MAX_DECIMALS
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Insert `,` every three digits in the integer part of a formatted
+/// number: "-1234567.5" → "-1,234,567.5".
+fn group_thousands(number: &str) -> String {
+    let (sign, rest) = match number.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", number),
+    };
+    let (int_part, frac_part) = match rest.find('.') {
+        Some(dot) => rest.split_at(dot),
+        None => (rest, ""),
+    };
+    let mut grouped = String::new();
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}{}{}", sign, grouped, frac_part)
+}
+
+/// Record a reference to `entity` for pronoun disambiguation.
+fn note_mention<'a>(ctx: &mut SelectionContext<'a>, entity: &'a Entity) {
+    if ctx.disambiguate_pronouns {
+        ctx.mentions.push(entity);
+    }
+}
+
+/// Suffixes that turn a role name into a pronoun reference, e.g.
+/// `{witness_reflexive}`.
+const PRONOUN_SUFFIXES: &[&str] = &["_possessive", "_reflexive", "_object"];
+
+/// Whether `name` is a role with a pronoun suffix, e.g. `witness_object`.
+fn is_role_pronoun(name: &str) -> bool {
+    PRONOUN_SUFFIXES.iter().any(|suffix| {
+        name.strip_suffix(suffix)
+            .is_some_and(|role| !role.is_empty() && is_identifier(role))
+    })
+}
+
+/// Whether `name` is `subject`, `object` or one of `roles`, bare or with
+/// a pronoun suffix.
+fn is_known_role_ref(name: &str, roles: &[&str]) -> bool {
+    let role = PRONOUN_SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name);
+    matches!(role, "subject" | "object") || roles.contains(&role)
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
 /// Resolve a pronoun reference using the entity's pronoun set.
 ///
 /// - `{subject}` → entity name (templates expect the name here)
 /// - `{object}` → entity name for the "object" role
 /// - `{possessive}` → possessive pronoun (her, his, their, its)
-fn resolve_pronoun(ctx: &SelectionContext<'_>, role: &str) -> Result<String, GrammarError> {
-    // Map pronoun role to entity binding
-    let binding_key = match role {
-        "subject" => "subject",
-        "object" => "object",
-        "possessive" => "subject",
-        other => other,
+/// - `{reflexive}` → reflexive pronoun (herself, himself, themselves, itself)
+/// - `{role_possessive}` / `{role_reflexive}` / `{role_object}` → that
+///   pronoun for the entity bound to `role`
+fn resolve_pronoun(ctx: &mut SelectionContext<'_>, role: &str) -> Result<String, GrammarError> {
+    // Map pronoun role to entity binding and form
+    let (binding_key, form) = match role {
+        "subject" | "object" => (role, "name"),
+        "possessive" | "reflexive" => ("subject", role),
+        other => PRONOUN_SUFFIXES
+            .iter()
+            .find_map(|suffix| other.strip_suffix(suffix).map(|key| (key, &suffix[1..])))
+            .unwrap_or((other, "name")),
     };
 
-    let entity = ctx
+    let shorthand = matches!(role, "subject" | "object" | "possessive" | "reflexive");
+    let entity = *ctx
         .entity_bindings
         .get(binding_key)
-        // Fall back to subject for object/possessive if not separately bound
-        .or_else(|| ctx.entity_bindings.get("subject"))
-        .ok_or_else(|| GrammarError::EntityBindingNotFound(role.to_string()))?;
+        // Fall back to subject for the shorthands if not separately bound
+        .or_else(|| {
+            shorthand
+                .then(|| ctx.entity_bindings.get("subject"))
+                .flatten()
+        })
+        .ok_or_else(|| GrammarError::EntityBindingNotFound(binding_key.to_string()))?;
 
-    match role {
+    // "his" is ambiguous when someone else in the scene is also "he" and
+    // the last "he" mentioned wasn't this entity
+    let ambiguous = ctx.disambiguate_pronouns
+        && ctx
+            .entity_bindings
+            .values()
+            .any(|other| other.id != entity.id && other.pronouns == entity.pronouns)
+        && !ctx
+            .mentions
+            .iter()
+            .rev()
+            .find(|mentioned| mentioned.pronouns == entity.pronouns)
+            .is_some_and(|mentioned| mentioned.id == entity.id);
+    note_mention(ctx, entity);
+
+    match form {
+        "possessive" if ambiguous => Ok(format!("{}'s", entity.name)),
         "possessive" => Ok(entity.pronouns.possessive().to_string()),
+        "reflexive" => Ok(entity.pronouns.reflexive().to_string()),
+        "object" if ambiguous => Ok(entity.name.clone()),
+        "object" => Ok(entity.pronouns.object().to_string()),
         _ => Ok(entity.name.clone()),
     }
 }
@@ -483,7 +2033,7 @@ fn resolve_pronoun(ctx: &SelectionContext<'_>, role: &str) -> Result<String, Gra
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::entity::{Entity, EntityId, VoiceId};
+    use crate::schema::entity::{Entity, EntityId, Pronouns, VoiceId};
     use rand::SeedableRng;
 
     fn make_test_entity(name: &str) -> Entity {
@@ -544,9 +2094,20 @@ mod tests {
         assert_eq!(
             t.segments[1],
             TemplateSegment::EntityField {
+                role: None,
                 field: "name".to_string()
             }
         );
+
+        let t = Template::parse("{object.title}").unwrap();
+        assert_eq!(
+            t.segments[0],
+            TemplateSegment::EntityField {
+                role: Some("object".to_string()),
+                field: "title".to_string()
+            }
+        );
+        assert_eq!(t.to_source(), "{object.title}");
     }
 
     #[test]
@@ -581,6 +2142,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn to_source_round_trip() {
+        let sources = [
+            "{subject} set down {possessive} {entity.held_item} and said {markov:dialogue:tense}.",
+            "Use {{braces}} here, {object}.",
+            "{greeting}{action_detail}",
+            "",
+        ];
+        for source in sources {
+            let t = Template::parse(source).unwrap();
+            assert_eq!(t.to_source(), source);
+            assert_eq!(Template::parse(&t.to_source()).unwrap(), t);
+        }
+    }
+
+    #[test]
+    fn lint_flags_unescaped_braces() {
+        let hint = Template::lint_braces("Use { here").unwrap();
+        assert!(hint.contains("unclosed brace"), "got: {}", hint);
+        assert!(Template::lint_braces("Use {{ here").is_none());
+        assert!(Template::lint_braces("{subject} {action_detail}").is_none());
+        assert!(Template::lint_braces("a {curly remark} here").is_some());
+
+        let input = r#"{
+            "greeting": Rule(
+                requires: [],
+                alternatives: [(weight: 1, text: "Use { here"), (weight: 1, text: "Fine.")],
+            ),
+        }"#;
+        let hints = GrammarSet::lint_ron_braces(input).unwrap();
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].0, "greeting");
+    }
+
+    #[test]
+    fn parse_inline_choice() {
+        let t = Template::parse("He {nodded|inclined his head|gave a slight bow}.").unwrap();
+        assert_eq!(t.segments.len(), 3);
+        match &t.segments[1] {
+            TemplateSegment::InlineChoice(branches) => {
+                assert_eq!(branches.len(), 3);
+                assert_eq!(
+                    branches[1].segments,
+                    vec![TemplateSegment::Literal("inclined his head".to_string())]
+                );
+            }
+            other => panic!("expected InlineChoice, got {:?}", other),
+        }
+
+        let nested = Template::parse("{greeting|{formal_greeting}}").unwrap();
+        assert_eq!(
+            nested.segments,
+            vec![TemplateSegment::InlineChoice(vec![
+                Template::parse("greeting").unwrap(),
+                Template::parse("{formal_greeting}").unwrap(),
+            ])]
+        );
+        assert_eq!(Template::parse(&nested.to_source()).unwrap(), nested);
+
+        assert!(Template::parse("{a||b}").is_err());
+        assert!(Template::parse("{a|b|}").is_err());
+    }
+
+    #[test]
+    fn expand_inline_choice() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "greet": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} {nodded|{bow}}.")],
+                ),
+                "bow": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "gave a slight bow")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let entity = make_test_entity("Margaret");
+        let mut seen = std::collections::HashSet::new();
+        for seed in 0..20 {
+            let mut ctx = SelectionContext::new().with_entity("subject", &entity);
+            let mut rng = StdRng::seed_from_u64(seed);
+            seen.insert(gs.expand("greet", &mut ctx, &mut rng).unwrap());
+        }
+        let expected: std::collections::HashSet<String> = [
+            "Margaret nodded.".to_string(),
+            "Margaret gave a slight bow.".to_string(),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(seen, expected);
+    }
+
     #[test]
     fn parse_empty_braces_error() {
         assert!(Template::parse("Bad {} here").is_err());
@@ -615,7 +2270,7 @@ mod tests {
             matches!(&t.segments[2], TemplateSegment::PronounRef { role } if role == "possessive")
         );
         assert!(
-            matches!(&t.segments[4], TemplateSegment::EntityField { field } if field == "held_item")
+            matches!(&t.segments[4], TemplateSegment::EntityField { role: None, field } if field == "held_item")
         );
         assert!(
             matches!(&t.segments[6], TemplateSegment::MarkovRef { corpus, tag } if corpus == "dialogue" && tag == "tense")
@@ -639,6 +2294,56 @@ mod tests {
         assert!(greeting.requires.is_empty());
     }
 
+    #[test]
+    fn json_matches_equivalent_ron() {
+        let from_json = GrammarSet::parse_json(
+            r#"{
+                "greeting": {
+                    "requires": ["mood:warm"],
+                    "excludes": ["mood:tense"],
+                    "alternatives": [
+                        { "weight": 2, "text": "{subject} smiled. {detail}" },
+                        { "weight": 1, "text": "Hello, {object}.", "intensity_scale": 0.5 }
+                    ]
+                },
+                "detail": {
+                    "requires": [],
+                    "alternatives": [{ "weight": 1, "text": "The candles glowed." }]
+                }
+            }"#,
+        )
+        .unwrap();
+        let from_ron = GrammarSet::parse_ron(
+            r#"{
+                "greeting": Rule(
+                    requires: ["mood:warm"],
+                    excludes: ["mood:tense"],
+                    alternatives: [
+                        (weight: 2, text: "{subject} smiled. {detail}"),
+                        (weight: 1, text: "Hello, {object}.", intensity_scale: 0.5),
+                    ],
+                ),
+                "detail": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "The candles glowed.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(from_json.rules, from_ron.rules);
+
+        assert!(matches!(
+            GrammarSet::parse_json(r#"{ "bad": { "requires": [] } }"#),
+            Err(GrammarError::Json(_))
+        ));
+        assert!(matches!(
+            GrammarSet::parse_json(
+                r#"{ "bad": { "requires": [], "alternatives": [{ "weight": 1, "text": "{oops" }] } }"#
+            ),
+            Err(GrammarError::TemplateParse(_))
+        ));
+    }
+
     #[test]
     fn ron_round_trip() {
         let mut gs = GrammarSet::default();
@@ -651,6 +2356,7 @@ mod tests {
                 alternatives: vec![Alternative {
                     weight: 1,
                     template: Template::parse("Hello {entity.name}.").unwrap(),
+                    intensity_scale: 0.0,
                 }],
             },
         );
@@ -661,6 +2367,34 @@ mod tests {
         assert!(deserialized.rules.contains_key("test_rule"));
     }
 
+    #[test]
+    fn to_ron_pretty_round_trip() {
+        let mut gs = load_test_grammar();
+        gs.merge(
+            GrammarSet::parse_ron(
+                r#"{
+                    "escapes": Rule(
+                        requires: ["mood:tense"],
+                        excludes: ["mood:warm"],
+                        alternatives: [
+                            (weight: 2, text: "Use {{braces}} and \"quotes\" here."),
+                            (weight: 1, text: "{subject} held {entity.held_item}.", intensity_scale: 1.5),
+                        ],
+                    ),
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        let ron_text = gs.to_ron_pretty().unwrap();
+        assert!(ron_text.contains("Rule("));
+        let reparsed = GrammarSet::parse_ron(&ron_text).unwrap();
+        assert_eq!(reparsed.rules, gs.rules);
+
+        // Canonical: serializing again is byte-identical
+        assert_eq!(reparsed.to_ron_pretty().unwrap(), ron_text);
+    }
+
     #[test]
     fn merge_precedence() {
         let mut base = GrammarSet::default();
@@ -673,6 +2407,7 @@ mod tests {
                 alternatives: vec![Alternative {
                     weight: 1,
                     template: Template::parse("base version").unwrap(),
+                    intensity_scale: 0.0,
                 }],
             },
         );
@@ -685,6 +2420,7 @@ mod tests {
                 alternatives: vec![Alternative {
                     weight: 1,
                     template: Template::parse("only in base").unwrap(),
+                    intensity_scale: 0.0,
                 }],
             },
         );
@@ -699,6 +2435,7 @@ mod tests {
                 alternatives: vec![Alternative {
                     weight: 2,
                     template: Template::parse("override version").unwrap(),
+                    intensity_scale: 0.0,
                 }],
             },
         );
@@ -729,6 +2466,119 @@ mod tests {
         assert_eq!(tense.requires, vec!["mood:tense".to_string()]);
     }
 
+    #[test]
+    fn supported_functions_match_entry_rules() {
+        let mut gs =
+            GrammarSet::load_from_ron(std::path::Path::new("genre_data/social_drama/grammar.ron"))
+                .unwrap();
+        let expected: Vec<&str> = NarrativeFunction::BUILTIN
+            .iter()
+            .map(|f| f.name())
+            .filter(|name| gs.rules.contains_key(&format!("{}_opening", name)))
+            .collect();
+        assert_eq!(
+            expected,
+            [
+                "revelation",
+                "confrontation",
+                "betrayal",
+                "alliance",
+                "comic_relief"
+            ]
+        );
+        assert_eq!(gs.supported_functions(), expected);
+
+        // A bare `{fn}` rule counts; an empty opening does not
+        gs.merge(
+            GrammarSet::parse_ron(
+                r#"{
+                    "loss": Rule(
+                        requires: [],
+                        alternatives: [(weight: 1, text: "The chair stayed empty.")],
+                    ),
+                }"#,
+            )
+            .unwrap(),
+        );
+        gs.rules
+            .get_mut("alliance_opening")
+            .unwrap()
+            .alternatives
+            .clear();
+        let supported = gs.supported_functions();
+        assert!(supported.contains(&"loss"));
+        assert!(!supported.contains(&"alliance"));
+        assert!(!gs.can_narrate(&NarrativeFunction::Alliance));
+    }
+
+    #[test]
+    fn remove_rule_and_function() {
+        let mut gs =
+            GrammarSet::load_from_ron(std::path::Path::new("genre_data/social_drama/grammar.ron"))
+                .unwrap();
+        assert!(gs.can_narrate(&NarrativeFunction::ComicRelief));
+
+        let removed = gs.remove_rule("comic_relief_opening").unwrap();
+        assert_eq!(removed.name, "comic_relief_opening");
+        assert!(!gs.rules.contains_key("comic_relief_opening"));
+        assert!(gs.remove_rule("comic_relief_opening").is_none());
+        assert!(!gs.can_narrate(&NarrativeFunction::ComicRelief));
+
+        assert!(gs.can_narrate(&NarrativeFunction::Confrontation));
+        let removed = gs.remove_function(&NarrativeFunction::Confrontation);
+        assert!(!removed.is_empty());
+        assert!(!gs.can_narrate(&NarrativeFunction::Confrontation));
+        assert!(!gs.rules.contains_key("confrontation_body"));
+
+        // Nothing left behind points at the removed function's rules
+        assert!(gs
+            .dangling_references(&[])
+            .iter()
+            .all(|(_, missing)| !missing.starts_with("confrontation")));
+    }
+
+    #[test]
+    fn dangling_references_include_inline_choices() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "greet": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{nodded|{bow}}")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            gs.dangling_references(&[]),
+            vec![("greet".to_string(), "bow".to_string())]
+        );
+    }
+
+    #[test]
+    fn dangling_references_accept_only_known_roles() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "scene": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{witness} saw {subject_object} hide {hiden_object} behind {witness_reflexive}.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            gs.dangling_references(&["witness"]),
+            vec![("scene".to_string(), "hiden_object".to_string())]
+        );
+        assert_eq!(
+            gs.dangling_references(&[]),
+            vec![
+                ("scene".to_string(), "hiden_object".to_string()),
+                ("scene".to_string(), "witness".to_string()),
+                ("scene".to_string(), "witness_reflexive".to_string()),
+            ]
+        );
+    }
+
     // --- Expansion tests ---
 
     #[test]
@@ -755,6 +2605,492 @@ mod tests {
         );
     }
 
+    #[test]
+    fn expand_custom_pronouns() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "steady": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} steadied {reflexive} and lowered {possessive} voice.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut entity = make_test_entity("Ash");
+        entity.pronouns = crate::schema::entity::Pronouns::Custom {
+            subject: "xe".to_string(),
+            object: "xem".to_string(),
+            possessive: "xyr".to_string(),
+            possessive_standalone: "xyrs".to_string(),
+            reflexive: "xemself".to_string(),
+        };
+        let mut ctx = SelectionContext::new().with_entity("subject", &entity);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let result = gs.expand("steady", &mut ctx, &mut rng).unwrap();
+        assert_eq!(result, "Ash steadied xemself and lowered xyr voice.");
+    }
+
+    #[test]
+    fn expand_role_pronouns() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "aside": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} caught {witness}'s eye. {witness} steadied {witness_reflexive}, set down {witness_possessive} glass, and {subject} thanked {witness_object}.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let margaret = make_test_entity("Margaret");
+        let mut robert = make_test_entity("Robert");
+        robert.pronouns = Pronouns::HeHim;
+        let mut ctx = SelectionContext::new()
+            .with_entity("subject", &margaret)
+            .with_entity("witness", &robert);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let result = gs.expand("aside", &mut ctx, &mut rng).unwrap();
+        assert_eq!(
+            result,
+            "Margaret caught Robert's eye. Robert steadied himself, set down his glass, and Margaret thanked him."
+        );
+
+        // Role pronouns don't fall back to the subject
+        let mut unbound = SelectionContext::new().with_entity("subject", &margaret);
+        let t = Template::parse("{witness_reflexive}").unwrap();
+        assert!(matches!(
+            gs.expand_segments(&t.segments, &mut unbound, &mut rng),
+            Err(GrammarError::EntityBindingNotFound(role)) if role == "witness"
+        ));
+    }
+
+    #[test]
+    fn parse_role_pronoun_suffixes() {
+        let t = Template::parse("{witness_reflexive}{ally_object}{_object}").unwrap();
+        assert_eq!(
+            t.segments,
+            vec![
+                TemplateSegment::RuleRef("witness_reflexive".to_string()),
+                TemplateSegment::RuleRef("ally_object".to_string()),
+                TemplateSegment::RuleRef("_object".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rule_takes_precedence_over_role_pronoun() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "scene": Rule(requires: [], alternatives: [(weight: 1, text: "{subject} pocketed {found_object}, eyeing {found_possessive} owner.")]),
+                "found_object": Rule(requires: [], alternatives: [(weight: 1, text: "the key")]),
+            }"#,
+        )
+        .unwrap();
+        let margaret = make_test_entity("Margaret");
+        let mut robert = make_test_entity("Robert");
+        robert.pronouns = Pronouns::HeHim;
+        let mut ctx = SelectionContext::new()
+            .with_entity("subject", &margaret)
+            .with_entity("found", &robert);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let result = gs.expand("scene", &mut ctx, &mut rng).unwrap();
+        assert_eq!(result, "Margaret pocketed the key, eyeing his owner.");
+        assert!(gs.dangling_references(&["found"]).is_empty());
+    }
+
+    #[test]
+    fn rule_takes_precedence_over_role() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "scene": Rule(requires: [], alternatives: [(weight: 1, text: "Enter {witness}.")]),
+                "witness": Rule(requires: [], alternatives: [(weight: 1, text: "a stranger")]),
+            }"#,
+        )
+        .unwrap();
+        let robert = make_test_entity("Robert");
+        let mut ctx = SelectionContext::new().with_entity("witness", &robert);
+        let mut rng = StdRng::seed_from_u64(42);
+        assert_eq!(
+            gs.expand("scene", &mut ctx, &mut rng).unwrap(),
+            "Enter a stranger."
+        );
+    }
+
+    #[test]
+    fn entity_field_targets_role() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "face_off": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{entity.name} stared down {object.name}, the {object.title}.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let margaret = make_test_entity("Margaret");
+        let mut james = make_test_entity("James");
+        james
+            .properties
+            .insert("title".to_string(), Value::String("Earl".to_string()));
+        let mut ctx = SelectionContext::new()
+            .with_entity("subject", &margaret)
+            .with_entity("object", &james);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let result = gs.expand("face_off", &mut ctx, &mut rng).unwrap();
+        assert_eq!(result, "Margaret stared down James, the Earl.");
+
+        let ctx = SelectionContext::new().with_entity("subject", &margaret);
+        assert!(matches!(
+            resolve_entity_field(&ctx, Some("object"), "name"),
+            Err(GrammarError::EntityBindingNotFound(ref role)) if role == "object"
+        ));
+    }
+
+    #[test]
+    fn entity_field_number_formatting() {
+        let mut entity = make_test_entity("Margaret");
+        entity
+            .properties
+            .insert("gold".to_string(), Value::Int(1234567));
+        entity
+            .properties
+            .insert("composure".to_string(), Value::Float(0.85));
+        let ctx = SelectionContext::new().with_entity("subject", &entity);
+
+        assert_eq!(resolve_entity_field(&ctx, None, "gold").unwrap(), "1234567");
+        assert_eq!(
+            resolve_entity_field(&ctx, None, "gold#,").unwrap(),
+            "1,234,567"
+        );
+        assert_eq!(
+            resolve_entity_field(&ctx, None, "composure%").unwrap(),
+            "85%"
+        );
+        assert_eq!(group_thousands("-1000.25"), "-1,000.25");
+        assert_eq!(group_thousands("999"), "999");
+
+        let template = Template::parse("{entity.gold#,} crowns").unwrap();
+        assert_eq!(Template::parse(&template.to_source()).unwrap(), template);
+    }
+
+    #[test]
+    fn entity_field_lists_and_maps() {
+        let mut entity = make_test_entity("Margaret");
+        entity.properties.insert(
+            "items".to_string(),
+            Value::List(vec![
+                Value::String("a lantern".to_string()),
+                Value::String("a key".to_string()),
+            ]),
+        );
+        entity.properties.insert(
+            "stats".to_string(),
+            Value::Map(HashMap::from([
+                ("health".to_string(), Value::Int(80)),
+                (
+                    "wealth".to_string(),
+                    Value::Map(HashMap::from([("gold".to_string(), Value::Int(12000))])),
+                ),
+            ])),
+        );
+        let ctx = SelectionContext::new().with_entity("subject", &entity);
+
+        assert_eq!(
+            resolve_entity_field(&ctx, None, "items").unwrap(),
+            "a lantern, a key"
+        );
+        assert_eq!(
+            resolve_entity_field(&ctx, None, "stats.health").unwrap(),
+            "80"
+        );
+        assert_eq!(
+            resolve_entity_field(&ctx, Some("subject"), "stats.wealth.gold#,").unwrap(),
+            "12,000"
+        );
+        assert!(matches!(
+            resolve_entity_field(&ctx, None, "stats"),
+            Err(GrammarError::EntityFieldIsMap(key)) if key == "stats"
+        ));
+        assert!(matches!(
+            resolve_entity_field(&ctx, None, "stats.mana"),
+            Err(GrammarError::EntityFieldNotFound(_))
+        ));
+        assert!(matches!(
+            resolve_entity_field(&ctx, None, "items.first"),
+            Err(GrammarError::EntityFieldNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn find_best_rule_prefers_specific() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "generic": Rule(
+                    requires: [],
+                    alternatives: [(weight: 10, text: "Something happened.")],
+                ),
+                "tense": Rule(
+                    requires: ["mood:tense"],
+                    alternatives: [(weight: 1, text: "The air tightened.")],
+                ),
+                "tense_high": Rule(
+                    requires: ["mood:tense", "stakes:high"],
+                    alternatives: [(weight: 1, text: "Everything hung on this.")],
+                ),
+                "tense_high_alt": Rule(
+                    requires: ["mood:tense", "stakes:high"],
+                    alternatives: [(weight: 1, text: "No one moved.")],
+                ),
+                "tense_critical": Rule(
+                    requires: ["mood:tense", "stakes:critical", "fn:escalation"],
+                    alternatives: [(weight: 1, text: "Too late.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let best = |tags: &[&str]| {
+            let ctx = SelectionContext::new().with_tags(tags.iter().map(|t| t.to_string()));
+            gs.find_best_rule(&ctx).map(|rule| rule.name.clone())
+        };
+
+        assert_eq!(best(&["mood:tense", "stakes:high"]).unwrap(), "tense_high");
+        assert_eq!(best(&["mood:tense"]).unwrap(), "tense");
+        assert_eq!(best(&["mood:warm"]).unwrap(), "generic");
+
+        // Equal specificity: heavier total weight wins over name order
+        let mut gs = gs;
+        gs.rules.get_mut("tense_high_alt").unwrap().alternatives[0].weight = 3;
+        let ctx = SelectionContext::new()
+            .with_tags(["mood:tense".to_string(), "stakes:high".to_string()]);
+        assert_eq!(gs.find_best_rule(&ctx).unwrap().name, "tense_high_alt");
+
+        assert!(GrammarSet::default()
+            .find_best_rule(&SelectionContext::new())
+            .is_none());
+    }
+
+    #[test]
+    fn avoid_immediate_repeat_varies_back_to_back_expansions() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "pair": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{sound}, then {sound}")],
+                ),
+                "sound": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "a creak"),
+                        (weight: 1, text: "a thud"),
+                        (weight: 1, text: "a hiss"),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let differing = |avoid: bool| {
+            (0..100)
+                .filter(|&seed| {
+                    let mut ctx = SelectionContext::new();
+                    ctx.avoid_immediate_repeat = avoid;
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let text = gs.expand("pair", &mut ctx, &mut rng).unwrap();
+                    let (first, second) = text.split_once(", then ").unwrap();
+                    first != second
+                })
+                .count()
+        };
+
+        assert!(differing(false) < 100);
+        assert_eq!(differing(true), 100);
+    }
+
+    #[test]
+    fn expansion_cache_matches_uncached_output() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "scene": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{entity.name} waited. {gesture} {gesture} {gesture}")],
+                ),
+                "gesture": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "A shrug."),
+                        (weight: 1, text: "{?mood:tense:A clenched jaw.|A slow nod.}"),
+                        (weight: 1, text: "{a glance|a sigh}."),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let entity = make_test_entity("Margaret");
+        let run = |cached: bool, tags: &[&str], seed: u64| {
+            let mut ctx = SelectionContext::new()
+                .with_tags(tags.iter().map(|t| t.to_string()))
+                .with_entity("subject", &entity);
+            if cached {
+                ctx = ctx.with_expansion_cache();
+            }
+            let mut rng = StdRng::seed_from_u64(seed);
+            let text = gs.expand("scene", &mut ctx, &mut rng).unwrap();
+            (text, ctx.expansion_cache.map_or(0, |c| c.len()))
+        };
+
+        for seed in 0..50 {
+            for tags in [&[][..], &["mood:tense"][..]] {
+                let (uncached, _) = run(false, tags, seed);
+                let (cached, entries) = run(true, tags, seed);
+                assert_eq!(cached, uncached, "seed {}", seed);
+                // Only the two pure alternatives are ever memoized
+                assert!(entries <= 2);
+            }
+        }
+
+        // A tag change empties the cache, so conditionals re-evaluate
+        let mut ctx = SelectionContext::new().with_expansion_cache();
+        let mut rng = StdRng::seed_from_u64(0);
+        let calm = (0..20)
+            .map(|_| gs.expand("gesture", &mut ctx, &mut rng).unwrap())
+            .collect::<Vec<_>>();
+        assert!(calm.iter().any(|t| t == "A slow nod."));
+        ctx.tags.insert("mood:tense".to_string());
+        let tense = (0..20)
+            .map(|_| gs.expand("gesture", &mut ctx, &mut rng).unwrap())
+            .collect::<Vec<_>>();
+        assert!(tense.iter().any(|t| t == "A clenched jaw."));
+        assert!(!tense.iter().any(|t| t == "A slow nod."));
+    }
+
+    #[test]
+    fn plural_follows_integer_field() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "status": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{entity.raptors} {plural:entity.raptors:raptor:raptors} remaining.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        for (count, expected) in [
+            (0, "0 raptors remaining."),
+            (1, "1 raptor remaining."),
+            (5, "5 raptors remaining."),
+        ] {
+            let mut entity = make_test_entity("Muldoon");
+            entity
+                .properties
+                .insert("raptors".to_string(), Value::Int(count));
+            let mut ctx = SelectionContext::new().with_entity("subject", &entity);
+            assert_eq!(gs.expand("status", &mut ctx, &mut rng).unwrap(), expected);
+        }
+
+        let mut entity = make_test_entity("Muldoon");
+        entity
+            .properties
+            .insert("raptors".to_string(), Value::Float(2.5));
+        let ctx = SelectionContext::new().with_entity("object", &entity);
+        assert!(matches!(
+            resolve_plural(&ctx, Some("object"), "raptors", "raptor", "raptors"),
+            Err(GrammarError::EntityFieldNotInteger(field)) if field == "raptors"
+        ));
+        assert!(matches!(
+            resolve_plural(&ctx, Some("object"), "eggs", "egg", "eggs"),
+            Err(GrammarError::EntityFieldNotFound(_))
+        ));
+
+        let template = Template::parse("{plural:object.eggs:egg:eggs}").unwrap();
+        assert_eq!(Template::parse(&template.to_source()).unwrap(), template);
+        for bad in [
+            "{plural:entity.eggs:egg}",
+            "{plural:eggs:egg:eggs}",
+            "{plural:entity.eggs::eggs}",
+        ] {
+            assert!(
+                matches!(Template::parse(bad), Err(GrammarError::TemplateParse(_))),
+                "{} should not parse",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn entity_field_format_specs() {
+        let mut entity = make_test_entity("Margaret");
+        entity
+            .properties
+            .insert("composure".to_string(), Value::Float(0.857));
+        entity.properties.insert("age".to_string(), Value::Int(45));
+        let ctx = SelectionContext::new().with_entity("subject", &entity);
+        let resolve = |field: &str| resolve_entity_field(&ctx, None, field).unwrap();
+
+        assert_eq!(resolve("composure:.0%"), "86%");
+        assert_eq!(resolve("composure:.1%"), "85.7%");
+        assert_eq!(resolve("composure:%"), "86%");
+        assert_eq!(resolve("composure:.2"), "0.86");
+        assert_eq!(resolve("composure:int"), "1");
+        assert_eq!(resolve("age:.1"), "45.0");
+        assert_eq!(resolve("age:int"), "45");
+
+        let template = Template::parse("Composure at {object.composure:.1%}").unwrap();
+        assert_eq!(Template::parse(&template.to_source()).unwrap(), template);
+        for bad in [
+            "{entity.age:pad2}",
+            "{object.composure:.x%}",
+            "{entity.age:}",
+            "{entity.x:.70000}",
+            "{entity.x:.10%}",
+        ] {
+            assert!(
+                matches!(Template::parse(bad), Err(GrammarError::TemplateParse(_))),
+                "{} should not parse",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn entity_field_dotted_paths() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "address": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "Good evening, {entity.title.formal} {entity.name}.")],
+                ),
+                "broken": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{entity.title.regional.north}")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut entity = make_test_entity("Margaret");
+        entity.properties.insert(
+            "title".to_string(),
+            Value::Map(HashMap::from([
+                ("formal".to_string(), Value::String("Lady".to_string())),
+                ("casual".to_string(), Value::String("Maggie".to_string())),
+            ])),
+        );
+        let mut ctx = SelectionContext::new().with_entity("subject", &entity);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert_eq!(
+            gs.expand("address", &mut ctx, &mut rng).unwrap(),
+            "Good evening, Lady Margaret."
+        );
+        // The miss is reported with the full path, not just the failing part
+        assert!(matches!(
+            gs.expand("broken", &mut ctx, &mut rng),
+            Err(GrammarError::EntityFieldNotFound(path)) if path == "title.regional.north"
+        ));
+    }
+
     #[test]
     fn expand_three_levels_deep() {
         let gs = load_test_grammar();
@@ -836,11 +3172,21 @@ mod tests {
 
     #[test]
     fn max_depth_error() {
-        let gs = load_test_grammar();
+        // A chain of distinct rules deeper than the limit, with no cycle
+        let rules: Vec<String> = (0..30)
+            .map(|i| {
+                format!(
+                    r#""chain_{}": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "{{chain_{}}}")]),"#,
+                    i,
+                    i + 1
+                )
+            })
+            .collect();
+        let gs = GrammarSet::parse_ron(&format!("{{ {} }}", rules.join("\n"))).unwrap();
         let mut ctx = SelectionContext::new();
         let mut rng = StdRng::seed_from_u64(42);
 
-        let result = gs.expand("recursive_bomb", &mut ctx, &mut rng);
+        let result = gs.expand("chain_0", &mut ctx, &mut rng);
         assert!(result.is_err());
         assert!(
             matches!(result, Err(GrammarError::MaxDepthExceeded(_))),
@@ -848,6 +3194,303 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unreachable_rules_from_entries() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "alliance_opening": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "{handshake}")]),
+            "handshake": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "They {grip|{nod}}.")]),
+            "nod": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "nodded")]),
+            "stale_detail": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "{nod}")]),
+        }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            gs.unreachable_rules(&["alliance_opening", "alliance"]),
+            vec!["stale_detail".to_string()]
+        );
+        assert_eq!(gs.unreachable_rules(&[]).len(), 4);
+    }
+
+    #[test]
+    fn recursive_cycles_skip_rules_with_an_exit() {
+        let gs = load_test_grammar();
+        assert_eq!(
+            gs.recursive_cycles(),
+            vec![vec![
+                "recursive_bomb".to_string(),
+                "recursive_bomb".to_string()
+            ]]
+        );
+
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "echo": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "An echo."),
+                (weight: 1, text: "An echo of {echo}"),
+            ]),
+            "either": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "{done|{either}}")]),
+        }"#,
+        )
+        .unwrap();
+        assert!(gs.recursive_cycles().is_empty());
+    }
+
+    #[test]
+    fn article_agrees_with_expanded_text() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "vowel": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "It was {a:fruit}.")]),
+            "fruit": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "apple")]),
+            "guest": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "She greeted {a:object.name}.")]),
+        }"#,
+        )
+        .unwrap();
+        let eleanor = Entity::new(EntityId(2), "Eleanor", Pronouns::SheHer);
+        let robert = Entity::new(EntityId(3), "Robert", Pronouns::HeHim);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let mut ctx = SelectionContext::new();
+        assert_eq!(
+            gs.expand("vowel", &mut ctx, &mut rng).unwrap(),
+            "It was an apple."
+        );
+        let mut ctx = SelectionContext::new().with_entity("object", &eleanor);
+        assert_eq!(
+            gs.expand("guest", &mut ctx, &mut rng).unwrap(),
+            "She greeted an Eleanor."
+        );
+        let mut ctx = SelectionContext::new().with_entity("object", &robert);
+        assert_eq!(
+            gs.expand("guest", &mut ctx, &mut rng).unwrap(),
+            "She greeted a Robert."
+        );
+
+        let template = Template::parse("{a:object.name}").unwrap();
+        assert_eq!(template.to_source(), "{a:object.name}");
+        assert!(Template::parse("{a:}").is_err());
+    }
+
+    #[test]
+    fn cap_filter_on_rule_and_markov_refs() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "opener": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "{aside|>cap} {markov:quiet:x|>cap}")]),
+            "aside": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "...and yet")]),
+            "choice": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "{nodded|cap}")]),
+        }"#,
+        )
+        .unwrap();
+        let model = crate::core::markov::MarkovTrainer::train(
+            "the lamps burned low over the table. the lamps burned low again.",
+            2,
+        );
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut ctx = SelectionContext::new().with_markov("quiet", &model);
+        let text = gs.expand("opener", &mut ctx, &mut rng).unwrap();
+        assert!(text.starts_with("...And yet The lamps"), "{}", text);
+
+        // A filter name after a plain `|` is still an inline choice
+        let mut ctx = SelectionContext::new();
+        let choice = gs.expand("choice", &mut ctx, &mut rng).unwrap();
+        assert!(choice == "nodded" || choice == "cap");
+
+        let template = Template::parse("{aside|>cap} {markov:quiet:x|>cap}").unwrap();
+        assert_eq!(
+            template.segments[0],
+            TemplateSegment::Filtered(
+                Box::new(TemplateSegment::RuleRef("aside".to_string())),
+                SegmentFilter::Cap
+            )
+        );
+        assert_eq!(template.to_source(), "{aside|>cap} {markov:quiet:x|>cap}");
+        for bad in [
+            "{markov:quiet:x|>shout}",
+            "{body_language|>cpa}",
+            "{nodded|bowed|>cap}",
+            "{|>cap}",
+        ] {
+            assert!(
+                matches!(Template::parse(bad), Err(GrammarError::TemplateParse(_))),
+                "{} should not parse",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn conditional_follows_context_tags() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "scene": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "{?mood:tense: The air tightened.|Nothing stirred.}"),
+            ]),
+            "aside": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "She waited.{?secretive:  {glance}}"),
+            ]),
+            "glance": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "Eyes darted.")]),
+        }"#,
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+
+        let mut ctx = SelectionContext::new().with_tags(["mood:tense".to_string()]);
+        assert_eq!(
+            gs.expand("scene", &mut ctx, &mut rng).unwrap(),
+            "The air tightened."
+        );
+        let mut ctx = SelectionContext::new();
+        assert_eq!(
+            gs.expand("scene", &mut ctx, &mut rng).unwrap(),
+            "Nothing stirred."
+        );
+
+        // No otherwise branch: expands to nothing
+        assert_eq!(
+            gs.expand("aside", &mut ctx, &mut rng).unwrap(),
+            "She waited."
+        );
+        let mut ctx = SelectionContext::new().with_tags(["secretive".to_string()]);
+        assert_eq!(
+            gs.expand("aside", &mut ctx, &mut rng).unwrap(),
+            "She waited. Eyes darted."
+        );
+    }
+
+    #[test]
+    fn conditional_parse_and_escapes() {
+        let template = Template::parse("{?fn:loss:Gone||lost|{a|b}}").unwrap();
+        assert_eq!(
+            template.segments[0],
+            TemplateSegment::Conditional {
+                tag: "fn:loss".to_string(),
+                then: Template::parse("Gone|lost").unwrap(),
+                otherwise: Some(Template::parse("{a|b}").unwrap()),
+            }
+        );
+        assert_eq!(template.to_source(), "{?fn:loss:Gone||lost|{a|b}}");
+        assert_eq!(template.rule_refs(), Vec::<&str>::new());
+
+        assert!(Template::parse("{?no_colon}").is_err());
+        assert!(Template::parse("{?:then}").is_err());
+        assert!(Template::parse("{?warm:a|b|c}").is_err());
+    }
+
+    #[test]
+    fn expand_traced_records_rules_and_depths() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "opening": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "{first} then {second}."),
+            ]),
+            "first": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "one")]),
+            "second": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "two"),
+                (weight: 1, text: "three"),
+            ]),
+        }"#,
+        )
+        .unwrap();
+        let mut ctx = SelectionContext::new();
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut trace = Vec::new();
+        let text = gs
+            .expand_traced("opening", &mut ctx, &mut rng, &mut trace)
+            .unwrap();
+
+        let shape: Vec<(&str, u32)> = trace
+            .iter()
+            .map(|t| (t.rule_name.as_str(), t.depth))
+            .collect();
+        assert_eq!(shape, [("opening", 0), ("first", 1), ("second", 1)]);
+        assert_eq!(trace[0].output, text);
+        assert_eq!(trace[1].output, "one");
+        assert_eq!(trace[2].output, ["two", "three"][trace[2].alt_index]);
+        assert!(ctx.trace.is_none());
+
+        // Tracing doesn't change the result
+        let mut rng = StdRng::seed_from_u64(42);
+        let plain = gs
+            .expand("opening", &mut SelectionContext::new(), &mut rng)
+            .unwrap();
+        assert_eq!(plain, text);
+    }
+
+    #[test]
+    fn indefinite_article_edge_cases() {
+        assert_eq!(indefinite_article("hour later"), "an");
+        assert_eq!(indefinite_article("horse"), "a");
+        assert_eq!(indefinite_article("unicorn"), "a");
+        assert_eq!(indefinite_article("uninvited guest"), "an");
+        assert_eq!(indefinite_article("one-eyed cat"), "a");
+        assert_eq!(indefinite_article("MRI scan"), "an");
+        assert_eq!(indefinite_article("UFO"), "a");
+        assert_eq!(indefinite_article("\"Emerald\""), "an");
+    }
+
+    #[test]
+    fn bounded_recursion_expands() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "echo": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "An echo."),
+                (weight: 1, text: "An echo of {echo}"),
+            ]),
+        }"#,
+        )
+        .unwrap();
+        assert!(gs.recursive_cycles().is_empty());
+
+        let mut recursed = false;
+        for seed in 0..50 {
+            let mut ctx = SelectionContext::new();
+            let mut rng = StdRng::seed_from_u64(seed);
+            let text = gs.expand("echo", &mut ctx, &mut rng).unwrap();
+            assert!(text.ends_with("An echo."), "{}", text);
+            if text.starts_with("An echo of") {
+                recursed = true;
+                // Computed once on re-entry, then reused
+                assert!(ctx
+                    .terminating_rules
+                    .as_ref()
+                    .is_some_and(|rules| rules.contains("echo")));
+            }
+        }
+        assert!(recursed);
+    }
+
+    #[test]
+    fn recursive_cycle_reports_path() {
+        let gs = load_test_grammar();
+        let mut ctx = SelectionContext::new();
+        let mut rng = StdRng::seed_from_u64(42);
+        let result = gs.expand("recursive_bomb", &mut ctx, &mut rng);
+        assert!(matches!(
+            result,
+            Err(GrammarError::RecursiveCycle(ref path)) if path == &["recursive_bomb", "recursive_bomb"]
+        ));
+
+        let gs = GrammarSet::parse_ron(
+            r#"{
+            "greeting": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "Hello, {ping}")]),
+            "ping": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "ping {pong}")]),
+            "pong": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "pong {ping}")]),
+        }"#,
+        )
+        .unwrap();
+        let mut ctx = SelectionContext::new();
+        let err = gs.expand("greeting", &mut ctx, &mut rng).unwrap_err();
+        match &err {
+            GrammarError::RecursiveCycle(path) => assert_eq!(path, &["ping", "pong", "ping"]),
+            other => panic!("expected RecursiveCycle, got {:?}", other),
+        }
+        assert_eq!(
+            err.to_string(),
+            "recursive rule cycle: ping -> pong -> ping"
+        );
+        assert!(ctx.expansion_stack.is_empty());
+    }
+
     #[test]
     fn tag_propagation_affects_selection() {
         let gs = load_test_grammar();
@@ -879,6 +3522,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tag_histograms() {
+        let gs = load_test_grammar();
+        let requires = gs.requirement_histogram();
+        let excludes = gs.exclusion_histogram();
+        assert_eq!(requires.get("mood:tense"), Some(&2));
+        assert_eq!(excludes.get("mood:tense"), Some(&1));
+
+        // Genre grammars gate on narrative function rather than mood
+        let social =
+            GrammarSet::load_from_ron(std::path::Path::new("genre_data/social_drama/grammar.ron"))
+                .unwrap();
+        let requires = social.requirement_histogram();
+        assert_eq!(requires.get("fn:revelation"), Some(&3));
+        assert_eq!(requires.get("mood:tense"), None);
+    }
+
     #[test]
     fn entity_field_expansion() {
         let gs = load_test_grammar();
@@ -902,6 +3562,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn subject_overlay_takes_precedence() {
+        let gs = GrammarSet::parse_ron(
+            r#"{ "holding": Rule(requires: [], alternatives: [(weight: 1, text: "{entity.held_item}")]) }"#,
+        )
+        .unwrap();
+        let entity = make_test_entity("Margaret");
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ctx = SelectionContext::new().with_entity("subject", &entity);
+        assert_eq!(
+            gs.expand("holding", &mut ctx, &mut rng).unwrap(),
+            "wine glass"
+        );
+
+        let mut ctx = SelectionContext::new()
+            .with_entity("subject", &entity)
+            .with_subject_property("held_item", Value::String("teacup".to_string()));
+        assert_eq!(gs.expand("holding", &mut ctx, &mut rng).unwrap(), "teacup");
+    }
+
+    #[test]
+    fn markov_missing_policies() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "line": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "She said {markov:unloaded:tense}.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let expand = |policy: MarkovMissingPolicy| {
+            let mut ctx = SelectionContext::new();
+            ctx.markov_missing = policy;
+            gs.expand("line", &mut ctx, &mut StdRng::seed_from_u64(1))
+        };
+
+        assert_eq!(
+            SelectionContext::new().markov_missing,
+            MarkovMissingPolicy::Placeholder
+        );
+        assert_eq!(
+            expand(MarkovMissingPolicy::Placeholder).unwrap(),
+            "She said [markov:unloaded:tense]."
+        );
+        assert_eq!(expand(MarkovMissingPolicy::Empty).unwrap(), "She said.");
+        match expand(MarkovMissingPolicy::Error) {
+            Err(GrammarError::MarkovError(msg)) => assert!(msg.contains("unloaded:tense")),
+            other => panic!("expected MarkovError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_markov_fill_drops_only_its_own_conjunction() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "line": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "I think so. It was late, yet {markov:unloaded:tense}. More so {markov:unloaded:tense} than ever.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut ctx = SelectionContext::new();
+        ctx.markov_missing = MarkovMissingPolicy::Empty;
+        let text = gs
+            .expand("line", &mut ctx, &mut StdRng::seed_from_u64(1))
+            .unwrap();
+        assert_eq!(text, "I think so. It was late. More so than ever.");
+    }
+
     #[test]
     fn markov_placeholder_expansion() {
         let gs = load_test_grammar();
@@ -916,6 +3648,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn intensity_scale_favours_dramatic_alternative() {
+        let gs = GrammarSet::parse_ron(
+            r#"{
+                "reaction": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "calm"),
+                        (weight: 1, text: "dramatic", intensity_scale: 4.0),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+
+        let count_dramatic = |intensity: f32| {
+            (0..500)
+                .filter(|&seed| {
+                    let mut ctx = SelectionContext::new().with_intensity(intensity);
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    gs.expand("reaction", &mut ctx, &mut rng).unwrap() == "dramatic"
+                })
+                .count()
+        };
+
+        let low = count_dramatic(0.1);
+        let high = count_dramatic(0.9);
+        assert!(
+            high > low + 100,
+            "Expected dramatic alternative more often at high intensity (low={}, high={})",
+            low,
+            high
+        );
+    }
+
+    #[test]
+    fn markov_bias_favours_preferred_words() {
+        let gs = GrammarSet::parse_ron(
+            r#"{ "fill": Rule(requires: [], alternatives: [(weight: 1, text: "{markov:test:tense}")]) }"#,
+        )
+        .unwrap();
+        let corpus = std::fs::read_to_string("tests/fixtures/test_corpus.txt").unwrap();
+        let model = crate::core::markov::MarkovTrainer::train(&corpus, 2);
+        let preferred: FxHashSet<String> = ["silence".to_string()].into_iter().collect();
+        let avoided = FxHashSet::default();
+
+        let count_word = |biased: bool| {
+            (0..300)
+                .filter(|&seed| {
+                    let mut ctx = SelectionContext::new().with_markov("test", &model);
+                    if biased {
+                        ctx.markov_bias = Some(TokenBias {
+                            preferred: &preferred,
+                            avoided: &avoided,
+                        });
+                    }
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let text = gs.expand("fill", &mut ctx, &mut rng).unwrap();
+                    text.to_lowercase().contains("silence")
+                })
+                .count()
+        };
+
+        let plain = count_word(false);
+        let biased = count_word(true);
+        assert!(
+            biased > plain,
+            "Expected preferred word more often with bias (plain={}, biased={})",
+            plain,
+            biased
+        );
+    }
+
     #[test]
     fn rule_not_found_error() {
         let gs = load_test_grammar();
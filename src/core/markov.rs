@@ -2,8 +2,9 @@
 use rand::distributions::WeightedIndex;
 use rand::prelude::Distribution;
 use rand::rngs::StdRng;
+use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use thiserror::Error;
 
 /// Transition table mapping n-gram prefixes to weighted next-token options.
@@ -19,6 +20,10 @@ pub enum MarkovError {
     Io(#[from] std::io::Error),
     #[error("RON deserialization error: {0}")]
     Ron(#[from] ron::error::SpannedError),
+    #[error("cannot merge a {other}-gram model into a {expected}-gram model")]
+    NgramMismatch { expected: usize, other: usize },
+    #[error("binary model error: {0}")]
+    Bincode(#[from] bincode::Error),
 }
 
 /// Special token marking sentence start.
@@ -30,6 +35,101 @@ const SENTENCE_END: &str = "</S>";
 const SENTENCE_ENDERS: &[char] = &['.', '!', '?'];
 const PUNCTUATION: &[char] = &['.', '!', '?', ',', ';', ':', '"', '\''];
 
+/// Weight multiplier for candidate tokens in a voice's preferred vocabulary.
+const PREFERRED_TOKEN_BOOST: f64 = 3.0;
+/// Weight multiplier for candidate tokens in a voice's avoided vocabulary.
+const AVOIDED_TOKEN_PENALTY: f64 = 0.2;
+/// Lowest sampling temperature honoured; smaller values are clamped.
+const MIN_TEMPERATURE: f32 = 0.05;
+
+/// Soft vocabulary preferences applied to candidate tokens during
+/// generation. Matching is case-insensitive on single-word entries.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenBias<'a> {
+    pub preferred: &'a FxHashSet<String>,
+    pub avoided: &'a FxHashSet<String>,
+}
+
+impl TokenBias<'_> {
+    /// Weight multiplier for a candidate token.
+    fn factor(&self, token: &str) -> f64 {
+        // Only tokens with capitals need a lowercased copy
+        let lower = token
+            .chars()
+            .any(char::is_uppercase)
+            .then(|| token.to_lowercase());
+        let matches = |set: &FxHashSet<String>| {
+            set.contains(token) || lower.as_ref().is_some_and(|lower| set.contains(lower))
+        };
+        if matches(self.preferred) {
+            PREFERRED_TOKEN_BOOST
+        } else if matches(self.avoided) {
+            AVOIDED_TOKEN_PENALTY
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Settings for a generation run; see `MarkovModel::generate_with`.
+#[derive(Debug, Clone, Copy)]
+pub struct GenerateOptions<'a> {
+    /// Restrict generation to a tagged region of the corpus.
+    pub tag: Option<&'a str>,
+    /// Keep going past sentence ends until at least this many words.
+    pub min_words: usize,
+    /// Stop (trimming to the last complete sentence) at this many words.
+    pub max_words: usize,
+    /// On a dead end, retry with progressively shorter prefixes instead of
+    /// stopping. See `MarkovModel::generate_with_backoff`.
+    pub backoff: bool,
+    /// Tokens never to emit, matched case-insensitively. A pick that lands
+    /// on one is re-sampled among the rest; if every option is banned the
+    /// sentence ends there.
+    pub banned: Option<&'a FxHashSet<String>>,
+    /// Tokens the output must begin with. The state window is taken from
+    /// the end of `start`, padded with sentence-start markers if it is
+    /// shorter than n-1, so `["Candles"]` continues the way sentences
+    /// beginning "Candles" do in the corpus. Start tokens count toward the
+    /// word limits; a start with no continuation fails with `NoData`.
+    pub start: &'a [String],
+    /// Reshapes transition weights as `count^(1/temperature)`: above 1.0
+    /// flattens the distribution (more surprising picks), below 1.0
+    /// sharpens it (more predictable). Clamped to at least 0.05.
+    pub temperature: f32,
+}
+
+impl Default for GenerateOptions<'_> {
+    fn default() -> Self {
+        Self {
+            tag: None,
+            min_words: 5,
+            max_words: 15,
+            backoff: false,
+            banned: None,
+            start: &[],
+            temperature: 1.0,
+        }
+    }
+}
+
+/// Size and coverage summary of a trained model, from `MarkovModel::stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkovStats {
+    /// N-gram depth.
+    pub n: usize,
+    /// Distinct prefixes in the global table.
+    pub prefixes: usize,
+    /// Distinct (prefix, next token) pairs in the global table.
+    pub transitions: usize,
+    /// Distinct tokens the global table can emit.
+    pub vocabulary: usize,
+    /// Distinct tokens that can open a sentence.
+    pub sentence_openers: usize,
+    /// Prefix count per tag, sorted by tag name.
+    pub tags: Vec<(String, usize)>,
+}
+
 /// A trained Markov model storing n-gram probability tables.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MarkovModel {
@@ -46,7 +146,7 @@ impl MarkovModel {
     ///
     /// Starts from a sentence-start state, walks the chain selecting next
     /// tokens by weighted probability, and stops at a sentence boundary
-    /// within the word count range.
+    /// within the word count range. Shorthand for `generate_with`.
     pub fn generate(
         &self,
         rng: &mut StdRng,
@@ -54,6 +154,134 @@ impl MarkovModel {
         min_words: usize,
         max_words: usize,
     ) -> Result<String, MarkovError> {
+        self.generate_with(
+            rng,
+            &GenerateOptions {
+                tag,
+                min_words,
+                max_words,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Generate text as configured by `opts`.
+    pub fn generate_with(
+        &self,
+        rng: &mut StdRng,
+        opts: &GenerateOptions<'_>,
+    ) -> Result<String, MarkovError> {
+        self.generate_inner(rng, opts, None)
+    }
+
+    /// Generate text, backing off to shorter prefixes on dead ends.
+    ///
+    /// When the current (n-1)-token state has no transitions, the last
+    /// n-2 tokens are matched against the ends of known prefixes, and so
+    /// on down to a single token, before giving up. Identical to
+    /// `generate` whenever no dead end is hit.
+    pub fn generate_with_backoff(
+        &self,
+        rng: &mut StdRng,
+        tag: Option<&str>,
+        min_words: usize,
+        max_words: usize,
+    ) -> Result<String, MarkovError> {
+        self.generate_with(
+            rng,
+            &GenerateOptions {
+                tag,
+                min_words,
+                max_words,
+                backoff: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Generate text that begins with the given `start` tokens, backing
+    /// off on dead ends. See `GenerateOptions::start`.
+    pub fn generate_from(
+        &self,
+        rng: &mut StdRng,
+        tag: Option<&str>,
+        start: &[String],
+        min_words: usize,
+        max_words: usize,
+    ) -> Result<String, MarkovError> {
+        self.generate_with(
+            rng,
+            &GenerateOptions {
+                tag,
+                min_words,
+                max_words,
+                backoff: true,
+                start,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Generate text, softly re-weighting candidate tokens by `bias`.
+    ///
+    /// With no bias (or when no candidate at a step is affected), sampling
+    /// is identical to `generate`.
+    pub fn generate_biased(
+        &self,
+        rng: &mut StdRng,
+        tag: Option<&str>,
+        min_words: usize,
+        max_words: usize,
+        bias: Option<&TokenBias<'_>>,
+    ) -> Result<String, MarkovError> {
+        let opts = GenerateOptions {
+            tag,
+            min_words,
+            max_words,
+            ..Default::default()
+        };
+        self.generate_inner(rng, &opts, bias)
+    }
+
+    /// Generate text incrementally, yielding each token with the spacing
+    /// `generate` would give it, for progressive ("typewriter") display.
+    ///
+    /// Tokens of the first sentence are yielded as soon as they are picked;
+    /// later sentences are held until they complete, since a run that hits
+    /// `max_words` is trimmed back to its last full sentence. Concatenating
+    /// the items gives exactly the `generate_with` output for the same seed
+    /// and options. An unknown tag, empty model, or start with no
+    /// continuation yields nothing.
+    pub fn generate_iter<'a>(
+        &'a self,
+        rng: &'a mut StdRng,
+        opts: GenerateOptions<'a>,
+    ) -> impl Iterator<Item = String> + 'a {
+        let transitions = self.transitions_for(opts.tag).ok();
+        MarkovStream::new(self.n, transitions, rng, opts, None)
+    }
+
+    fn generate_inner(
+        &self,
+        rng: &mut StdRng,
+        opts: &GenerateOptions<'_>,
+        bias: Option<&TokenBias<'_>>,
+    ) -> Result<String, MarkovError> {
+        let transitions = self.transitions_for(opts.tag)?;
+        let stream = MarkovStream::new(self.n, Some(transitions), rng, *opts, bias.copied());
+        if !opts.start.is_empty() && !has_continuation(transitions, &stream.state) {
+            return Err(MarkovError::NoData);
+        }
+        let text: String = stream.collect();
+
+        if text.is_empty() {
+            return Err(MarkovError::NoSentenceStart);
+        }
+        Ok(text)
+    }
+
+    /// The transition table for `tag`, or the untagged table.
+    fn transitions_for(&self, tag: Option<&str>) -> Result<&TransitionTable, MarkovError> {
         let transitions = if let Some(tag) = tag {
             self.tagged_transitions
                 .get(tag)
@@ -65,69 +293,458 @@ impl MarkovModel {
         if transitions.is_empty() {
             return Err(MarkovError::NoData);
         }
+        Ok(transitions)
+    }
 
-        let mut result_tokens: Vec<String> = Vec::new();
-        let mut state: Vec<String> = vec![SENTENCE_START.to_string(); self.n - 1];
-        let mut word_count = 0;
-        let mut last_sentence_end = 0;
+    /// Fraction of the passage's n-grams (padded with sentence boundaries,
+    /// as in training) that never occur in the training transitions.
+    /// 0.0 means every n-gram was seen; 1.0 means none were. Returns 0.0
+    /// for text with no tokens.
+    pub fn novelty(&self, text: &str) -> f32 {
+        let tokens = tokenize(text);
+        let mut total = 0usize;
+        let mut unseen = 0usize;
+
+        for sentence in split_into_sentences(&tokens) {
+            let mut padded = vec![SENTENCE_START.to_string(); self.n - 1];
+            padded.extend(sentence);
+            padded.push(SENTENCE_END.to_string());
+
+            for window in padded.windows(self.n) {
+                let (prefix, next) = window.split_at(self.n - 1);
+                let seen = self
+                    .transitions
+                    .get(prefix)
+                    .is_some_and(|options| options.iter().any(|(tok, _)| tok == &next[0]));
+                total += 1;
+                if !seen {
+                    unseen += 1;
+                }
+            }
+        }
 
-        for _ in 0..(max_words * 3) {
-            // safety limit on iterations
-            let next = match pick_next(transitions, &state, rng) {
-                Some(tok) => tok,
-                None => break,
-            };
+        if total == 0 {
+            0.0
+        } else {
+            unseen as f32 / total as f32
+        }
+    }
 
-            if next == SENTENCE_END {
-                // Record sentence boundary position
-                last_sentence_end = result_tokens.len();
+    /// Summarize the model's size and coverage, e.g. to judge a corpus
+    /// before committing a model trained from it.
+    pub fn stats(&self) -> MarkovStats {
+        let vocabulary: FxHashSet<&str> = self
+            .transitions
+            .values()
+            .flatten()
+            .map(|(token, _)| token.as_str())
+            .filter(|token| *token != SENTENCE_END)
+            .collect();
+        let start = vec![SENTENCE_START.to_string(); self.n.saturating_sub(1)];
+        let sentence_openers = self
+            .transitions
+            .get(&start)
+            .map_or(0, |options| options.len());
+        let mut tags: Vec<(String, usize)> = self
+            .tagged_transitions
+            .iter()
+            .map(|(tag, table)| (tag.clone(), table.len()))
+            .collect();
+        tags.sort();
+
+        MarkovStats {
+            n: self.n,
+            prefixes: self.transitions.len(),
+            transitions: self.transitions.values().map(Vec::len).sum(),
+            vocabulary: vocabulary.len(),
+            sentence_openers,
+            tags,
+        }
+    }
 
-                if word_count >= min_words {
-                    break;
-                }
+    /// Drop transitions seen fewer than `min_count` times, in both the
+    /// global and tagged tables, then any prefix (or tag) left empty.
+    pub fn prune(&mut self, min_count: u32) {
+        prune_transitions(&mut self.transitions, min_count);
+        for table in self.tagged_transitions.values_mut() {
+            prune_transitions(table, min_count);
+        }
+        self.tagged_transitions.retain(|_, table| !table.is_empty());
+    }
 
-                // Start a new sentence
-                state = vec![SENTENCE_START.to_string(); self.n - 1];
-                continue;
+    /// Rename a tag in `tagged_transitions`, merging into the target tag's
+    /// table if it already exists. Returns whether the source tag existed.
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> bool {
+        let Some(table) = self.tagged_transitions.remove(from) else {
+            return false;
+        };
+        match self.tagged_transitions.get_mut(to) {
+            Some(target) => {
+                for (prefix, options) in table {
+                    for (token, count) in options {
+                        add_transition_count(target, prefix.clone(), token, count);
+                    }
+                }
             }
+            None => {
+                self.tagged_transitions.insert(to.to_string(), table);
+            }
+        }
+        true
+    }
 
-            // Count actual words (not punctuation)
-            if !PUNCTUATION.contains(&next.chars().next().unwrap_or(' ')) {
-                word_count += 1;
+    /// Merge another model's counts into this one: shared transitions have
+    /// their counts summed and tagged tables are unioned. Both models must
+    /// have the same n-gram depth. Unlike `MarkovBlender`, which mixes
+    /// models at generation time, this produces a single combined table.
+    pub fn merge(&mut self, other: &MarkovModel) -> Result<(), MarkovError> {
+        if self.n != other.n {
+            return Err(MarkovError::NgramMismatch {
+                expected: self.n,
+                other: other.n,
+            });
+        }
+        merge_table(&mut self.transitions, &other.transitions);
+        for (tag, table) in &other.tagged_transitions {
+            merge_table(
+                self.tagged_transitions.entry(tag.clone()).or_default(),
+                table,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Add every transition count in `from` to `into`.
+fn merge_table(into: &mut TransitionTable, from: &TransitionTable) {
+    for (prefix, options) in from {
+        for (token, count) in options {
+            add_transition_count(into, prefix.clone(), token.clone(), *count);
+        }
+    }
+}
+
+/// Incremental walk of a transition table; see `MarkovModel::generate_iter`.
+struct MarkovStream<'a> {
+    n: usize,
+    transitions: Option<&'a TransitionTable>,
+    rng: &'a mut StdRng,
+    opts: GenerateOptions<'a>,
+    bias: Option<TokenBias<'a>>,
+    /// `opts.banned`, lowercased once up front.
+    banned: FxHashSet<String>,
+    backoff: bool,
+    state: Vec<String>,
+    steps: usize,
+    word_count: usize,
+    picked: usize,
+    yielded: usize,
+    /// Whether a sentence with at least one token has ended.
+    sentence_ended: bool,
+    /// Tokens after the last sentence end, which may yet be trimmed.
+    pending: Vec<String>,
+    ready: VecDeque<String>,
+    done: bool,
+}
+
+impl<'a> MarkovStream<'a> {
+    fn new(
+        n: usize,
+        transitions: Option<&'a TransitionTable>,
+        rng: &'a mut StdRng,
+        opts: GenerateOptions<'a>,
+        bias: Option<TokenBias<'a>>,
+    ) -> Self {
+        let mut stream = Self {
+            n,
+            transitions,
+            rng,
+            opts,
+            bias,
+            banned: opts
+                .banned
+                .map(|banned| banned.iter().map(|b| b.to_lowercase()).collect())
+                .unwrap_or_default(),
+            backoff: opts.backoff,
+            state: vec![SENTENCE_START.to_string(); n - 1],
+            steps: 0,
+            word_count: 0,
+            picked: 0,
+            yielded: 0,
+            sentence_ended: false,
+            pending: Vec::new(),
+            ready: VecDeque::new(),
+            done: transitions.is_none(),
+        };
+        if !opts.start.is_empty() {
+            stream.begin_with(opts.start);
+        }
+        stream
+    }
+
+    /// Begin mid-sentence: emit `start` and take the state window from its
+    /// end, padded with sentence-start markers. Emits nothing if the start
+    /// can't be continued.
+    fn begin_with(&mut self, start: &[String]) {
+        let mut padded = vec![SENTENCE_START.to_string(); self.n - 1];
+        padded.extend(start.iter().cloned());
+        self.state = padded[padded.len() - (self.n - 1)..].to_vec();
+        if !self
+            .transitions
+            .is_some_and(|transitions| has_continuation(transitions, &self.state))
+        {
+            self.done = true;
+            return;
+        }
+        for token in start {
+            if !PUNCTUATION.contains(&token.chars().next().unwrap_or(' ')) {
+                self.word_count += 1;
             }
+            self.picked += 1;
+            self.emit(token.clone());
+        }
+    }
 
-            result_tokens.push(next.clone());
+    /// Queue a token for output with the spacing `reassemble_tokens` uses.
+    fn emit(&mut self, token: String) {
+        let is_punct = token.len() == 1
+            && token
+                .chars()
+                .next()
+                .is_some_and(|c| PUNCTUATION.contains(&c));
+        if self.yielded > 0 && !is_punct {
+            self.ready.push_back(format!(" {}", token));
+        } else {
+            self.ready.push_back(token);
+        }
+        self.yielded += 1;
+    }
 
-            // Slide state window
-            state.push(next);
-            if state.len() > self.n - 1 {
-                state.remove(0);
+    fn flush_pending(&mut self) {
+        for token in std::mem::take(&mut self.pending) {
+            self.emit(token);
+        }
+    }
+
+    fn finish(&mut self) {
+        self.flush_pending();
+        self.done = true;
+    }
+
+    /// Advance the chain by one pick.
+    fn step(&mut self) {
+        let Some(transitions) = self.transitions else {
+            self.done = true;
+            return;
+        };
+        // Safety limit on iterations
+        if self.steps >= self.opts.max_words * 3 {
+            self.finish();
+            return;
+        }
+        self.steps += 1;
+
+        let sampling = Sampling {
+            bias: self.bias.as_ref(),
+            banned: &self.banned,
+            temperature: self.opts.temperature,
+        };
+        let picked = pick_next(transitions, &self.state, &sampling, self.rng).or_else(|| {
+            if self.backoff {
+                pick_next_backoff(transitions, &self.state, &sampling, self.rng)
+            } else {
+                None
             }
+        });
+        let next = match picked {
+            Some(tok) => tok,
+            // Every continuation is banned: end the sentence here
+            None if transitions.contains_key(&self.state) => SENTENCE_END.to_string(),
+            None => return self.finish(),
+        };
 
-            if word_count >= max_words {
-                // Truncate at last complete sentence
-                if last_sentence_end > 0 {
-                    result_tokens.truncate(last_sentence_end);
-                }
-                break;
+        if next == SENTENCE_END {
+            // The sentence is complete and can no longer be trimmed
+            if self.picked > 0 {
+                self.sentence_ended = true;
             }
+            self.flush_pending();
+
+            if self.word_count >= self.opts.min_words {
+                self.done = true;
+                return;
+            }
+
+            // Start a new sentence
+            self.state = vec![SENTENCE_START.to_string(); self.n - 1];
+            return;
         }
 
-        if result_tokens.is_empty() {
-            return Err(MarkovError::NoSentenceStart);
+        // Count actual words (not punctuation)
+        if !PUNCTUATION.contains(&next.chars().next().unwrap_or(' ')) {
+            self.word_count += 1;
         }
 
-        Ok(reassemble_tokens(&result_tokens))
+        self.picked += 1;
+        if self.sentence_ended {
+            self.pending.push(next.clone());
+        } else {
+            self.emit(next.clone());
+        }
+
+        // Slide state window
+        self.state.push(next);
+        if self.state.len() > self.n - 1 {
+            self.state.remove(0);
+        }
+
+        if self.word_count >= self.opts.max_words {
+            // Truncate at last complete sentence
+            self.pending.clear();
+            self.done = true;
+        }
+    }
+}
+
+impl Iterator for MarkovStream<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(token) = self.ready.pop_front() {
+                return Some(token);
+            }
+            if self.done {
+                return None;
+            }
+            self.step();
+        }
     }
 }
 
 /// Pick the next token from transitions given a state prefix.
-fn pick_next(transitions: &TransitionTable, state: &[String], rng: &mut StdRng) -> Option<String> {
+fn pick_next(
+    transitions: &TransitionTable,
+    state: &[String],
+    sampling: &Sampling<'_>,
+    rng: &mut StdRng,
+) -> Option<String> {
     let options = transitions.get(state)?;
+    sample_option(options, sampling, rng)
+}
+
+/// Whether `state`, or some shorter suffix of it that backoff would try,
+/// has any recorded transition.
+fn has_continuation(transitions: &TransitionTable, state: &[String]) -> bool {
+    transitions.contains_key(state)
+        || (1..state.len()).any(|k| {
+            let suffix = &state[state.len() - k..];
+            transitions
+                .keys()
+                .any(|prefix| prefix.len() >= k && &prefix[prefix.len() - k..] == suffix)
+        })
+}
+
+/// Pick the next token using progressively shorter suffixes of `state`,
+/// pooling the options of every prefix that ends with that suffix.
+fn pick_next_backoff(
+    transitions: &TransitionTable,
+    state: &[String],
+    sampling: &Sampling<'_>,
+    rng: &mut StdRng,
+) -> Option<String> {
+    for k in (1..state.len()).rev() {
+        let suffix = &state[state.len() - k..];
+        let mut pooled: Vec<(String, u32)> = Vec::new();
+        for (prefix, options) in transitions {
+            if prefix.len() >= k && &prefix[prefix.len() - k..] == suffix {
+                for (token, count) in options {
+                    match pooled.iter_mut().find(|(tok, _)| tok == token) {
+                        Some(entry) => entry.1 += count,
+                        None => pooled.push((token.clone(), *count)),
+                    }
+                }
+            }
+        }
+        // HashMap iteration order is unstable; sort for determinism
+        pooled.sort();
+        if let Some(token) = sample_option(&pooled, sampling, rng) {
+            return Some(token);
+        }
+    }
+    None
+}
+
+/// How a stream samples each pick.
+struct Sampling<'a> {
+    bias: Option<&'a TokenBias<'a>>,
+    /// Lowercased tokens never to pick.
+    banned: &'a FxHashSet<String>,
+    temperature: f32,
+}
+
+impl Sampling<'_> {
+    fn is_banned(&self, token: &str) -> bool {
+        if token.chars().any(char::is_uppercase) {
+            self.banned.contains(&token.to_lowercase())
+        } else {
+            self.banned.contains(token)
+        }
+    }
+}
+
+/// Weighted choice among `options` that aren't banned, reshaped by the
+/// temperature and re-weighted by the bias if either has an effect.
+fn sample_option(
+    options: &[(String, u32)],
+    sampling: &Sampling<'_>,
+    rng: &mut StdRng,
+) -> Option<String> {
+    let allowed: Vec<(String, u32)>;
+    let options =
+        if !sampling.banned.is_empty() && options.iter().any(|(tok, _)| sampling.is_banned(tok)) {
+            allowed = options
+                .iter()
+                .filter(|(tok, _)| !sampling.is_banned(tok))
+                .cloned()
+                .collect();
+            &allowed
+        } else {
+            options
+        };
     if options.is_empty() {
         return None;
     }
 
+    let temperature = sampling.temperature.max(MIN_TEMPERATURE);
+    // Unbiased picks at temperature 1.0 (the default) sample the raw counts
+    let factors: Vec<f64> = match sampling.bias {
+        None if temperature == 1.0 => Vec::new(),
+        bias => options
+            .iter()
+            .map(|(tok, _)| bias.map_or(1.0, |bias| bias.factor(tok)))
+            .collect(),
+    };
+    if temperature != 1.0 || factors.iter().any(|&f| f != 1.0) {
+        // Scale by the largest count first so low temperatures can't overflow
+        let max_count = options.iter().map(|(_, count)| *count).max().unwrap_or(1) as f64;
+        let exponent = 1.0 / temperature as f64;
+        let weights: Vec<f64> = options
+            .iter()
+            .zip(&factors)
+            .map(|((_, count), factor)| {
+                let weight = if temperature == 1.0 {
+                    *count as f64
+                } else {
+                    (*count as f64 / max_count).powf(exponent)
+                };
+                weight * factor
+            })
+            .collect();
+        let dist = WeightedIndex::new(&weights).ok()?;
+        return Some(options[dist.sample(rng)].0.clone());
+    }
+
     let weights: Vec<u32> = options.iter().map(|(_, count)| *count).collect();
     let dist = WeightedIndex::new(&weights).ok()?;
     Some(options[dist.sample(rng)].0.clone())
@@ -155,67 +772,122 @@ impl MarkovTrainer {
     /// Supports tagged regions: lines prefixed with `[tag]` apply that tag
     /// to subsequent text until the next tag or end of file.
     pub fn train(text: &str, n: usize) -> MarkovModel {
+        Self::train_sources(&[(text, None)], n)
+    }
+
+    /// Train one model from several texts, each with an optional tag.
+    ///
+    /// Every sentence of a tagged source is added under that tag as well
+    /// as to the global table, and under any inline `[tag]` region it sits
+    /// in. Untagged sources contribute globally, apart from their own
+    /// inline markers.
+    pub fn train_sources(sources: &[(&str, Option<&str>)], n: usize) -> MarkovModel {
         assert!((2..=4).contains(&n), "n-gram depth must be 2-4");
 
         let mut transitions: TransitionTable = HashMap::new();
         let mut tagged_transitions: HashMap<String, TransitionTable> = HashMap::new();
+        for (text, tag) in sources {
+            add_text(text, *tag, n, &mut transitions, &mut tagged_transitions);
+        }
 
-        let mut current_tag: Option<String> = None;
+        MarkovModel {
+            n,
+            transitions,
+            tagged_transitions,
+        }
+    }
 
-        for line in text.lines() {
-            let trimmed = line.trim();
+    /// Like `train`, but drops transitions seen fewer than `min_count`
+    /// times, in both the global and tagged tables. Prefixes (and tags)
+    /// left with no transitions are removed. A `min_count` of 1 keeps
+    /// everything.
+    pub fn train_pruned(text: &str, n: usize, min_count: u32) -> MarkovModel {
+        let mut model = Self::train(text, n);
+        model.prune(min_count);
+        model
+    }
+}
 
-            // Check for tag markers: [tagname]
-            if trimmed.starts_with('[') && trimmed.ends_with(']') && trimmed.len() > 2 {
-                let tag = &trimmed[1..trimmed.len() - 1];
-                current_tag = Some(tag.to_string());
-                continue;
-            }
+/// Add `text`'s transitions to the global table, to `source_tag`'s table
+/// if given, and to the table of any inline `[tag]` region.
+fn add_text(
+    text: &str,
+    source_tag: Option<&str>,
+    n: usize,
+    transitions: &mut TransitionTable,
+    tagged_transitions: &mut HashMap<String, TransitionTable>,
+) {
+    let mut current_tag: Option<String> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+
+        // Check for tag markers: [tagname]
+        if trimmed.starts_with('[') && trimmed.ends_with(']') && trimmed.len() > 2 {
+            let tag = &trimmed[1..trimmed.len() - 1];
+            current_tag = Some(tag.to_string());
+            continue;
+        }
 
-            if trimmed.is_empty() {
-                continue;
-            }
+        if trimmed.is_empty() {
+            continue;
+        }
 
-            let tokens = tokenize(trimmed);
-            let sentences = split_into_sentences(&tokens);
+        let tokens = tokenize(trimmed);
+        let sentences = split_into_sentences(&tokens);
 
-            for sentence in &sentences {
-                // Build n-gram chain for this sentence
-                let mut padded = vec![SENTENCE_START.to_string(); n - 1];
-                padded.extend(sentence.iter().cloned());
-                padded.push(SENTENCE_END.to_string());
+        for sentence in &sentences {
+            // Build n-gram chain for this sentence
+            let mut padded = vec![SENTENCE_START.to_string(); n - 1];
+            padded.extend(sentence.iter().cloned());
+            padded.push(SENTENCE_END.to_string());
 
-                for window in padded.windows(n) {
-                    let prefix: Vec<String> = window[..n - 1].to_vec();
-                    let next = window[n - 1].clone();
+            for window in padded.windows(n) {
+                let prefix: Vec<String> = window[..n - 1].to_vec();
+                let next = window[n - 1].clone();
 
-                    // Add to global transitions
-                    add_transition(&mut transitions, prefix.clone(), next.clone());
+                // Add to global transitions
+                add_transition(transitions, prefix.clone(), next.clone());
 
-                    // Add to tagged transitions if we have a tag
-                    if let Some(ref tag) = current_tag {
-                        let tag_table = tagged_transitions.entry(tag.clone()).or_default();
-                        add_transition(tag_table, prefix, next);
-                    }
+                // Add to tagged transitions if we have a tag
+                if let Some(tag) = source_tag.filter(|&t| current_tag.as_deref() != Some(t)) {
+                    let tag_table = tagged_transitions.entry(tag.to_string()).or_default();
+                    add_transition(tag_table, prefix.clone(), next.clone());
+                }
+                if let Some(ref tag) = current_tag {
+                    let tag_table = tagged_transitions.entry(tag.clone()).or_default();
+                    add_transition(tag_table, prefix, next);
                 }
             }
         }
+    }
+}
 
-        MarkovModel {
-            n,
-            transitions,
-            tagged_transitions,
-        }
+/// Remove transitions below `min_count`, then any prefix left empty.
+fn prune_transitions(table: &mut TransitionTable, min_count: u32) {
+    for options in table.values_mut() {
+        options.retain(|(_, count)| *count >= min_count);
     }
+    table.retain(|_, options| !options.is_empty());
 }
 
 /// Add a transition to a transition table, incrementing the count.
 fn add_transition(table: &mut TransitionTable, prefix: Vec<String>, next: String) {
+    add_transition_count(table, prefix, next, 1);
+}
+
+/// Add `count` occurrences of a transition to a transition table.
+fn add_transition_count(
+    table: &mut TransitionTable,
+    prefix: Vec<String>,
+    next: String,
+    count: u32,
+) {
     let entries = table.entry(prefix).or_default();
     if let Some(entry) = entries.iter_mut().find(|(tok, _)| tok == &next) {
-        entry.1 += 1;
+        entry.1 += count;
     } else {
-        entries.push((next, 1));
+        entries.push((next, count));
     }
 }
 
@@ -273,6 +945,16 @@ fn split_into_sentences(tokens: &[String]) -> Vec<Vec<String>> {
 /// Blends output from multiple Markov models with configurable weights.
 pub struct MarkovBlender;
 
+/// One model's share of a blend, for `MarkovBlender::generate_parts`.
+#[derive(Debug, Clone, Copy)]
+pub struct BlendPart<'a> {
+    pub model: &'a MarkovModel,
+    pub weight: f32,
+    /// Tagged region to draw from; `None`, or a tag the model lacks, uses
+    /// its untagged transitions.
+    pub tag: Option<&'a str>,
+}
+
 impl MarkovBlender {
     /// Generate text by blending multiple models at each step.
     pub fn generate(
@@ -282,13 +964,36 @@ impl MarkovBlender {
         min_words: usize,
         max_words: usize,
     ) -> Result<String, MarkovError> {
-        if models.is_empty() {
+        let parts: Vec<BlendPart<'_>> = models
+            .iter()
+            .map(|&(model, weight)| BlendPart { model, weight, tag })
+            .collect();
+        Self::generate_parts(&parts, rng, min_words, max_words, None)
+    }
+
+    /// Generate text by blending `parts`, each drawing from its own tag,
+    /// with candidate tokens softly re-weighted by `bias` as in
+    /// `MarkovModel::generate_biased`. All parts must share one n-gram
+    /// depth, since they walk a single state; otherwise this fails with
+    /// `NgramMismatch`.
+    pub fn generate_parts(
+        parts: &[BlendPart<'_>],
+        rng: &mut StdRng,
+        min_words: usize,
+        max_words: usize,
+        bias: Option<&TokenBias<'_>>,
+    ) -> Result<String, MarkovError> {
+        let Some(first) = parts.first() else {
             return Err(MarkovError::NoData);
+        };
+        let n = first.model.n;
+        if let Some(other) = parts.iter().find(|part| part.model.n != n) {
+            return Err(MarkovError::NgramMismatch {
+                expected: n,
+                other: other.model.n,
+            });
         }
 
-        // All models must have the same n
-        let n = models[0].0.n;
-
         let mut result_tokens: Vec<String> = Vec::new();
         let mut state: Vec<String> = vec![SENTENCE_START.to_string(); n - 1];
         let mut word_count = 0;
@@ -296,7 +1001,7 @@ impl MarkovBlender {
 
         for _ in 0..(max_words * 3) {
             // Blend transition probabilities from all models
-            let next = match pick_next_blended(models, &state, tag, rng) {
+            let next = match pick_next_blended(parts, &state, bias, rng) {
                 Some(tok) => tok,
                 None => break,
             };
@@ -338,15 +1043,16 @@ impl MarkovBlender {
 
 /// Pick next token by blending transition probabilities from multiple models.
 fn pick_next_blended(
-    models: &[(&MarkovModel, f32)],
+    parts: &[BlendPart<'_>],
     state: &[String],
-    tag: Option<&str>,
+    bias: Option<&TokenBias<'_>>,
     rng: &mut StdRng,
 ) -> Option<String> {
     let mut combined: HashMap<String, f64> = HashMap::new();
 
-    for (model, blend_weight) in models {
-        let transitions = if let Some(tag) = tag {
+    for part in parts {
+        let model = part.model;
+        let transitions = if let Some(tag) = part.tag {
             model
                 .tagged_transitions
                 .get(tag)
@@ -361,7 +1067,7 @@ fn pick_next_blended(
                 continue;
             }
             for (tok, count) in options {
-                let prob = (*count as f64) / (total as f64) * (*blend_weight as f64);
+                let prob = (*count as f64) / (total as f64) * (part.weight as f64);
                 *combined.entry(tok.clone()).or_default() += prob;
             }
         }
@@ -371,8 +1077,13 @@ fn pick_next_blended(
         return None;
     }
 
-    let tokens: Vec<String> = combined.keys().cloned().collect();
-    let weights: Vec<f64> = tokens.iter().map(|t| combined[t]).collect();
+    // Sorted so the same seed picks the same token regardless of hash order
+    let mut tokens: Vec<String> = combined.keys().cloned().collect();
+    tokens.sort();
+    let weights: Vec<f64> = tokens
+        .iter()
+        .map(|t| combined[t] * bias.map_or(1.0, |bias| bias.factor(t)))
+        .collect();
     let dist = WeightedIndex::new(&weights).ok()?;
     Some(tokens[dist.sample(rng)].clone())
 }
@@ -392,6 +1103,21 @@ pub fn load_model(path: &std::path::Path) -> Result<MarkovModel, MarkovError> {
     Ok(model)
 }
 
+/// Save a MarkovModel in a compact binary format (`.bin`). Much faster to
+/// load than RON for large corpora, but not human-readable.
+pub fn save_model_bin(model: &MarkovModel, path: &std::path::Path) -> Result<(), MarkovError> {
+    let bytes = bincode::serialize(model)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Load a MarkovModel saved by `save_model_bin`.
+pub fn load_model_bin(path: &std::path::Path) -> Result<MarkovModel, MarkovError> {
+    let bytes = std::fs::read(path)?;
+    let model: MarkovModel = bincode::deserialize(&bytes)?;
+    Ok(model)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -432,6 +1158,54 @@ mod tests {
         assert!(model.tagged_transitions.contains_key("warm"));
     }
 
+    #[test]
+    fn train_sources_tags_each_source() {
+        let tense = "The door slammed shut. Footsteps echoed in the hall.";
+        let warm = "[hearth]\nThe fire crackled softly. Laughter filled the room.";
+        let plain = "Rain fell on the roof.";
+        let model = MarkovTrainer::train_sources(
+            &[(tense, Some("tense")), (warm, Some("warm")), (plain, None)],
+            2,
+        );
+
+        let mut tags: Vec<&String> = model.tagged_transitions.keys().collect();
+        tags.sort();
+        assert_eq!(tags, ["hearth", "tense", "warm"]);
+
+        let follows =
+            |table: &TransitionTable, word: &str| table.contains_key(&vec![word.to_string()]);
+        assert!(follows(&model.tagged_transitions["tense"], "slammed"));
+        assert!(!follows(&model.tagged_transitions["tense"], "crackled"));
+        assert!(follows(&model.tagged_transitions["warm"], "crackled"));
+        // Inline markers add their own tag without displacing the file's
+        assert!(follows(&model.tagged_transitions["hearth"], "crackled"));
+        // Every source also feeds the global table
+        for word in ["slammed", "crackled", "Rain"] {
+            assert!(follows(&model.transitions, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn stats_summarize_tables() {
+        let model = MarkovTrainer::train_sources(
+            &[
+                ("The cat sat. The dog ran.", Some("pets")),
+                ("A bird sang.", None),
+            ],
+            2,
+        );
+        let stats = model.stats();
+
+        assert_eq!(stats.n, 2);
+        // Openers: "The", "A"
+        assert_eq!(stats.sentence_openers, 2);
+        // The, cat, sat, dog, ran, A, bird, sang, "."
+        assert_eq!(stats.vocabulary, 9);
+        assert_eq!(stats.prefixes, model.transitions.len());
+        // <S>, The, cat, sat, dog, ran, "."
+        assert_eq!(stats.tags, vec![("pets".to_string(), 7)]);
+    }
+
     #[test]
     fn generate_deterministic() {
         let model = train_test_corpus();
@@ -518,6 +1292,275 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn rename_tag_preserves_generation() {
+        let original = train_test_corpus();
+        let mut renamed = original.clone();
+
+        assert!(renamed.rename_tag("tense", "strained"));
+        assert!(!renamed.tagged_transitions.contains_key("tense"));
+        assert!(!renamed.rename_tag("tense", "strained"));
+
+        for seed in 0..10 {
+            let mut rng1 = StdRng::seed_from_u64(seed);
+            let mut rng2 = StdRng::seed_from_u64(seed);
+            let before = original.generate(&mut rng1, Some("tense"), 3, 15).unwrap();
+            let after = renamed
+                .generate(&mut rng2, Some("strained"), 3, 15)
+                .unwrap();
+            assert_eq!(before, after);
+        }
+    }
+
+    #[test]
+    fn rename_tag_merges_into_existing() {
+        let mut model = train_test_corpus();
+        let warm_total: u32 = model.tagged_transitions["warm"]
+            .values()
+            .flat_map(|opts| opts.iter().map(|(_, c)| *c))
+            .sum();
+        let neutral_total: u32 = model.tagged_transitions["neutral"]
+            .values()
+            .flat_map(|opts| opts.iter().map(|(_, c)| *c))
+            .sum();
+
+        assert!(model.rename_tag("warm", "neutral"));
+        let merged_total: u32 = model.tagged_transitions["neutral"]
+            .values()
+            .flat_map(|opts| opts.iter().map(|(_, c)| *c))
+            .sum();
+        assert_eq!(merged_total, warm_total + neutral_total);
+    }
+
+    #[test]
+    fn unbiased_generation_matches_generate() {
+        let model = train_test_corpus();
+        let empty = FxHashSet::default();
+        let bias = TokenBias {
+            preferred: &empty,
+            avoided: &empty,
+        };
+        for seed in 0..10 {
+            let mut rng1 = StdRng::seed_from_u64(seed);
+            let mut rng2 = StdRng::seed_from_u64(seed);
+            assert_eq!(
+                model.generate(&mut rng1, None, 3, 15).unwrap(),
+                model
+                    .generate_biased(&mut rng2, None, 3, 15, Some(&bias))
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn backoff_extends_sparse_model() {
+        // A trigram model whose ("cat", "sat") prefix was never seen, but
+        // "sat" continues elsewhere
+        let mut transitions: TransitionTable = HashMap::new();
+        let chain = [
+            (["<S>", "<S>"], "The"),
+            (["<S>", "The"], "cat"),
+            (["The", "cat"], "sat"),
+            (["dog", "sat"], "down"),
+            (["sat", "down"], "by"),
+            (["down", "by"], "the"),
+            (["by", "the"], "door"),
+            (["the", "door"], "."),
+            (["door", "."], "</S>"),
+        ];
+        for (prefix, next) in chain {
+            add_transition(
+                &mut transitions,
+                prefix.iter().map(|t| t.to_string()).collect(),
+                next.to_string(),
+            );
+        }
+        let model = MarkovModel {
+            n: 3,
+            transitions,
+            tagged_transitions: HashMap::new(),
+        };
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let plain = model.generate(&mut rng, None, 3, 20).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+        let backed_off = model.generate_with_backoff(&mut rng, None, 3, 20).unwrap();
+
+        assert_eq!(plain, "The cat sat");
+        assert_eq!(backed_off, "The cat sat down by the door.");
+    }
+
+    #[test]
+    fn backoff_matches_generate_on_dense_model() {
+        let model = train_test_corpus();
+        for seed in 0..10 {
+            let mut rng1 = StdRng::seed_from_u64(seed);
+            let mut rng2 = StdRng::seed_from_u64(seed);
+            assert_eq!(
+                model.generate(&mut rng1, None, 3, 15).unwrap(),
+                model.generate_with_backoff(&mut rng2, None, 3, 15).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn generate_iter_matches_generate() {
+        let model = train_test_corpus();
+        for seed in 0..20 {
+            let opts = GenerateOptions {
+                tag: None,
+                min_words: 3,
+                max_words: 8,
+                ..Default::default()
+            };
+            let mut rng1 = StdRng::seed_from_u64(seed);
+            let mut rng2 = StdRng::seed_from_u64(seed);
+            let streamed: Vec<String> = model.generate_iter(&mut rng1, opts).collect();
+            assert!(streamed.len() > 1);
+            assert_eq!(
+                streamed.concat(),
+                model.generate(&mut rng2, None, 3, 8).unwrap()
+            );
+        }
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let opts = GenerateOptions {
+            tag: Some("nonexistent"),
+            ..Default::default()
+        };
+        assert_eq!(model.generate_iter(&mut rng, opts).count(), 0);
+    }
+
+    #[test]
+    fn generate_with_default_options_matches_generate() {
+        let model = train_test_corpus();
+        for seed in 0..20 {
+            let opts = GenerateOptions {
+                tag: Some("tense"),
+                ..Default::default()
+            };
+            let mut rng1 = StdRng::seed_from_u64(seed);
+            let mut rng2 = StdRng::seed_from_u64(seed);
+            assert_eq!(
+                model.generate_with(&mut rng1, &opts).unwrap(),
+                model.generate(&mut rng2, Some("tense"), 5, 15).unwrap()
+            );
+        }
+
+        let defaults = GenerateOptions::default();
+        assert!(!defaults.backoff);
+        assert!(defaults.banned.is_none());
+        assert!(defaults.start.is_empty());
+
+        // Options compose: a fixed start with bans and backoff
+        let banned: FxHashSet<String> = ["excellent".to_string()].into_iter().collect();
+        let start = vec!["The".to_string(), "wine".to_string()];
+        let opts = GenerateOptions {
+            min_words: 3,
+            max_words: 20,
+            backoff: true,
+            banned: Some(&banned),
+            start: &start,
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        let text = model.generate_with(&mut rng, &opts).unwrap();
+        assert!(text.starts_with("The wine"), "{}", text);
+        assert!(!text.contains("excellent"), "{}", text);
+    }
+
+    #[test]
+    fn temperature_controls_variety() {
+        let model = train_test_corpus();
+        let distinct = |temperature: f32| {
+            let outputs: FxHashSet<String> = (0..60)
+                .map(|seed| {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let opts = GenerateOptions {
+                        min_words: 3,
+                        max_words: 12,
+                        temperature,
+                        ..Default::default()
+                    };
+                    model.generate_with(&mut rng, &opts).unwrap()
+                })
+                .collect();
+            outputs.len()
+        };
+
+        let cold = distinct(0.1);
+        let hot = distinct(3.0);
+        assert!(hot > cold, "hot {} vs cold {}", hot, cold);
+
+        // Non-positive temperatures are clamped rather than panicking
+        let mut rng = StdRng::seed_from_u64(0);
+        let opts = GenerateOptions {
+            temperature: 0.0,
+            ..Default::default()
+        };
+        assert!(model.generate_with(&mut rng, &opts).is_ok());
+        let opts = GenerateOptions {
+            temperature: -2.0,
+            ..Default::default()
+        };
+        assert!(model.generate_with(&mut rng, &opts).is_ok());
+    }
+
+    #[test]
+    fn banned_tokens_never_appear() {
+        let model = train_test_corpus();
+        let banned: FxHashSet<String> = ["ROOM", "wine", "The"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        for seed in 0..100 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let opts = GenerateOptions {
+                min_words: 3,
+                max_words: 20,
+                banned: Some(&banned),
+                ..Default::default()
+            };
+            let text: String = model.generate_iter(&mut rng, opts).collect();
+            let words: Vec<String> = tokenize(&text).iter().map(|w| w.to_lowercase()).collect();
+            assert!(
+                !words
+                    .iter()
+                    .any(|w| w == "room" || w == "wine" || w == "the"),
+                "banned word in: {}",
+                text
+            );
+        }
+
+        // An empty ban list changes nothing
+        let none = FxHashSet::default();
+        let mut rng1 = StdRng::seed_from_u64(9);
+        let mut rng2 = StdRng::seed_from_u64(9);
+        let opts = GenerateOptions {
+            min_words: 3,
+            max_words: 12,
+            banned: Some(&none),
+            ..Default::default()
+        };
+        assert_eq!(
+            model.generate_iter(&mut rng1, opts).collect::<String>(),
+            model.generate(&mut rng2, None, 3, 12).unwrap()
+        );
+    }
+
+    #[test]
+    fn novelty_separates_corpus_from_new_text() {
+        let model = train_test_corpus();
+
+        let verbatim = model.novelty("Candles flickered along the mantelpiece.");
+        assert!(verbatim < 0.01, "verbatim novelty was {}", verbatim);
+
+        let novel = model.novelty("Quantum bicycles rarely negotiate with hungry volcanoes.");
+        assert!(novel > 0.8, "novel text scored only {}", novel);
+
+        assert_eq!(model.novelty(""), 0.0);
+    }
+
     #[test]
     fn ron_round_trip() {
         let model = train_test_corpus();
@@ -544,6 +1587,133 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn generate_from_continues_start() {
+        let model = train_test_corpus();
+        let start = vec!["The".to_string(), "wine".to_string()];
+        for seed in 0..10 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let text = model
+                .generate_from(&mut rng, Some("neutral"), &start, 3, 20)
+                .unwrap();
+            assert!(text.starts_with("The wine was"), "{}", text);
+        }
+
+        // A short start is padded with sentence-start markers
+        let trigram = MarkovTrainer::train(
+            &std::fs::read_to_string("tests/fixtures/test_corpus.txt").unwrap(),
+            3,
+        );
+        let mut rng = StdRng::seed_from_u64(1);
+        let text = trigram
+            .generate_from(&mut rng, None, &["Candles".to_string()], 3, 20)
+            .unwrap();
+        assert!(text.starts_with("Candles flickered"), "{}", text);
+
+        // No start at all behaves like a fresh sentence
+        let mut a = StdRng::seed_from_u64(4);
+        let mut b = StdRng::seed_from_u64(4);
+        assert_eq!(
+            model.generate_from(&mut a, None, &[], 3, 20).unwrap(),
+            model.generate_with_backoff(&mut b, None, 3, 20).unwrap()
+        );
+
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(matches!(
+            model.generate_from(&mut rng, None, &["Zebra".to_string()], 3, 20),
+            Err(MarkovError::NoData)
+        ));
+    }
+
+    #[test]
+    fn merge_sums_counts_and_unions_tags() {
+        let mut first = MarkovTrainer::train("[warm]\nThe door opened.", 2);
+        let second = MarkovTrainer::train("The door opened.\n[tense]\nThe door slammed.", 2);
+        first.merge(&second).unwrap();
+
+        let door = vec!["door".to_string()];
+        let mut options = first.transitions[&door].clone();
+        options.sort();
+        assert_eq!(
+            options,
+            vec![("opened".to_string(), 2), ("slammed".to_string(), 1)]
+        );
+        assert!(first.tagged_transitions.contains_key("warm"));
+        assert_eq!(
+            first.tagged_transitions["tense"][&door],
+            vec![("slammed".to_string(), 1)]
+        );
+
+        let trigram = MarkovTrainer::train("The door opened.", 3);
+        assert!(matches!(
+            first.merge(&trigram),
+            Err(MarkovError::NgramMismatch {
+                expected: 2,
+                other: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn train_pruned_drops_singletons() {
+        let corpus = "The door opened.\nThe door opened.\nThe window rattled.\n[tense]\nThe door slammed.\nThe door slammed.\nA dog barked.";
+        let full = MarkovTrainer::train(corpus, 2);
+        let pruned = MarkovTrainer::train_pruned(corpus, 2, 2);
+        let count = |model: &MarkovModel, prefix: &str, next: &str| {
+            model
+                .transitions
+                .get(&vec![prefix.to_string()])
+                .and_then(|opts| opts.iter().find(|(tok, _)| tok == next))
+                .map(|(_, count)| *count)
+        };
+
+        assert_eq!(count(&full, "door", "opened"), Some(2));
+        assert_eq!(count(&pruned, "door", "opened"), Some(2));
+        assert_eq!(count(&full, "window", "rattled"), Some(1));
+        assert_eq!(count(&pruned, "window", "rattled"), None);
+        // Prefixes left with no transitions are gone
+        assert!(!pruned.transitions.contains_key(&vec!["window".to_string()]));
+        assert!(pruned
+            .transitions
+            .values()
+            .flatten()
+            .all(|(_, count)| *count >= 2));
+
+        // Tagged tables are pruned the same way
+        let tense = &pruned.tagged_transitions["tense"];
+        assert!(tense.contains_key(&vec!["door".to_string()]));
+        assert!(!tense.contains_key(&vec!["dog".to_string()]));
+        assert!(tense.values().flatten().all(|(_, count)| *count >= 2));
+
+        // min_count 1 is a no-op
+        let unpruned = MarkovTrainer::train_pruned(corpus, 2, 1);
+        assert_eq!(unpruned.transitions, full.transitions);
+        assert_eq!(unpruned.tagged_transitions, full.tagged_transitions);
+    }
+
+    #[test]
+    fn save_and_load_model_bin() {
+        let model = train_test_corpus();
+        let path = std::path::PathBuf::from("target/test_markov_model.bin");
+
+        save_model_bin(&model, &path).unwrap();
+        let loaded = load_model_bin(&path).unwrap();
+
+        assert_eq!(loaded.n, model.n);
+        assert_eq!(loaded.transitions, model.transitions);
+        assert_eq!(loaded.tagged_transitions, model.tagged_transitions);
+
+        // Not a valid model
+        std::fs::write(&path, b"\x01").unwrap();
+        assert!(matches!(
+            load_model_bin(&path),
+            Err(MarkovError::Bincode(_))
+        ));
+
+        // Cleanup
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn blending_produces_output() {
         let model = train_test_corpus();
@@ -553,6 +1723,71 @@ mod tests {
         assert!(!result.is_empty());
     }
 
+    #[test]
+    fn blend_parts_draw_from_their_own_tags() {
+        let sea = MarkovTrainer::train(
+            "The rope held fast.\n[sea]\nThe ship sailed over the waves. The crew sailed on.",
+            2,
+        );
+        let garden = MarkovTrainer::train(
+            "The rope held fast.\n[garden]\nThe roses bloomed by the wall. The roses faded.",
+            2,
+        );
+        let parts = [
+            BlendPart {
+                model: &sea,
+                weight: 1.0,
+                tag: Some("sea"),
+            },
+            BlendPart {
+                model: &garden,
+                weight: 1.0,
+                tag: Some("garden"),
+            },
+        ];
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let text = MarkovBlender::generate_parts(&parts, &mut rng, 3, 12, None).unwrap();
+            assert!(!text.contains("rope"), "{}", text);
+        }
+
+        let preferred: FxHashSet<String> = ["roses".to_string()].into_iter().collect();
+        let avoided = FxHashSet::default();
+        let bias = TokenBias {
+            preferred: &preferred,
+            avoided: &avoided,
+        };
+        let roses = |bias: Option<&TokenBias<'_>>| {
+            (0..200)
+                .filter(|&seed| {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    MarkovBlender::generate_parts(&parts, &mut rng, 1, 6, bias)
+                        .unwrap()
+                        .contains("roses")
+                })
+                .count()
+        };
+        assert!(roses(Some(&bias)) > roses(None));
+
+        let trigram = MarkovTrainer::train("The ship sailed over the waves.", 3);
+        let mixed = [
+            parts[0],
+            BlendPart {
+                model: &trigram,
+                weight: 1.0,
+                tag: None,
+            },
+        ];
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(matches!(
+            MarkovBlender::generate_parts(&mixed, &mut rng, 3, 12, None),
+            Err(MarkovError::NgramMismatch {
+                expected: 2,
+                other: 3
+            })
+        ));
+    }
+
     #[test]
     fn trigram_model() {
         let corpus = std::fs::read_to_string("tests/fixtures/test_corpus.txt").unwrap();
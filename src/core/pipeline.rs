@@ -3,18 +3,22 @@
 /// Wires together grammar expansion, voice selection, Markov fill,
 /// variety pass, and context checking.
 use rand::rngs::StdRng;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxHashSet;
 use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 
-use crate::core::context::NarrativeContext;
-use crate::core::grammar::{GrammarError, GrammarSet, SelectionContext};
-use crate::core::markov::{MarkovError, MarkovModel};
-use crate::core::variety::VarietyPass;
-use crate::core::voice::{VoiceError, VoiceRegistry};
-use crate::schema::entity::{Entity, EntityId, VoiceId};
-use crate::schema::event::Event;
+use crate::core::context::{ContextConfig, ContextStats, NarrativeContext, RepetitionIssue};
+use crate::core::grammar::{
+    BoundCorpus, ExpansionCache, GrammarError, GrammarSet, MarkovMissingPolicy, SelectionContext,
+    TraceEntry,
+};
+use crate::core::markov::{MarkovError, MarkovModel, TokenBias};
+use crate::core::variety::{prepend_lead_in, SynonymTable, VarietyPass};
+use crate::core::voice::{Voice, VoiceError, VoiceRegistry, VoiceSelector};
+use crate::schema::entity::{Entity, EntityId, Pronouns, Value, VoiceId};
+use crate::schema::event::{EntityRef, Event, Mood, Stakes};
 use crate::schema::narrative_fn::NarrativeFunction;
 
 #[derive(Debug, Error)]
@@ -35,6 +39,62 @@ pub enum PipelineError {
     NoRuleForFunction(String),
     #[error("generation failed after {0} retries")]
     GenerationFailed(u32),
+    #[error("genre template not found: {0} (expected genre_data/{0}/grammar.ron)")]
+    GenreTemplateNotFound(String),
+    #[error("duplicate participant role: {0}")]
+    DuplicateRole(String),
+    #[error("event has {count} participants, exceeding the maximum of {max}")]
+    TooManyParticipants { count: usize, max: usize },
+    #[error("no narrative function has grammar rules loaded")]
+    NoSupportedFunctions,
+}
+
+/// Default cap on participants per event.
+const DEFAULT_MAX_PARTICIPANTS: usize = 64;
+
+/// Default number of generation attempts before accepting repetition.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Lead-ins used to join the beats of a compound passage.
+const COMPOUND_CONNECTIVES: &[&str] = &[
+    "In the same breath, ",
+    "And with that, ",
+    "At the same time, ",
+    "Beneath it all, ",
+];
+
+/// Combined valence (function plus outcome) beyond which a
+/// `tone:grim` / `tone:hopeful` tag is added.
+const TONE_THRESHOLD: f32 = 0.3;
+
+/// How much any relationship at all counts toward `suggest_object`, so
+/// neutral functions still prefer someone the subject knows.
+const FAMILIARITY_WEIGHT: f32 = 0.1;
+
+/// A rendered passage plus the entry alternatives that produced it.
+struct Rendered {
+    text: String,
+    entry_selections: Vec<(String, usize)>,
+    voice_id: Option<VoiceId>,
+    trace: Vec<TraceEntry>,
+}
+
+/// A generated passage with details of how it was produced.
+#[derive(Debug, Clone)]
+pub struct Narration {
+    pub text: String,
+    /// The resolved narrative function (the first one, for compound passages).
+    pub narrative_fn: NarrativeFunction,
+    /// The voice used, if any.
+    pub voice_id: Option<VoiceId>,
+    /// Extra attempts made after the first; 0 if the first was accepted.
+    pub retries: u32,
+    /// Repetition issues still present in the accepted text.
+    pub remaining_issues: Vec<RepetitionIssue>,
+    /// Every rule expanded to produce `text`, parents before children
+    /// (see `GrammarSet::expand_traced`). Empty unless the engine was
+    /// built with `trace_expansions`.
+    pub trace: Vec<TraceEntry>,
 }
 
 /// World state passed by the game to the narration pipeline.
@@ -49,6 +109,16 @@ pub struct EventMapping {
     pub narrative_fn: NarrativeFunction,
 }
 
+/// A saveable snapshot of an engine's generation state: seed, generation
+/// count, and repetition context. Content (grammars, voices, models) is
+/// not included; it is reloaded from files.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct EngineState {
+    pub seed: u64,
+    pub generation_count: u64,
+    pub context: NarrativeContext,
+}
+
 /// The top-level narrative engine. Built via `NarrativeEngine::builder()`.
 pub struct NarrativeEngine {
     grammars: GrammarSet,
@@ -58,6 +128,27 @@ pub struct NarrativeEngine {
     context: NarrativeContext,
     seed: u64,
     generation_count: u64,
+    metadata_as_properties: bool,
+    metadata_as_tags: bool,
+    avoid_repeat_entries: bool,
+    markov_vocabulary_bias: bool,
+    max_sentences: Option<usize>,
+    default_moods: HashMap<NarrativeFunction, Mood>,
+    relationship_polarities: HashMap<String, f32>,
+    strict_entities: bool,
+    max_participants: usize,
+    max_retries: u32,
+    role_prefixed_tags: bool,
+    select_opening_by_tags: bool,
+    disambiguate_pronouns: bool,
+    avoid_immediate_repeat: bool,
+    cache_expansions: bool,
+    trace_expansions: bool,
+    markov_missing: MarkovMissingPolicy,
+    substitute_pronouns: bool,
+    /// The built-in synonyms merged with any custom table, built once.
+    synonyms: SynonymTable,
+    voice_selectors: Vec<VoiceSelector>,
 }
 
 /// Builder for constructing a `NarrativeEngine`.
@@ -68,6 +159,33 @@ pub struct NarrativeEngineBuilder {
     markov_models_dir: Option<String>,
     mappings_path: Option<String>,
     seed: u64,
+    metadata_as_properties: bool,
+    metadata_as_tags: bool,
+    avoid_repeat_entries: bool,
+    markov_vocabulary_bias: bool,
+    max_sentences: Option<usize>,
+    default_moods: HashMap<NarrativeFunction, Mood>,
+    relationship_polarities: HashMap<String, f32>,
+    strict_entities: bool,
+    max_participants: usize,
+    max_retries: u32,
+    role_prefixed_tags: bool,
+    select_opening_by_tags: bool,
+    disambiguate_pronouns: bool,
+    avoid_immediate_repeat: bool,
+    cache_expansions: bool,
+    trace_expansions: bool,
+    markov_missing: MarkovMissingPolicy,
+    substitute_pronouns: bool,
+    synonyms: Option<SynonymTable>,
+    voice_selectors: Vec<VoiceSelector>,
+    context_config: ContextConfig,
+    /// Individual repetition settings, applied over `context_config` in
+    /// `build()` so setter order doesn't matter.
+    repetition_window: Option<usize>,
+    overuse_threshold: Option<usize>,
+    min_significant_len: Option<usize>,
+    monotony_stddev: Option<f64>,
     /// Directly provided grammars (for testing without files).
     grammars: Option<GrammarSet>,
     /// Directly provided voices (for testing without files).
@@ -87,6 +205,31 @@ impl NarrativeEngine {
             markov_models_dir: None,
             mappings_path: None,
             seed: 0,
+            metadata_as_properties: false,
+            metadata_as_tags: false,
+            avoid_repeat_entries: false,
+            markov_vocabulary_bias: false,
+            max_sentences: None,
+            default_moods: HashMap::new(),
+            relationship_polarities: HashMap::new(),
+            strict_entities: false,
+            max_participants: DEFAULT_MAX_PARTICIPANTS,
+            max_retries: DEFAULT_MAX_RETRIES,
+            role_prefixed_tags: false,
+            select_opening_by_tags: false,
+            disambiguate_pronouns: false,
+            avoid_immediate_repeat: false,
+            cache_expansions: false,
+            trace_expansions: false,
+            markov_missing: MarkovMissingPolicy::default(),
+            substitute_pronouns: false,
+            voice_selectors: Vec::new(),
+            synonyms: None,
+            context_config: ContextConfig::default(),
+            repetition_window: None,
+            overuse_threshold: None,
+            min_significant_len: None,
+            monotony_stddev: None,
             grammars: None,
             voices: None,
             markov_models: None,
@@ -94,15 +237,77 @@ impl NarrativeEngine {
         }
     }
 
+    /// The base seed this engine was built with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number of passages generated so far. Together with `seed()`, this
+    /// determines the RNG state of the next narration.
+    pub fn generation_count(&self) -> u64 {
+        self.generation_count
+    }
+
+    /// Replace the engine's grammars in place, e.g. after editing a grammar
+    /// file. The repetition context and generation count are kept.
+    pub fn reload_grammars(&mut self, grammars: GrammarSet) {
+        self.grammars = grammars;
+    }
+
+    /// Merge `other` into the engine's grammars; rules in `other` override
+    /// same-named rules. See `GrammarSet::merge`.
+    pub fn merge_grammars(&mut self, other: GrammarSet) {
+        self.grammars.merge(other);
+    }
+
+    /// Capture the generation state for save games or deterministic replay.
+    pub fn snapshot(&self) -> EngineState {
+        EngineState {
+            seed: self.seed,
+            generation_count: self.generation_count,
+            context: self.context.clone(),
+        }
+    }
+
+    /// Restore a snapshot taken with `snapshot`. With the same content
+    /// loaded, the next narration matches what the snapshotted engine
+    /// would have produced.
+    pub fn restore(&mut self, state: EngineState) {
+        self.seed = state.seed;
+        self.generation_count = state.generation_count;
+        self.context = state.context;
+    }
+
+    /// What the repetition context currently tracks; see
+    /// `NarrativeContext::stats`.
+    pub fn context_stats(&self) -> ContextStats {
+        self.context.stats()
+    }
+
+    /// Clear the repetition context, e.g. at a scene or chapter break, so
+    /// earlier passages no longer suppress openings or words. The seed and
+    /// generation count are unaffected.
+    pub fn reset_context(&mut self) {
+        self.context.clear();
+    }
+
     /// Generate narration for an event using the first participant's voice.
     pub fn narrate(
         &mut self,
         event: &Event,
         world: &WorldState<'_>,
     ) -> Result<String, PipelineError> {
-        // Select voice from first participant
-        let voice_id = self.resolve_voice_id(event, world);
-        self.narrate_with_voice(event, voice_id, world)
+        Ok(self.narrate_detailed(event, world)?.text)
+    }
+
+    /// Like `narrate`, but also reports the resolved function, voice,
+    /// retry count, and any repetition issues left in the result.
+    pub fn narrate_detailed(
+        &mut self,
+        event: &Event,
+        world: &WorldState<'_>,
+    ) -> Result<Narration, PipelineError> {
+        self.narrate_with_voice(event, None, world)
     }
 
     /// Generate narration for an event using a specific voice.
@@ -112,6 +317,17 @@ impl NarrativeEngine {
         voice_id: VoiceId,
         world: &WorldState<'_>,
     ) -> Result<String, PipelineError> {
+        Ok(self.narrate_with_voice(event, Some(voice_id), world)?.text)
+    }
+
+    /// Like `narrate_as`, but returns the full `Narration`; see
+    /// `narrate_detailed`.
+    pub fn narrate_as_detailed(
+        &mut self,
+        event: &Event,
+        voice_id: VoiceId,
+        world: &WorldState<'_>,
+    ) -> Result<Narration, PipelineError> {
         self.narrate_with_voice(event, Some(voice_id), world)
     }
 
@@ -134,7 +350,141 @@ impl NarrativeEngine {
         Ok(results)
     }
 
-    fn resolve_voice_id(&self, event: &Event, world: &WorldState<'_>) -> Option<VoiceId> {
+    /// Generate up to `count` distinct variants for an event.
+    ///
+    /// Keeps trying new seed offsets until `count` different strings are
+    /// collected or `max_attempts` generations have been made, so a small
+    /// grammar returns fewer variants rather than looping forever.
+    pub fn narrate_unique_variants(
+        &mut self,
+        event: &Event,
+        count: usize,
+        max_attempts: usize,
+        world: &WorldState<'_>,
+    ) -> Result<Vec<String>, PipelineError> {
+        let mut results = Vec::with_capacity(count);
+        let saved_count = self.generation_count;
+        for attempt in 0..max_attempts {
+            if results.len() >= count {
+                break;
+            }
+            self.generation_count = saved_count + (attempt as u64 * 1000);
+            let result = self.narrate(event, world)?;
+            if !results.contains(&result) {
+                results.push(result);
+            }
+        }
+        self.generation_count = saved_count + 1;
+        Ok(results)
+    }
+
+    /// Generate narration for consecutive events, in order.
+    ///
+    /// Each passage is recorded in the repetition context before the next
+    /// is generated, so later events avoid the openings and overused words
+    /// of earlier ones — the same as calling `narrate` for each event.
+    /// Stops at the first error.
+    pub fn narrate_sequence(
+        &mut self,
+        events: &[Event],
+        world: &WorldState<'_>,
+    ) -> Result<Vec<String>, PipelineError> {
+        events
+            .iter()
+            .map(|event| self.narrate(event, world))
+            .collect()
+    }
+
+    /// Check that `event` is well formed: no duplicate non-empty roles, no
+    /// more than the configured number of participants, and every
+    /// participant and the location exist in `world`.
+    pub fn validate_event(
+        &self,
+        event: &Event,
+        world: &WorldState<'_>,
+    ) -> Result<(), PipelineError> {
+        self.validate_participants(event)?;
+        for entity_ref in event.participants.iter().chain(event.location.as_ref()) {
+            if !world.entities.contains_key(&entity_ref.entity_id) {
+                return Err(PipelineError::EntityNotFound(entity_ref.entity_id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Suggest a plausible object for `narrative_fn` with `subject`.
+    ///
+    /// Candidates are scored by relationships in either direction, scaled
+    /// by intensity: negative functions favour relationship types with a
+    /// negative `relationship_polarities` entry, positive ones types with a
+    /// positive entry. Unlisted types count as familiarity only. Ties go to
+    /// the lowest id. Returns
+    /// `None` if the subject is unknown or there are no other entities.
+    pub fn suggest_object(
+        &self,
+        subject: EntityId,
+        narrative_fn: &NarrativeFunction,
+        world: &WorldState<'_>,
+    ) -> Option<EntityId> {
+        let subject_entity = world.entities.get(&subject)?;
+        let valence = narrative_fn.valence();
+
+        let mut best: Option<(f32, EntityId)> = None;
+        for (&id, candidate) in world.entities {
+            if id == subject {
+                continue;
+            }
+            let edges = subject_entity
+                .relationships_to(id)
+                .chain(candidate.relationships_to(subject));
+            let score: f32 = edges
+                .map(|rel| {
+                    let polarity = self
+                        .relationship_polarities
+                        .get(&rel.rel_type)
+                        .copied()
+                        .unwrap_or(0.0);
+                    rel.intensity * (valence * polarity + FAMILIARITY_WEIGHT)
+                })
+                .sum();
+            let better = match best {
+                None => true,
+                Some((best_score, best_id)) => {
+                    score > best_score || (score == best_score && id.0 < best_id.0)
+                }
+            };
+            if better {
+                best = Some((score, id));
+            }
+        }
+        best.map(|(_, id)| id)
+    }
+
+    /// Structural checks run before every narration: the participant cap
+    /// and duplicate roles (which would otherwise silently overwrite
+    /// each other's bindings).
+    fn validate_participants(&self, event: &Event) -> Result<(), PipelineError> {
+        if event.participants.len() > self.max_participants {
+            return Err(PipelineError::TooManyParticipants {
+                count: event.participants.len(),
+                max: self.max_participants,
+            });
+        }
+        let mut roles = std::collections::HashSet::new();
+        for entity_ref in event.participants.iter().chain(event.location.as_ref()) {
+            if !entity_ref.role.is_empty() && !roles.insert(entity_ref.role.as_str()) {
+                return Err(PipelineError::DuplicateRole(entity_ref.role.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_voice_id(
+        &self,
+        event: &Event,
+        world: &WorldState<'_>,
+        tags: &FxHashSet<String>,
+    ) -> Option<VoiceId> {
         // Use first participant's voice_id
         for participant in &event.participants {
             if let Some(entity) = world.entities.get(&participant.entity_id) {
@@ -143,7 +493,30 @@ impl NarrativeEngine {
                 }
             }
         }
-        None
+
+        // Otherwise the first selector matching the event's tags
+        self.voice_selectors
+            .iter()
+            .find(|selector| selector.matches(tags))
+            .map(|selector| selector.voice)
+    }
+
+    /// Generate a single passage that blends several narrative functions,
+    /// e.g. a revelation that is also a betrayal.
+    ///
+    /// Each function's entry rule is expanded in order against one shared
+    /// context, and the pieces are joined with a connective. The passage is
+    /// recorded once. An empty `fns` slice narrates the event's own function.
+    pub fn narrate_compound(
+        &mut self,
+        event: &Event,
+        fns: &[NarrativeFunction],
+        world: &WorldState<'_>,
+    ) -> Result<String, PipelineError> {
+        if fns.is_empty() {
+            return self.narrate(event, world);
+        }
+        Ok(self.narrate_functions(event, fns, None, world)?.text)
     }
 
     fn narrate_with_voice(
@@ -151,74 +524,419 @@ impl NarrativeEngine {
         event: &Event,
         voice_id: Option<VoiceId>,
         world: &WorldState<'_>,
-    ) -> Result<String, PipelineError> {
-        let max_retries = 3u32;
+    ) -> Result<Narration, PipelineError> {
+        // 1. Resolve narrative function
+        let narrative_fn = self.resolve_narrative_fn(event);
+        self.narrate_functions(event, &[narrative_fn], voice_id, world)
+    }
+
+    fn narrate_functions(
+        &mut self,
+        event: &Event,
+        fns: &[NarrativeFunction],
+        voice_id: Option<VoiceId>,
+        world: &WorldState<'_>,
+    ) -> Result<Narration, PipelineError> {
+        if self.strict_entities {
+            self.validate_event(event, world)?;
+        } else {
+            self.validate_participants(event)?;
+        }
+
+        let max_retries = self.max_retries;
 
         for retry in 0..max_retries {
-            let mut rng = StdRng::seed_from_u64(
-                self.seed
-                    .wrapping_add(self.generation_count)
-                    .wrapping_add(retry as u64 * 7919), // prime offset per retry
-            );
+            let seed = self
+                .seed
+                .wrapping_add(self.generation_count)
+                .wrapping_add(retry as u64 * 7919); // prime offset per retry
+            let rendered = self.render(event, fns, voice_id, world, seed, &self.context)?;
+
+            // 8. Check for repetition
+            let issues = self.context.check_repetition(&rendered.text);
+            if issues.is_empty() || retry == max_retries - 1 {
+                // 9. Record and return
+                self.context.record(&rendered.text);
+                for (rule_name, index) in &rendered.entry_selections {
+                    self.context.record_alternative(rule_name, *index);
+                }
+                self.generation_count += 1;
+                return Ok(Narration {
+                    text: rendered.text,
+                    narrative_fn: fns[0].clone(),
+                    voice_id: rendered.voice_id,
+                    retries: retry,
+                    remaining_issues: issues,
+                    trace: rendered.trace,
+                });
+            }
+            // Retry with different seed offset
+        }
 
-            // 1. Resolve narrative function
-            let narrative_fn = self.resolve_narrative_fn(event);
+        Err(PipelineError::GenerationFailed(max_retries))
+    }
 
-            // 2. Build SelectionContext
-            let mut ctx = self.build_context(event, world, &narrative_fn);
+    /// Built-in narrative functions the loaded grammars can narrate (see
+    /// `GrammarSet::can_narrate`), in declaration order.
+    pub fn supported_functions(&self) -> Vec<NarrativeFunction> {
+        NarrativeFunction::BUILTIN
+            .into_iter()
+            .filter(|f| self.grammars.can_narrate(f))
+            .collect()
+    }
 
-            // 3-4. Resolve voice
-            let resolved_voice = voice_id.and_then(|id| self.voices.resolve(id));
-            if let Some(ref voice) = resolved_voice {
-                ctx.voice_weights = Some(&voice.grammar_weights);
+    /// Synthesize and narrate a random event, reproducibly from `seed`.
+    ///
+    /// Picks a supported function, a mood, stakes, and a subject from
+    /// `world` (plus an object via `suggest_object`), then narrates it as
+    /// `narrate_seeded` would: the text is rendered against a fresh
+    /// repetition context, so earlier narrations don't influence it. The
+    /// same seed, grammars, and world always yield the same event and
+    /// text. The result is still recorded in the engine's repetition
+    /// context, so later `narrate` calls avoid repeating it.
+    pub fn narrate_random(
+        &mut self,
+        world: &WorldState<'_>,
+        seed: u64,
+    ) -> Result<(Event, String), PipelineError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let fns = self.supported_functions();
+        if fns.is_empty() {
+            return Err(PipelineError::NoSupportedFunctions);
+        }
+        let narrative_fn = fns[rng.gen_range(0..fns.len())].clone();
+        let mood = Mood::ALL[rng.gen_range(0..Mood::ALL.len())];
+        let stakes = Stakes::ALL[rng.gen_range(0..Stakes::ALL.len())];
+
+        // Sorted so the pick doesn't depend on HashMap order
+        let mut ids: Vec<EntityId> = world.entities.keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+        let mut participants = Vec::new();
+        if !ids.is_empty() {
+            let subject = ids[rng.gen_range(0..ids.len())];
+            participants.push(EntityRef {
+                entity_id: subject,
+                role: "subject".to_string(),
+            });
+            if let Some(object) = self.suggest_object(subject, &narrative_fn, world) {
+                participants.push(EntityRef {
+                    entity_id: object,
+                    role: "object".to_string(),
+                });
             }
+        }
+
+        let event = Event {
+            event_type: narrative_fn.name().to_string(),
+            participants,
+            location: None,
+            mood,
+            stakes,
+            outcome: None,
+            narrative_fn: narrative_fn.clone(),
+            metadata: HashMap::new(),
+        };
+        let fresh = NarrativeContext::default();
+        let rendered = self.render(&event, &[narrative_fn], None, world, rng.gen(), &fresh)?;
+
+        self.context.record(&rendered.text);
+        for (rule_name, index) in &rendered.entry_selections {
+            self.context.record_alternative(rule_name, *index);
+        }
+        Ok((event, rendered.text))
+    }
+
+    /// Generate narration from an explicit seed, independent of call order.
+    ///
+    /// The same event, seed, and world always produce the same text. The
+    /// repetition context is neither consulted nor updated, and no retries
+    /// are made. Pair with `Event::content_seed` for per-event stability.
+    pub fn narrate_seeded(
+        &self,
+        event: &Event,
+        seed: u64,
+        world: &WorldState<'_>,
+    ) -> Result<String, PipelineError> {
+        if self.strict_entities {
+            self.validate_event(event, world)?;
+        } else {
+            self.validate_participants(event)?;
+        }
+        self.render_seeded(event, None, seed, world)
+    }
+
+    /// Generate one passage per event with `narrate_seeded` semantics,
+    /// seeding event `i` with `base_seed + i`. `voice_id` overrides the
+    /// participant's voice when given. Results are returned in event order;
+    /// like `narrate_seeded`, no cross-passage repetition checking is done.
+    pub fn narrate_batch(
+        &self,
+        events: &[Event],
+        voice_id: Option<VoiceId>,
+        base_seed: u64,
+        world: &WorldState<'_>,
+    ) -> Vec<Result<String, PipelineError>> {
+        events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| self.narrate_batch_entry(event, voice_id, base_seed, i, world))
+            .collect()
+    }
+
+    /// Like `narrate_batch`, but generates across threads with rayon. The
+    /// output is identical to `narrate_batch` for the same arguments.
+    #[cfg(feature = "parallel")]
+    pub fn narrate_batch_parallel(
+        &self,
+        events: &[Event],
+        voice_id: Option<VoiceId>,
+        base_seed: u64,
+        world: &WorldState<'_>,
+    ) -> Vec<Result<String, PipelineError>> {
+        use rayon::prelude::*;
+
+        events
+            .par_iter()
+            .enumerate()
+            .map(|(i, event)| self.narrate_batch_entry(event, voice_id, base_seed, i, world))
+            .collect()
+    }
+
+    fn narrate_batch_entry(
+        &self,
+        event: &Event,
+        voice_id: Option<VoiceId>,
+        base_seed: u64,
+        index: usize,
+        world: &WorldState<'_>,
+    ) -> Result<String, PipelineError> {
+        if self.strict_entities {
+            self.validate_event(event, world)?;
+        } else {
+            self.validate_participants(event)?;
+        }
+        self.render_seeded(event, voice_id, base_seed.wrapping_add(index as u64), world)
+    }
+
+    /// Render against an empty repetition context.
+    fn render_seeded(
+        &self,
+        event: &Event,
+        voice_id: Option<VoiceId>,
+        seed: u64,
+        world: &WorldState<'_>,
+    ) -> Result<String, PipelineError> {
+        let narrative_fn = self.resolve_narrative_fn(event);
+        let fresh = NarrativeContext::default();
+        let rendered = self.render(event, &[narrative_fn], voice_id, world, seed, &fresh)?;
+        Ok(rendered.text)
+    }
 
-            // Add markov model references to context
-            for (corpus_id, model) in &self.markov_models {
-                ctx.markov_models.insert(corpus_id.clone(), model);
+    /// Steps 2–7: build the context, expand, normalize, and apply the
+    /// variety pass, reading (but never updating) `history`. Without an
+    /// explicit `voice_id`, the voice comes from the participants or the
+    /// voice selectors.
+    fn render(
+        &self,
+        event: &Event,
+        fns: &[NarrativeFunction],
+        voice_id: Option<VoiceId>,
+        world: &WorldState<'_>,
+        seed: u64,
+        history: &NarrativeContext,
+    ) -> Result<Rendered, PipelineError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // 2. Build SelectionContext
+        let mut ctx = self.build_context(event, world, &fns[0]);
+
+        // 3-4. Resolve voice
+        let voice_id = voice_id.or_else(|| self.resolve_voice_id(event, world, &ctx.tags));
+        let resolved_voice = voice_id.and_then(|id| self.voices.resolve(id));
+        if let Some(ref voice) = resolved_voice {
+            ctx.voice_weights = Some(&voice.grammar_weights);
+            if self.markov_vocabulary_bias {
+                ctx.markov_bias = Some(TokenBias {
+                    preferred: &voice.vocabulary.preferred,
+                    avoided: &voice.vocabulary.avoided,
+                });
             }
+        }
 
-            // 5. Determine entry rule name
-            let rule_name = format!("{}_opening", narrative_fn.name());
+        // Add markov model references to context
+        for (corpus_id, model) in &self.markov_models {
+            ctx.markov_models.insert(corpus_id.clone(), model);
+        }
+
+        // The voice's own corpora take precedence for {markov:...} fills
+        if let Some(ref voice) = resolved_voice {
+            for binding in &voice.markov_bindings {
+                if let Some(model) = self.markov_models.get(&binding.corpus_id) {
+                    ctx.markov_bindings.push(BoundCorpus {
+                        model,
+                        weight: binding.weight,
+                        tags: &binding.tags,
+                    });
+                }
+            }
+        }
 
-            // 6. Expand grammar
-            let expanded = match self.grammars.expand(&rule_name, &mut ctx, &mut rng) {
-                Ok(text) => text,
-                Err(GrammarError::RuleNotFound(_)) => {
-                    // Try without _opening suffix
-                    match self
-                        .grammars
-                        .expand(narrative_fn.name(), &mut ctx, &mut rng)
-                    {
-                        Ok(text) => text,
-                        Err(e) => return Err(PipelineError::Grammar(e)),
+        // Steer entry rules away from the alternative used last time
+        if self.avoid_repeat_entries {
+            for narrative_fn in fns {
+                for rule_name in [
+                    format!("{}_opening", narrative_fn.name()),
+                    narrative_fn.name().to_string(),
+                ] {
+                    if let Some(index) = history.last_alternative(&rule_name) {
+                        ctx.avoid_alternatives.insert(rule_name, index);
                     }
                 }
-                Err(e) => return Err(PipelineError::Grammar(e)),
-            };
+            }
+        }
 
-            // 7. Run variety pass
-            let output = if let Some(ref voice) = resolved_voice {
-                VarietyPass::apply(&expanded, voice, &self.context, &mut rng)
-            } else {
-                expanded
-            };
+        let entity_names: Vec<&str> = ctx
+            .entity_bindings
+            .values()
+            .map(|e| e.name.as_str())
+            .collect();
+
+        // 5-6. Expand each function's entry rule, joining compound beats
+        let mut expanded = String::new();
+        for (i, narrative_fn) in fns.iter().enumerate() {
+            if i == 0 {
+                expanded = self.expand_entry(narrative_fn, &mut ctx, &mut rng)?;
+                continue;
+            }
+            // Each beat gets its own function's tags and intensity
+            let (previous, _) = self.function_tags(event, &fns[i - 1]);
+            for tag in &previous {
+                ctx.tags.remove(tag);
+            }
+            let (tags, intensity) = self.function_tags(event, narrative_fn);
+            ctx.tags.extend(tags);
+            ctx.intensity = intensity;
+            if let Some(cache) = ctx.expansion_cache.as_mut() {
+                cache.clear();
+            }
+            let part = self.expand_entry(narrative_fn, &mut ctx, &mut rng)?;
+            let connective = COMPOUND_CONNECTIVES[rng.gen_range(0..COMPOUND_CONNECTIVES.len())];
+            expanded.push(' ');
+            expanded.push_str(&prepend_lead_in(&part, connective, &entity_names));
+        }
 
-            // 8. Check for repetition
-            let issues = self.context.check_repetition(&output);
-            if issues.is_empty() || retry == max_retries - 1 {
-                // 9. Record and return
-                self.context.record(&output);
-                self.generation_count += 1;
-                return Ok(output);
+        // 6b. Repair artifacts where adjacent segments repeat a bound name
+        let expanded = normalize_output(&expanded, &entity_names);
+
+        // 7. Run variety pass
+        let output = if let Some(ref voice) = resolved_voice {
+            VarietyPass::apply(
+                &expanded,
+                voice,
+                history,
+                &self.synonyms,
+                &entity_names,
+                &mut rng,
+            )
+        } else {
+            expanded
+        };
+
+        // 7a. Swap repeated names for pronouns
+        let output = if self.substitute_pronouns {
+            let mut entities: Vec<(&str, &Pronouns)> = ctx
+                .entity_bindings
+                .values()
+                .map(|e| (e.name.as_str(), &e.pronouns))
+                .collect();
+            // Sorted so the result doesn't depend on HashMap order
+            entities.sort_by_key(|(name, _)| *name);
+            entities.dedup_by_key(|(name, _)| *name);
+            history.substitute_pronouns(&output, &entities)
+        } else {
+            output
+        };
+
+        // 7b. Enforce the sentence cap on the final passage
+        let text = match self.max_sentences {
+            Some(max) => truncate_sentences(&output, max),
+            None => output,
+        };
+
+        Ok(Rendered {
+            text,
+            entry_selections: ctx.entry_selections,
+            voice_id,
+            trace: ctx.trace.unwrap_or_default(),
+        })
+    }
+
+    /// Expand the entry rule for a narrative function: `{fn}_opening`,
+    /// falling back to a rule named after the function itself. With
+    /// `select_opening_by_tags`, a matching opening variant is picked first.
+    fn expand_entry(
+        &self,
+        narrative_fn: &NarrativeFunction,
+        ctx: &mut SelectionContext<'_>,
+        rng: &mut StdRng,
+    ) -> Result<String, PipelineError> {
+        if self.select_opening_by_tags {
+            if let Some(rule_name) = self.pick_opening(narrative_fn, ctx, rng) {
+                return Ok(self.grammars.expand(&rule_name, ctx, rng)?);
             }
-            // Retry with different seed offset
         }
 
-        Err(PipelineError::GenerationFailed(max_retries))
+        let rule_name = format!("{}_opening", narrative_fn.name());
+        match self.grammars.expand(&rule_name, ctx, rng) {
+            Ok(text) => Ok(text),
+            // Try without _opening suffix
+            Err(GrammarError::RuleNotFound(_)) => {
+                Ok(self.grammars.expand(narrative_fn.name(), ctx, rng)?)
+            }
+            Err(e) => Err(PipelineError::Grammar(e)),
+        }
+    }
+
+    /// Weight-pick among the `{fn}_*_opening` rules matching `ctx`, or
+    /// `None` if there are none.
+    fn pick_opening(
+        &self,
+        narrative_fn: &NarrativeFunction,
+        ctx: &SelectionContext<'_>,
+        rng: &mut StdRng,
+    ) -> Option<String> {
+        let prefix = format!("{}_", narrative_fn.name());
+        let mut candidates: Vec<(&str, u32)> = self
+            .grammars
+            .find_matching_rules(ctx)
+            .into_iter()
+            .filter(|rule| rule.name.starts_with(&prefix) && rule.name.ends_with("_opening"))
+            .map(|rule| {
+                let weight = rule.alternatives.iter().map(|a| a.weight).sum();
+                (rule.name.as_str(), weight)
+            })
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        // Sorted so the pick doesn't depend on HashMap order
+        candidates.sort();
+
+        let total: u32 = candidates.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0..total);
+        for (name, weight) in candidates {
+            if roll < weight {
+                return Some(name.to_string());
+            }
+            roll -= weight;
+        }
+        None
     }
 
-    fn resolve_narrative_fn(&self, event: &Event) -> NarrativeFunction {
+    /// The narrative function the engine will use for an event: the
+    /// mapping for its `event_type` if one exists, else `event.narrative_fn`.
+    pub fn resolve_narrative_fn(&self, event: &Event) -> NarrativeFunction {
         // Event can specify narrative_fn directly
         // Or look up from mappings table
         if let Some(mapped) = self.mappings.get(&event.event_type) {
@@ -228,6 +946,47 @@ impl NarrativeEngine {
         }
     }
 
+    /// The tags that depend on `narrative_fn` (mood, function, intensity,
+    /// and tone) and the combined intensity, for one beat of `event`.
+    fn function_tags(&self, event: &Event, narrative_fn: &NarrativeFunction) -> (Vec<String>, f32) {
+        // Neutral mood defers to the function's configured default, if any
+        let mood = match event.mood {
+            Mood::Neutral => self
+                .default_moods
+                .get(narrative_fn)
+                .copied()
+                .unwrap_or(Mood::Neutral),
+            mood => mood,
+        };
+        let mut tags = vec![
+            mood.tag().to_string(),
+            format!("fn:{}", narrative_fn.name()),
+        ];
+
+        // Add intensity-based tags
+        let intensity = narrative_fn.intensity();
+        if intensity >= 0.7 {
+            tags.push("intensity:high".to_string());
+        } else if intensity <= 0.3 {
+            tags.push("intensity:low".to_string());
+        }
+
+        // Outcome shifts the function's valence into an overall tone
+        let mut valence = narrative_fn.valence();
+        if let Some(outcome) = event.outcome {
+            valence += outcome.valence_modifier();
+        }
+        if valence <= -TONE_THRESHOLD {
+            tags.push("tone:grim".to_string());
+        } else if valence >= TONE_THRESHOLD {
+            tags.push("tone:hopeful".to_string());
+        }
+
+        // Combined intensity drives `intensity_scale` alternatives
+        let combined = (intensity + mood.intensity() + event.stakes.intensity()) / 3.0;
+        (tags, combined)
+    }
+
     fn build_context<'a>(
         &'a self,
         event: &Event,
@@ -235,21 +994,23 @@ impl NarrativeEngine {
         narrative_fn: &NarrativeFunction,
     ) -> SelectionContext<'a> {
         let mut ctx = SelectionContext::new();
+        ctx.disambiguate_pronouns = self.disambiguate_pronouns;
+        ctx.avoid_immediate_repeat = self.avoid_immediate_repeat;
+        if self.cache_expansions {
+            ctx.expansion_cache = Some(ExpansionCache::new());
+        }
+        if self.trace_expansions {
+            ctx.trace = Some(Vec::new());
+        }
+        ctx.markov_missing = self.markov_missing;
 
-        // Add mood and stakes as tags
-        ctx.tags.insert(event.mood.tag().to_string());
         ctx.tags.insert(event.stakes.tag().to_string());
-
-        // Add narrative function as tag
-        ctx.tags.insert(format!("fn:{}", narrative_fn.name()));
-
-        // Add intensity-based tags
-        let intensity = narrative_fn.intensity();
-        if intensity >= 0.7 {
-            ctx.tags.insert("intensity:high".to_string());
-        } else if intensity <= 0.3 {
-            ctx.tags.insert("intensity:low".to_string());
+        if let Some(outcome) = event.outcome {
+            ctx.tags.insert(outcome.tag().to_string());
         }
+        let (fn_tags, intensity) = self.function_tags(event, narrative_fn);
+        ctx.tags.extend(fn_tags);
+        ctx.intensity = intensity;
 
         // Add participant entity tags and bindings
         for (i, participant) in event.participants.iter().enumerate() {
@@ -268,6 +1029,52 @@ impl NarrativeEngine {
             }
         }
 
+        // Project the subject's relationships toward the object
+        if let (Some(subject), Some(object)) = (
+            ctx.entity_bindings.get("subject").copied(),
+            ctx.entity_bindings.get("object").copied(),
+        ) {
+            for relationship in subject.relationships_to(object.id) {
+                let bucket = if relationship.intensity >= 0.7 {
+                    "high"
+                } else if relationship.intensity <= 0.3 {
+                    "low"
+                } else {
+                    "medium"
+                };
+                ctx.tags.insert(format!("rel:{}", relationship.rel_type));
+                ctx.tags
+                    .insert(format!("rel:{}:{}", relationship.rel_type, bucket));
+                for tag in &relationship.tags {
+                    ctx.tags.insert(tag.clone());
+                }
+            }
+        }
+
+        // Overlay event metadata onto the subject's properties if enabled
+        if self.metadata_as_properties && ctx.entity_bindings.contains_key("subject") {
+            for (key, value) in &event.metadata {
+                if !matches!(value, Value::Bool(_)) {
+                    ctx.subject_overlay.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        // Project string and true-bool metadata into tags if enabled
+        if self.metadata_as_tags {
+            for (key, value) in &event.metadata {
+                match value {
+                    Value::String(s) => {
+                        ctx.tags.insert(format!("meta:{}:{}", key, s));
+                    }
+                    Value::Bool(true) => {
+                        ctx.tags.insert(format!("meta:{}", key));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         // Add location entity tags
         if let Some(ref location) = event.location {
             if let Some(entity) = world.entities.get(&location.entity_id) {
@@ -278,6 +1085,18 @@ impl NarrativeEngine {
             }
         }
 
+        // Role-qualified copies let rules tell whose tag it is
+        if self.role_prefixed_tags {
+            for (role, entity) in &ctx.entity_bindings {
+                if role.is_empty() {
+                    continue;
+                }
+                for tag in &entity.tags {
+                    ctx.tags.insert(format!("{}:{}", role, tag));
+                }
+            }
+        }
+
         ctx
     }
 }
@@ -313,43 +1132,240 @@ impl NarrativeEngineBuilder {
         self
     }
 
-    /// Provide grammars directly (for testing without files).
-    pub fn with_grammars(mut self, grammars: GrammarSet) -> Self {
-        self.grammars = Some(grammars);
+    /// Resolve `{entity.field}` from string/numeric event metadata before
+    /// falling back to the subject's own properties. Off by default.
+    pub fn metadata_as_properties(mut self, enabled: bool) -> Self {
+        self.metadata_as_properties = enabled;
         self
     }
 
-    /// Provide voices directly (for testing without files).
-    pub fn with_voices(mut self, voices: VoiceRegistry) -> Self {
-        self.voices = Some(voices);
+    /// Add event metadata to the context tags: string entries as
+    /// `meta:key:value`, `true` bools as `meta:key`. Off by default.
+    pub fn metadata_as_tags(mut self, enabled: bool) -> Self {
+        self.metadata_as_tags = enabled;
         self
     }
 
-    /// Provide markov models directly (for testing without files).
-    pub fn with_markov_models(mut self, models: HashMap<String, MarkovModel>) -> Self {
-        self.markov_models = Some(models);
+    /// Avoid selecting the same entry-rule alternative as the previous
+    /// passage that used that rule. Off by default.
+    pub fn avoid_repeat_entries(mut self, enabled: bool) -> Self {
+        self.avoid_repeat_entries = enabled;
         self
     }
 
-    /// Provide mappings directly (for testing without files).
-    pub fn with_mappings(mut self, mappings: HashMap<String, NarrativeFunction>) -> Self {
-        self.mappings = Some(mappings);
+    /// Bias `{markov:...}` fills toward the voice's preferred vocabulary
+    /// and away from its avoided words. Off by default.
+    pub fn markov_vocabulary_bias(mut self, enabled: bool) -> Self {
+        self.markov_vocabulary_bias = enabled;
         self
     }
 
-    pub fn build(self) -> Result<NarrativeEngine, PipelineError> {
-        let mut grammars = self.grammars.unwrap_or_default();
-        let mut voices = self.voices.unwrap_or_default();
-        let mut markov_models = self.markov_models.unwrap_or_default();
-        let mappings = self.mappings.unwrap_or_default();
-
+    /// Cap each passage at `max` complete sentences. `0` disables the cap.
+    pub fn max_sentences(mut self, max: usize) -> Self {
+        self.max_sentences = (max > 0).then_some(max);
+        self
+    }
+
+    /// Moods to use for events whose mood is `Neutral`, keyed by narrative
+    /// function (e.g. Betrayal → Somber). Affects tags and intensity.
+    pub fn default_moods(mut self, moods: HashMap<NarrativeFunction, Mood>) -> Self {
+        self.default_moods = moods;
+        self
+    }
+
+    /// How `suggest_object` reads relationship types, from -1.0 (hostile,
+    /// e.g. `"rival"`) to 1.0 (friendly, e.g. `"ally"`). Types not listed
+    /// count as neither. Empty by default.
+    pub fn relationship_polarities(mut self, polarities: HashMap<String, f32>) -> Self {
+        self.relationship_polarities = polarities;
+        self
+    }
+
+    /// Make narration fail with `EntityNotFound` when an event references
+    /// an entity missing from the world, instead of skipping it.
+    pub fn strict_entities(mut self, enabled: bool) -> Self {
+        self.strict_entities = enabled;
+        self
+    }
+
+    /// Also tag each bound entity's tags with its role, e.g.
+    /// `subject:secretive` and `object:armed`, alongside the flat tags.
+    pub fn role_prefixed_tags(mut self, enabled: bool) -> Self {
+        self.role_prefixed_tags = enabled;
+        self
+    }
+
+    /// Choose the entry rule among every `{fn}_*_opening` rule (including
+    /// `{fn}_opening`) whose tags match the event, weighted by each rule's
+    /// total alternative weight, instead of `{fn}_opening` by name.
+    pub fn select_opening_by_tags(mut self, enabled: bool) -> Self {
+        self.select_opening_by_tags = enabled;
+        self
+    }
+
+    /// When two bound entities share pronouns, write the referent's name
+    /// for a possessive or object pronoun (`Tom's` rather than `his`) if
+    /// the last entity mentioned with those pronouns was the other one.
+    /// Reflexives are left alone.
+    pub fn disambiguate_pronouns(mut self, enabled: bool) -> Self {
+        self.disambiguate_pronouns = enabled;
+        self
+    }
+
+    /// Within one narration, never expand a rule with the same alternative
+    /// twice in a row (when it has others). Unlike `avoid_repeat_entries`,
+    /// this applies to every rule but does not carry across passages.
+    pub fn avoid_immediate_repeat(mut self, enabled: bool) -> Self {
+        self.avoid_immediate_repeat = enabled;
+        self
+    }
+
+    /// Record a `TraceEntry` for every rule expanded, returned in
+    /// `Narration::trace` by `narrate_detailed`. Off by default.
+    pub fn trace_expansions(mut self, enabled: bool) -> Self {
+        self.trace_expansions = enabled;
+        self
+    }
+
+    /// Memoize pure alternatives (see `ExpansionCache`) within each
+    /// narration. Output is identical with or without the cache.
+    pub fn cache_expansions(mut self, enabled: bool) -> Self {
+        self.cache_expansions = enabled;
+        self
+    }
+
+    /// What `{markov:...}` segments expand to when their corpus isn't
+    /// loaded. Defaults to a visible `[markov:corpus:tag]` placeholder.
+    pub fn markov_missing_policy(mut self, policy: MarkovMissingPolicy) -> Self {
+        self.markov_missing = policy;
+        self
+    }
+
+    /// Replace sentence-initial repeat mentions of a bound entity's name
+    /// with its subject pronoun, unless another bound entity shares the
+    /// same pronouns. See `NarrativeContext::substitute_pronouns`.
+    pub fn substitute_pronouns(mut self, enabled: bool) -> Self {
+        self.substitute_pronouns = enabled;
+        self
+    }
+
+    /// Add a tag-based voice selector, used when no participant has a
+    /// voice. Selectors are tried in the order added; the first whose
+    /// required tags are all present on the event wins.
+    pub fn voice_selector(mut self, selector: VoiceSelector) -> Self {
+        self.voice_selectors.push(selector);
+        self
+    }
+
+    /// Extra synonyms for the variety pass, e.g. domain vocabulary. They
+    /// extend the built-in table; where both define a word, these win.
+    pub fn synonyms(mut self, synonyms: SynonymTable) -> Self {
+        self.synonyms = Some(synonyms);
+        self
+    }
+
+    /// Maximum participants per event (default 64). Larger events fail
+    /// with `TooManyParticipants`.
+    pub fn max_participants(mut self, max: usize) -> Self {
+        self.max_participants = max;
+        self
+    }
+
+    /// Attempts per narration before accepting a passage despite
+    /// repetition issues (default 3, minimum 1). With 1 the first
+    /// generation is always accepted.
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries.max(1);
+        self
+    }
+
+    /// Number of recent passages checked for repetition (default 10).
+    /// Zero disables repetition checking entirely.
+    pub fn repetition_window(mut self, size: usize) -> Self {
+        self.repetition_window = Some(size);
+        self
+    }
+
+    /// Occurrences of a significant word across the repetition window at
+    /// which it is flagged as overused (default 4). Lower values suit
+    /// lyrical prose; higher values tolerate terse, repetitive genres.
+    pub fn overuse_threshold(mut self, threshold: usize) -> Self {
+        self.overuse_threshold = Some(threshold);
+        self
+    }
+
+    /// Minimum character length for a word to count toward overuse
+    /// (default 5).
+    pub fn min_significant_len(mut self, len: usize) -> Self {
+        self.min_significant_len = Some(len);
+        self
+    }
+
+    /// Sentence-length standard deviation below which recent passages are
+    /// flagged as monotonous (default 2.0).
+    pub fn monotony_stddev(mut self, stddev: f64) -> Self {
+        self.monotony_stddev = Some(stddev);
+        self
+    }
+
+    /// Configure repetition tracking (window size, stopwords, thresholds).
+    /// Values set through `repetition_window`, `overuse_threshold`,
+    /// `min_significant_len`, or `monotony_stddev` take precedence.
+    pub fn context_config(mut self, config: ContextConfig) -> Self {
+        self.context_config = config;
+        self
+    }
+
+    /// Provide grammars directly (for testing without files).
+    pub fn with_grammars(mut self, grammars: GrammarSet) -> Self {
+        self.grammars = Some(grammars);
+        self
+    }
+
+    /// Provide voices directly (for testing without files). Merges into
+    /// any voices already added; on an id clash, these win.
+    pub fn with_voices(mut self, voices: VoiceRegistry) -> Self {
+        self.voices
+            .get_or_insert_with(VoiceRegistry::new)
+            .merge(voices);
+        self
+    }
+
+    /// Register a single voice. Repeated calls accumulate; voices are added
+    /// to any registry already given via `with_voices`.
+    pub fn with_voice(mut self, voice: Voice) -> Self {
+        self.voices
+            .get_or_insert_with(VoiceRegistry::new)
+            .register(voice);
+        self
+    }
+
+    /// Provide markov models directly (for testing without files).
+    pub fn with_markov_models(mut self, models: HashMap<String, MarkovModel>) -> Self {
+        self.markov_models = Some(models);
+        self
+    }
+
+    /// Provide mappings directly (for testing without files).
+    pub fn with_mappings(mut self, mappings: HashMap<String, NarrativeFunction>) -> Self {
+        self.mappings = Some(mappings);
+        self
+    }
+
+    pub fn build(self) -> Result<NarrativeEngine, PipelineError> {
+        let mut grammars = self.grammars.unwrap_or_default();
+        let mut voices = self.voices.unwrap_or_default();
+        let mut markov_models = self.markov_models.unwrap_or_default();
+        let mappings = self.mappings.unwrap_or_default();
+
         // Load genre templates
         for template_name in &self.genre_templates {
             let grammar_path = format!("genre_data/{}/grammar.ron", template_name);
-            if Path::new(&grammar_path).exists() {
-                let template_grammars = GrammarSet::load_from_ron(Path::new(&grammar_path))?;
-                grammars.merge(template_grammars);
+            if !Path::new(&grammar_path).exists() {
+                return Err(PipelineError::GenreTemplateNotFound(template_name.clone()));
             }
+            let template_grammars = GrammarSet::load_from_ron(Path::new(&grammar_path))?;
+            grammars.merge(template_grammars);
 
             let voices_path = format!("genre_data/{}/voices.ron", template_name);
             if Path::new(&voices_path).exists() {
@@ -381,8 +1397,12 @@ impl NarrativeEngineBuilder {
         // Load Markov models
         if let Some(ref dir) = self.markov_models_dir {
             if Path::new(dir).exists() {
-                load_ron_files_from_dir(dir, |path| {
-                    let model = crate::core::markov::load_model(path)?;
+                load_files_from_dir(dir, &["ron", "bin"], |path| {
+                    let model = if path.extension().and_then(|s| s.to_str()) == Some("bin") {
+                        crate::core::markov::load_model_bin(path)?
+                    } else {
+                        crate::core::markov::load_model(path)?
+                    };
                     let name = path
                         .file_stem()
                         .and_then(|s| s.to_str())
@@ -411,20 +1431,70 @@ impl NarrativeEngineBuilder {
             mappings
         };
 
+        let mut context_config = self.context_config;
+        if let Some(size) = self.repetition_window {
+            context_config.window_size = size;
+        }
+        if let Some(threshold) = self.overuse_threshold {
+            context_config.overuse_threshold = threshold;
+        }
+        if let Some(len) = self.min_significant_len {
+            context_config.min_significant_len = len;
+        }
+        if let Some(stddev) = self.monotony_stddev {
+            context_config.monotony_stddev = stddev;
+        }
+
+        let mut synonyms = SynonymTable::builtin();
+        if let Some(custom) = &self.synonyms {
+            synonyms.extend(custom);
+        }
+
         Ok(NarrativeEngine {
             grammars,
             voices,
             markov_models,
             mappings,
-            context: NarrativeContext::default(),
+            context: NarrativeContext::with_config(context_config),
             seed: self.seed,
             generation_count: 0,
+            metadata_as_properties: self.metadata_as_properties,
+            metadata_as_tags: self.metadata_as_tags,
+            avoid_repeat_entries: self.avoid_repeat_entries,
+            markov_vocabulary_bias: self.markov_vocabulary_bias,
+            max_sentences: self.max_sentences,
+            default_moods: self.default_moods,
+            relationship_polarities: self.relationship_polarities,
+            strict_entities: self.strict_entities,
+            max_participants: self.max_participants,
+            max_retries: self.max_retries,
+            role_prefixed_tags: self.role_prefixed_tags,
+            select_opening_by_tags: self.select_opening_by_tags,
+            disambiguate_pronouns: self.disambiguate_pronouns,
+            avoid_immediate_repeat: self.avoid_immediate_repeat,
+            cache_expansions: self.cache_expansions,
+            trace_expansions: self.trace_expansions,
+            markov_missing: self.markov_missing,
+            substitute_pronouns: self.substitute_pronouns,
+            voice_selectors: self.voice_selectors,
+            synonyms,
         })
     }
 }
 
 /// Load all .ron files from a directory, calling `loader` for each.
-fn load_ron_files_from_dir<F>(dir: &str, mut loader: F) -> Result<(), PipelineError>
+fn load_ron_files_from_dir<F>(dir: &str, loader: F) -> Result<(), PipelineError>
+where
+    F: FnMut(&Path) -> Result<(), PipelineError>,
+{
+    load_files_from_dir(dir, &["ron"], loader)
+}
+
+fn load_files_from_dir<F>(
+    dir: &str,
+    extensions: &[&str],
+    mut loader: F,
+) -> Result<(), PipelineError>
 where
     F: FnMut(&Path) -> Result<(), PipelineError>,
 {
@@ -432,20 +1502,186 @@ where
     for entry in entries {
         let entry = entry?;
         let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) == Some("ron") {
+        if path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| extensions.contains(&ext))
+        {
             loader(&path)?;
         }
     }
     Ok(())
 }
 
+/// Post-generation cleanup applied to expanded text before the variety pass.
+fn normalize_output(text: &str, entity_names: &[&str]) -> String {
+    let text = collapse_whitespace(text);
+    collapse_doubled_names(&text, entity_names)
+}
+
+/// Collapse runs of spaces into one and trim the ends — the leftovers of
+/// empty fills. Spacing around punctuation is left as written; dangling
+/// text around empty Markov fills is cleaned up during expansion.
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.trim().chars() {
+        if c == ' ' && result.ends_with(' ') {
+            continue;
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Abbreviations whose trailing period does not end a sentence.
+const ABBREVIATIONS: &[&str] = &["dr", "mr", "mrs", "ms", "st", "prof", "sgt", "capt", "lt"];
+
+/// Keep at most `max` complete sentences of `text`. A sentence ends at
+/// `.`, `!`, or `?` (plus any closing quotes or brackets) followed by
+/// whitespace or the end of the text, unless the next word is lowercase
+/// or the period follows a known abbreviation. Text with fewer sentences
+/// is returned unchanged.
+fn truncate_sentences(text: &str, max: usize) -> String {
+    let mut count = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        // Absorb runs of terminators ("?!", "...") and closing punctuation
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if matches!(next, '.' | '!' | '?' | '"' | '\'' | ')' | '”' | '’') {
+                end = j + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let at_boundary = chars.peek().is_none_or(|&(_, next)| next.is_whitespace());
+        if !at_boundary {
+            continue;
+        }
+        // A lowercase continuation means a dialogue tag ("Stop!" he said)
+        if text[end..]
+            .trim_start()
+            .chars()
+            .next()
+            .is_some_and(char::is_lowercase)
+        {
+            continue;
+        }
+        if c == '.' {
+            let word = text[..i]
+                .rsplit(|ch: char| !ch.is_alphabetic())
+                .next()
+                .unwrap_or("");
+            if ABBREVIATIONS.contains(&word.to_lowercase().as_str()) {
+                continue;
+            }
+        }
+        count += 1;
+        if count == max {
+            return text[..end].to_string();
+        }
+    }
+
+    text.to_string()
+}
+
+/// Collapse an immediate repeat of a bound entity name ("Margaret Margaret
+/// said") into a single occurrence. Matching is ASCII case-insensitive and
+/// whole-word; only names in `entity_names` are considered.
+fn collapse_doubled_names(text: &str, entity_names: &[&str]) -> String {
+    let mut names: Vec<&str> = entity_names
+        .iter()
+        .copied()
+        .filter(|n| !n.trim().is_empty())
+        .collect();
+    // Longest first so "Mary Ann" is handled before "Mary"
+    names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+    names.dedup();
+
+    let mut result = text.to_string();
+    for name in names {
+        while let Some(range) = find_doubled_name(&result, name) {
+            result.replace_range(range, "");
+        }
+    }
+    result
+}
+
+/// Find the byte range covering the whitespace and second occurrence of
+/// a doubled `name`, so that removing it leaves a single occurrence.
+fn find_doubled_name(text: &str, name: &str) -> Option<std::ops::Range<usize>> {
+    let is_word_char = |c: char| c.is_alphanumeric();
+    let matches_at = |pos: usize| {
+        text.get(pos..pos + name.len())
+            .is_some_and(|s| s.eq_ignore_ascii_case(name))
+    };
+
+    for (start, _) in text.char_indices() {
+        if !matches_at(start) {
+            continue;
+        }
+        if text[..start].chars().next_back().is_some_and(is_word_char) {
+            continue;
+        }
+        let first_end = start + name.len();
+        let rest = &text[first_end..];
+        let gap = rest.len() - rest.trim_start().len();
+        if gap == 0 {
+            continue;
+        }
+        let second = first_end + gap;
+        if !matches_at(second) {
+            continue;
+        }
+        let second_end = second + name.len();
+        if text[second_end..].chars().next().is_some_and(is_word_char) {
+            continue;
+        }
+        return Some(first_end..second_end);
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::context::RepetitionIssue;
     use crate::core::markov::MarkovTrainer;
-    use crate::core::voice::Voice;
-    use crate::schema::entity::Value;
-    use crate::schema::event::{EntityRef, Mood, Stakes};
+    use crate::schema::entity::Pronouns;
+    use crate::schema::event::{EntityRef, Outcome, Stakes};
+    use crate::schema::relationship::Relationship;
+
+    /// A parentless voice with no weights, vocabulary, bindings, or quirks.
+    fn test_voice(id: u64) -> Voice {
+        Voice {
+            id: VoiceId(id),
+            name: format!("voice{id}"),
+            parent: None,
+            grammar_weights: HashMap::new(),
+            vocabulary: crate::core::voice::VocabularyPool::default(),
+            markov_bindings: Vec::new(),
+            structure_prefs: crate::core::voice::StructurePrefs::default(),
+            quirks: Vec::new(),
+            synonym_overrides: HashMap::new(),
+        }
+    }
+
+    /// A test voice that always adds `quirk` and never asks questions, so
+    /// its output is recognizable.
+    fn quirky_voice(id: u64, quirk: &str) -> Voice {
+        let mut voice = test_voice(id);
+        voice.structure_prefs.question_frequency = 0.0;
+        voice.quirks.push(crate::core::voice::Quirk {
+            pattern: quirk.to_string(),
+            frequency: 1.0,
+        });
+        voice
+    }
 
     fn build_test_engine() -> NarrativeEngine {
         // Create minimal grammar
@@ -481,14 +1717,8 @@ mod tests {
         // Create a voice
         let mut voices = VoiceRegistry::new();
         voices.register(Voice {
-            id: VoiceId(1),
             name: "narrator".to_string(),
-            parent: None,
-            grammar_weights: HashMap::new(),
-            vocabulary: crate::core::voice::VocabularyPool::default(),
-            markov_bindings: Vec::new(),
-            structure_prefs: crate::core::voice::StructurePrefs::default(),
-            quirks: Vec::new(),
+            ..test_voice(1)
         });
 
         // Train a small Markov model
@@ -578,6 +1808,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn compound_beats_get_their_own_intensity() {
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(
+                GrammarSet::parse_ron(
+                    r#"{
+                "comic_relief_opening": Rule(requires: [], alternatives: [
+                    (weight: 1, text: "{?intensity:low:A light moment.|A heavy moment.}"),
+                ]),
+                "confrontation_opening": Rule(requires: [], alternatives: [
+                    (weight: 1, text: "{?intensity:high:tempers flared.|tempers held.}"),
+                ]),
+            }"#,
+                )
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let text = engine
+            .narrate_compound(
+                &event,
+                &[
+                    NarrativeFunction::ComicRelief,
+                    NarrativeFunction::Confrontation,
+                ],
+                &world,
+            )
+            .unwrap();
+        assert!(text.starts_with("A light moment. "), "{}", text);
+        assert!(text.ends_with("tempers flared."), "{}", text);
+    }
+
     #[test]
     fn narrate_deterministic_same_seed() {
         let (entities, event) = make_test_world();
@@ -696,9 +1963,1415 @@ mod tests {
         assert!(found_name, "Expected entity name in at least one narration");
     }
 
+    #[test]
+    fn doubled_entity_name_collapsed() {
+        let text = "Margaret Margaret said nothing. It was very very quiet.";
+        let normalized = normalize_output(text, &["Margaret", "James"]);
+        assert_eq!(normalized, "Margaret said nothing. It was very very quiet.");
+
+        // Case-insensitive, whole-word only, and possessives survive
+        assert_eq!(
+            normalize_output("margaret Margaret's glass", &["Margaret"]),
+            "margaret's glass"
+        );
+        assert_eq!(
+            normalize_output("Margaret Margareta arrived", &["Margaret"]),
+            "Margaret Margareta arrived"
+        );
+    }
+
+    #[test]
+    fn event_metadata_resolves_as_entity_field() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} raised {possessive} {entity.held_item}.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let (entities, mut event) = make_test_world();
+        event.metadata.insert(
+            "held_item".to_string(),
+            Value::String("wine glass".to_string()),
+        );
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(grammars.clone())
+            .metadata_as_properties(true)
+            .build()
+            .unwrap();
+        let text = engine.narrate(&event, &world).unwrap();
+        assert_eq!(text, "Margaret raised her wine glass.");
+
+        // Without the flag, metadata is not consulted
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(grammars)
+            .build()
+            .unwrap();
+        assert!(engine.narrate(&event, &world).is_err());
+    }
+
+    #[test]
+    fn avoid_repeat_entries_changes_consecutive_alternatives() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 100, text: "{subject} stood up abruptly."),
+                        (weight: 1, text: "A long pause preceded the storm."),
+                        (weight: 1, text: "Voices rose across the table."),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let mut engine = NarrativeEngine::builder()
+            .seed(7)
+            .with_grammars(grammars)
+            .avoid_repeat_entries(true)
+            .build()
+            .unwrap();
+
+        let first = engine.narrate(&event, &world).unwrap();
+        let first_index = engine.context.last_alternative("confrontation_opening");
+        let second = engine.narrate(&event, &world).unwrap();
+        let second_index = engine.context.last_alternative("confrontation_opening");
+
+        assert!(first_index.is_some());
+        assert_ne!(first_index, second_index);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn unique_variants_stop_at_grammar_limit() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "{subject} stood up abruptly."),
+                        (weight: 1, text: "Voices rose across the table."),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut engine = NarrativeEngine::builder()
+            .seed(5)
+            .with_grammars(grammars)
+            .build()
+            .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let variants = engine
+            .narrate_unique_variants(&event, 3, 20, &world)
+            .unwrap();
+        assert_eq!(variants.len(), 2, "{:?}", variants);
+        assert_ne!(variants[0], variants[1]);
+
+        let one = engine
+            .narrate_unique_variants(&event, 1, 20, &world)
+            .unwrap();
+        assert_eq!(one.len(), 1);
+        assert!(engine
+            .narrate_unique_variants(&event, 3, 0, &world)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn narrate_sequence_avoids_repeated_openings() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 100, text: "{subject} stood up abruptly."),
+                        (weight: 1, text: "A long pause preceded the storm."),
+                        (weight: 1, text: "Voices rose across the table."),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let build = || {
+            NarrativeEngine::builder()
+                .seed(7)
+                .with_grammars(grammars.clone())
+                .avoid_repeat_entries(true)
+                .build()
+                .unwrap()
+        };
+        let events = vec![event.clone(), event.clone(), event.clone()];
+
+        let mut engine = build();
+        let passages = engine.narrate_sequence(&events, &world).unwrap();
+        assert_eq!(passages.len(), 3);
+        assert_eq!(engine.generation_count(), 3);
+        for pair in passages.windows(2) {
+            let first: Vec<&str> = pair[0].split_whitespace().take(3).collect();
+            let second: Vec<&str> = pair[1].split_whitespace().take(3).collect();
+            assert_ne!(first, second, "{:?}", passages);
+        }
+
+        // Equivalent to narrating each event in turn
+        let mut single = build();
+        let one_by_one: Vec<String> = events
+            .iter()
+            .map(|e| single.narrate(e, &world).unwrap())
+            .collect();
+        assert_eq!(passages, one_by_one);
+    }
+
+    #[test]
+    fn narrate_detailed_reports_resolution() {
+        let mut engine = build_test_engine();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let first = engine.narrate_detailed(&event, &world).unwrap();
+        assert!(!first.text.is_empty());
+        assert_eq!(first.narrative_fn, NarrativeFunction::Confrontation);
+        assert_eq!(first.voice_id, Some(VoiceId(1)));
+        assert_eq!(first.retries, 0);
+        assert!(first.remaining_issues.is_empty());
+
+        // Repeats are retried; whatever is left over is reported
+        for _ in 0..4 {
+            let detail = engine.narrate_detailed(&event, &world).unwrap();
+            assert!(detail.retries < 3);
+            assert!(detail.remaining_issues.is_empty() || detail.retries == 2);
+        }
+    }
+
+    #[test]
+    fn disambiguate_pronouns_names_second_reference() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} glared at {object} and clenched {possessive} fist. {object} lowered {object_possessive} eyes.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut entities = HashMap::new();
+        entities.insert(
+            EntityId(1),
+            Entity::new(EntityId(1), "Tom", Pronouns::HeHim),
+        );
+        entities.insert(
+            EntityId(2),
+            Entity::new(EntityId(2), "Jim", Pronouns::HeHim),
+        );
+        let world = WorldState {
+            entities: &entities,
+        };
+        let (_, event) = make_test_world();
+        let build = |enabled: bool| {
+            NarrativeEngine::builder()
+                .with_grammars(grammars.clone())
+                .disambiguate_pronouns(enabled)
+                .build()
+                .unwrap()
+        };
+
+        // Only the reference following the other "he" is named
+        let text = build(true).narrate(&event, &world).unwrap();
+        assert_eq!(
+            text,
+            "Tom glared at Jim and clenched Tom's fist. Jim lowered his eyes."
+        );
+        let text = build(false).narrate(&event, &world).unwrap();
+        assert_eq!(
+            text,
+            "Tom glared at Jim and clenched his fist. Jim lowered his eyes."
+        );
+
+        // Distinct pronouns are already unambiguous
+        entities.get_mut(&EntityId(2)).unwrap().pronouns = Pronouns::SheHer;
+        let world = WorldState {
+            entities: &entities,
+        };
+        let text = build(true).narrate(&event, &world).unwrap();
+        assert_eq!(
+            text,
+            "Tom glared at Jim and clenched his fist. Jim lowered her eyes."
+        );
+    }
+
+    #[test]
+    fn substitute_pronouns_replaces_repeat_names() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} rose. {subject} faced {object}.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut entities = HashMap::new();
+        entities.insert(
+            EntityId(1),
+            Entity::new(EntityId(1), "Tom", Pronouns::HeHim),
+        );
+        entities.insert(
+            EntityId(2),
+            Entity::new(EntityId(2), "Ann", Pronouns::SheHer),
+        );
+        let (_, event) = make_test_world();
+        let build = || {
+            NarrativeEngine::builder()
+                .with_grammars(grammars.clone())
+                .substitute_pronouns(true)
+                .build()
+                .unwrap()
+        };
+
+        let world = WorldState {
+            entities: &entities,
+        };
+        let text = build().narrate(&event, &world).unwrap();
+        assert_eq!(text, "Tom rose. He faced Ann.");
+
+        // Two bound entities with the same pronouns keep their names
+        entities.get_mut(&EntityId(2)).unwrap().pronouns = Pronouns::HeHim;
+        let world = WorldState {
+            entities: &entities,
+        };
+        let text = build().narrate(&event, &world).unwrap();
+        assert_eq!(text, "Tom rose. Tom faced Ann.");
+    }
+
+    #[test]
+    fn max_retries_one_accepts_repetition() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} stood up abruptly.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let mut engine = NarrativeEngine::builder()
+            .seed(3)
+            .with_grammars(grammars)
+            .max_retries(1)
+            .build()
+            .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let first = engine.narrate(&event, &world).unwrap();
+        assert!(!engine.context.check_repetition(&first).is_empty());
+        let second = engine.narrate(&event, &world).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(engine.generation_count(), 2);
+    }
+
+    #[test]
+    fn max_retries_is_deterministic() {
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let run = || {
+            let mut engine = NarrativeEngine::builder()
+                .seed(11)
+                .with_grammars(
+                    GrammarSet::parse_ron(
+                        r#"{
+                    "confrontation_opening": Rule(
+                        requires: [],
+                        alternatives: [
+                            (weight: 1, text: "{subject} stood up abruptly."),
+                            (weight: 1, text: "Voices rose across the table."),
+                            (weight: 1, text: "A long pause preceded the storm."),
+                        ],
+                    ),
+                }"#,
+                    )
+                    .unwrap(),
+                )
+                .max_retries(6)
+                .build()
+                .unwrap();
+            (0..4)
+                .map(|_| engine.narrate(&event, &world).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn with_voice_accumulates_voices() {
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(
+                GrammarSet::parse_ron(
+                    r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} stood up.")],
+                ),
+            }"#,
+                )
+                .unwrap(),
+            )
+            .with_voice(quirky_voice(1, "mark my words"))
+            .with_voice(quirky_voice(2, "if you please"))
+            .build()
+            .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let text = engine.narrate_as(&event, VoiceId(1), &world).unwrap();
+        assert!(text.contains("mark my words"), "{}", text);
+        let text = engine.narrate_as(&event, VoiceId(2), &world).unwrap();
+        assert!(text.contains("if you please"), "{}", text);
+    }
+
+    #[test]
+    fn trace_expansions_fills_narration_trace() {
+        let build = |trace: bool| {
+            NarrativeEngine::builder()
+                .with_grammars(
+                    GrammarSet::parse_ron(
+                        r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} stood. {glare}")],
+                ),
+                "glare": Rule(requires: [], alternatives: [(weight: 1, text: "A glare.")]),
+            }"#,
+                    )
+                    .unwrap(),
+                )
+                .trace_expansions(trace)
+                .build()
+                .unwrap()
+        };
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let narration = build(true).narrate_detailed(&event, &world).unwrap();
+        let steps: Vec<(&str, u32, &str)> = narration
+            .trace
+            .iter()
+            .map(|t| (t.rule_name.as_str(), t.depth, t.output.as_str()))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                ("confrontation_opening", 0, "Margaret stood. A glare."),
+                ("glare", 1, "A glare."),
+            ]
+        );
+
+        let narration = build(false).narrate_detailed(&event, &world).unwrap();
+        assert!(narration.trace.is_empty());
+    }
+
+    #[test]
+    fn with_voices_keeps_earlier_voices() {
+        let mut registry = VoiceRegistry::new();
+        registry.register(test_voice(2));
+        let engine = NarrativeEngine::builder()
+            .with_voice(test_voice(1))
+            .with_voices(registry)
+            .build()
+            .unwrap();
+        assert!(engine.voices.get(VoiceId(1)).is_some());
+        assert!(engine.voices.get(VoiceId(2)).is_some());
+    }
+
+    #[test]
+    fn voice_selector_picks_narrator_by_mood() {
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(
+                GrammarSet::parse_ron(
+                    r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} stood up.")],
+                ),
+            }"#,
+                )
+                .unwrap(),
+            )
+            .with_voice(quirky_voice(203, "God help them"))
+            .with_voice(quirky_voice(204, "as it happened"))
+            .voice_selector(VoiceSelector {
+                requires: vec!["mood:dread".to_string()],
+                voice: VoiceId(203),
+            })
+            .voice_selector(VoiceSelector {
+                requires: Vec::new(),
+                voice: VoiceId(204),
+            })
+            .build()
+            .unwrap();
+        let (mut entities, mut event) = make_test_world();
+        for entity in entities.values_mut() {
+            entity.voice_id = None;
+        }
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        event.mood = Mood::Dread;
+        let narration = engine.narrate_detailed(&event, &world).unwrap();
+        assert_eq!(narration.voice_id, Some(VoiceId(203)));
+        assert!(
+            narration.text.contains("God help them"),
+            "{}",
+            narration.text
+        );
+
+        // First match wins; the catch-all covers everything else
+        event.mood = Mood::Warm;
+        let narration = engine.narrate_detailed(&event, &world).unwrap();
+        assert_eq!(narration.voice_id, Some(VoiceId(204)));
+    }
+
+    #[test]
+    fn relationship_tags_gate_rules() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "betrayal_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} turned away.")],
+                ),
+                "betrayal_lover_opening": Rule(
+                    requires: ["rel:lover"],
+                    alternatives: [(weight: 1, text: "{subject} broke {object}'s heart.")],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let engine = NarrativeEngine::builder()
+            .with_grammars(grammars)
+            .build()
+            .unwrap();
+        let (mut entities, mut event) = make_test_world();
+        let subject = event.participants[0].entity_id;
+        let object = event.participants[1].entity_id;
+
+        let world = WorldState {
+            entities: &entities,
+        };
+        let ctx = engine.build_context(&event, &world, &NarrativeFunction::Betrayal);
+        assert!(!ctx.tags.iter().any(|t| t.starts_with("rel:")));
+
+        entities
+            .get_mut(&subject)
+            .unwrap()
+            .relationships
+            .push(Relationship {
+                source: subject,
+                target: object,
+                rel_type: "lover".to_string(),
+                intensity: 0.9,
+                tags: ["secret".to_string()].into_iter().collect(),
+            });
+        let world = WorldState {
+            entities: &entities,
+        };
+        let ctx = engine.build_context(&event, &world, &NarrativeFunction::Betrayal);
+        for tag in ["rel:lover", "rel:lover:high", "secret"] {
+            assert!(ctx.tags.contains(tag), "missing {}", tag);
+        }
+        let lover_matches = |ctx: &SelectionContext<'_>| {
+            engine
+                .grammars
+                .find_matching_rules(ctx)
+                .iter()
+                .any(|rule| rule.name == "betrayal_lover_opening")
+        };
+        assert!(lover_matches(&ctx));
+
+        // Only subject -> object projects
+        event.participants[0].entity_id = object;
+        event.participants[1].entity_id = subject;
+        let ctx = engine.build_context(&event, &world, &NarrativeFunction::Betrayal);
+        assert!(!ctx.tags.contains("rel:lover"));
+        assert!(!lover_matches(&ctx));
+    }
+
+    #[test]
+    fn merged_grammar_rules_are_expandable() {
+        let mut engine = build_test_engine();
+        let (entities, mut event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        event.narrative_fn = NarrativeFunction::Discovery;
+        assert!(engine.narrate(&event, &world).is_err());
+
+        engine.merge_grammars(
+            GrammarSet::parse_ron(
+                r#"{
+                "discovery_opening": Rule(
+                    requires: [],
+                    alternatives: [(weight: 1, text: "{subject} found the letter.")],
+                ),
+            }"#,
+            )
+            .unwrap(),
+        );
+        assert!(engine
+            .narrate(&event, &world)
+            .unwrap()
+            .contains("found the letter"));
+        // Existing rules survive a merge
+        assert!(engine.grammars.rules.contains_key("revelation_opening"));
+
+        engine.reload_grammars(GrammarSet::default());
+        assert!(engine.supported_functions().is_empty());
+    }
+
+    #[test]
+    fn restored_snapshot_reproduces_next_narration() {
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let mut original = build_test_engine();
+        for _ in 0..3 {
+            original.narrate(&event, &world).unwrap();
+        }
+        let json = serde_json::to_string(&original.snapshot()).unwrap();
+
+        let mut restored = NarrativeEngine::builder()
+            .seed(7)
+            .with_grammars(original.grammars.clone())
+            .with_voices(original.voices.clone())
+            .build()
+            .unwrap();
+        restored.restore(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.snapshot(), original.snapshot());
+
+        for _ in 0..3 {
+            assert_eq!(
+                restored.narrate(&event, &world).unwrap(),
+                original.narrate(&event, &world).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn reset_context_forgets_previous_scene() {
+        let mut engine = build_test_engine();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let text = engine.narrate(&event, &world).unwrap();
+        assert!(!engine.context.check_repetition(&text).is_empty());
+
+        engine.reset_context();
+        assert!(engine.context.check_repetition(&text).is_empty());
+        assert_eq!(engine.generation_count(), 1);
+    }
+
+    #[test]
+    fn repetition_window_zero_disables_checks() {
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(
+                GrammarSet::parse_ron(
+                    r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "{subject} stood up abruptly."),
+                        (weight: 1, text: "Voices rose across the table."),
+                    ],
+                ),
+            }"#,
+                )
+                .unwrap(),
+            )
+            .repetition_window(0)
+            .avoid_repeat_entries(true)
+            .build()
+            .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        for _ in 0..5 {
+            let text = engine.narrate(&event, &world).unwrap();
+            assert!(engine.context.check_repetition(&text).is_empty());
+        }
+        assert_eq!(
+            engine.context.last_alternative("confrontation_opening"),
+            None
+        );
+    }
+
+    #[test]
+    fn repetition_window_survives_later_context_config() {
+        let mut engine = NarrativeEngine::builder()
+            .repetition_window(0)
+            .context_config(ContextConfig::default())
+            .build()
+            .unwrap();
+        for _ in 0..3 {
+            engine.context.record("Thunder rolled overhead.");
+        }
+        assert!(engine
+            .context
+            .check_repetition("Thunder rolled overhead.")
+            .is_empty());
+    }
+
+    #[test]
+    fn repetition_window_limits_tracked_passages() {
+        let build = |window: usize| {
+            NarrativeEngine::builder()
+                .with_grammars(
+                    GrammarSet::parse_ron(
+                        r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "{subject} stood up abruptly."),
+                        (weight: 1, text: "Voices rose across the table."),
+                    ],
+                ),
+            }"#,
+                    )
+                    .unwrap(),
+                )
+                .repetition_window(window)
+                .build()
+                .unwrap()
+        };
+        let passages = [
+            "Thunder rolled overhead.",
+            "Rain lashed the windows.",
+            "Someone laughed nervously.",
+            "Candles guttered low.",
+        ];
+        let first_opening = RepetitionIssue::RepeatedOpening("thunder rolled overhead.".into());
+
+        let mut small = build(3);
+        let mut default = build(10);
+        for text in passages {
+            small.context.record(text);
+            default.context.record(text);
+        }
+
+        // The first passage has slid out of a three-passage window
+        assert!(!small
+            .context
+            .check_repetition(passages[0])
+            .contains(&first_opening));
+        assert!(small
+            .context
+            .check_repetition(passages[1])
+            .contains(&RepetitionIssue::RepeatedOpening("rain lashed the".into())));
+        assert!(default
+            .context
+            .check_repetition(passages[0])
+            .contains(&first_opening));
+    }
+
+    #[test]
+    fn truncate_sentences_keeps_complete_sentences() {
+        let text = "Dr. Grant froze. \"Don't move!\" he hissed. Nothing stirred... Then a roar.";
+        assert_eq!(truncate_sentences(text, 1), "Dr. Grant froze.");
+        assert_eq!(
+            truncate_sentences(text, 2),
+            "Dr. Grant froze. \"Don't move!\" he hissed."
+        );
+        assert_eq!(truncate_sentences(text, 10), text);
+    }
+
+    #[test]
+    fn max_sentences_caps_passage() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "{subject} rose. The room went still. Glasses froze mid-air. Nobody spoke."),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(grammars)
+            .max_sentences(2)
+            .build()
+            .unwrap();
+        let text = engine.narrate(&event, &world).unwrap();
+        assert_eq!(text, "Margaret rose. The room went still.");
+    }
+
+    #[test]
+    fn missing_genre_template_is_an_error() {
+        let result = NarrativeEngine::builder()
+            .genre_templates(&["social_dramaa"])
+            .build();
+        assert!(matches!(
+            result,
+            Err(PipelineError::GenreTemplateNotFound(ref name)) if name == "social_dramaa"
+        ));
+
+        let engine = NarrativeEngine::builder()
+            .genre_templates(&["social_drama"])
+            .build()
+            .unwrap();
+        assert!(engine.grammars.rules.contains_key("revelation_opening"));
+    }
+
+    #[test]
+    fn empty_markov_fill_leaves_clean_spacing() {
+        // A degenerate model whose only token is empty yields whitespace fills
+        let mut empty_model = MarkovModel {
+            n: 2,
+            ..Default::default()
+        };
+        empty_model
+            .transitions
+            .insert(vec!["<S>".to_string()], vec![(String::new(), 1)]);
+        empty_model
+            .transitions
+            .insert(vec![String::new()], vec![("</S>".to_string(), 1)]);
+
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+                "confrontation_opening": Rule(
+                    requires: [],
+                    alternatives: [
+                        (weight: 1, text: "{subject} said {markov:empty:tense} and left. {subject} frowned, and {markov:empty:tense}."),
+                    ],
+                ),
+            }"#,
+        )
+        .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let mut engine = NarrativeEngine::builder()
+            .with_grammars(grammars)
+            .with_markov_models(HashMap::from([("empty".to_string(), empty_model)]))
+            .build()
+            .unwrap();
+        let text = engine.narrate(&event, &world).unwrap();
+        assert_eq!(text, "Margaret said and left. Margaret frowned.");
+    }
+
+    #[test]
+    fn normalize_collapses_whitespace() {
+        assert_eq!(
+            normalize_output("  She said  and left.  ", &[]),
+            "She said and left."
+        );
+        assert_eq!(normalize_output("The band.", &[]), "The band.");
+        // Sentence-final conjunctions in real prose are untouched
+        let prose = "No one else knew it yet. Medical supplies, more so. I think so!";
+        assert_eq!(normalize_output(prose, &[]), prose);
+        assert_eq!(normalize_output("Wait ... what ?", &[]), "Wait ... what ?");
+    }
+
+    #[test]
+    fn default_mood_applies_to_neutral_events() {
+        let engine = NarrativeEngine::builder()
+            .default_moods(HashMap::from([
+                (NarrativeFunction::Betrayal, Mood::Somber),
+                (NarrativeFunction::ComicRelief, Mood::Warm),
+            ]))
+            .build()
+            .unwrap();
+        let (entities, mut event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        event.mood = Mood::Neutral;
+        let ctx = engine.build_context(&event, &world, &NarrativeFunction::Betrayal);
+        assert!(ctx.tags.contains("mood:somber"));
+        assert!(!ctx.tags.contains("mood:neutral"));
+
+        // An explicit mood is never overridden
+        event.mood = Mood::Tense;
+        let ctx = engine.build_context(&event, &world, &NarrativeFunction::Betrayal);
+        assert!(ctx.tags.contains("mood:tense"));
+        assert!(!ctx.tags.contains("mood:somber"));
+    }
+
+    #[test]
+    fn role_prefixed_tags_distinguish_participants() {
+        let (mut entities, event) = make_test_world();
+        entities
+            .get_mut(&EntityId(1))
+            .unwrap()
+            .tags
+            .insert("secretive".to_string());
+        let world = WorldState {
+            entities: &entities,
+        };
+        let confrontation = NarrativeFunction::Confrontation;
+
+        let flat = build_test_engine();
+        let ctx = flat.build_context(&event, &world, &confrontation);
+        assert!(ctx.tags.contains("secretive"));
+        assert!(!ctx.tags.contains("subject:secretive"));
+
+        let prefixed = NarrativeEngine::builder()
+            .role_prefixed_tags(true)
+            .build()
+            .unwrap();
+        let ctx = prefixed.build_context(&event, &world, &confrontation);
+        assert!(ctx.tags.contains("secretive"));
+        assert!(ctx.tags.contains("subject:secretive"));
+        assert!(!ctx.tags.contains("object:secretive"));
+        assert!(ctx.tags.contains("object:guest"));
+    }
+
+    #[test]
+    fn outcome_sets_tone() {
+        let engine = build_test_engine();
+        let (entities, mut event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let revelation = NarrativeFunction::Revelation;
+
+        let ctx = engine.build_context(&event, &world, &revelation);
+        assert!(!ctx.tags.contains("tone:grim"));
+        assert!(!ctx.tags.contains("tone:hopeful"));
+
+        event.outcome = Some(Outcome::Failure);
+        let ctx = engine.build_context(&event, &world, &revelation);
+        assert!(ctx.tags.contains("outcome:failure"));
+        assert!(ctx.tags.contains("tone:grim"));
+
+        event.outcome = Some(Outcome::Success);
+        let ctx = engine.build_context(&event, &world, &revelation);
+        assert!(ctx.tags.contains("tone:hopeful"));
+
+        // A successful alliance stays hopeful; a failed one is merely neutral
+        event.outcome = Some(Outcome::Failure);
+        let ctx = engine.build_context(&event, &world, &NarrativeFunction::Alliance);
+        assert!(!ctx.tags.contains("tone:hopeful"));
+        assert!(!ctx.tags.contains("tone:grim"));
+    }
+
+    /// An engine whose only entry rule is a `{markov:...}` fill, with a
+    /// "sea" and a "garden" corpus loaded for `voices` to bind.
+    fn sea_garden_engine(voices: VoiceRegistry, seed: u64) -> NarrativeEngine {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+            "confrontation_opening": Rule(
+                requires: [],
+                excludes: [],
+                alternatives: [(weight: 1, text: "{markov:sea:dialogue}")],
+            ),
+        }"#,
+        )
+        .unwrap();
+        let sea = MarkovTrainer::train(
+            "The ship sailed over the grey waves. The ship sailed past the harbor \
+             lights. The crew sailed the ship through the storm.",
+            2,
+        );
+        let garden = MarkovTrainer::train(
+            "The roses bloomed along the garden wall. The roses bloomed beside the \
+             old fountain. The gardener tended the roses every morning.",
+            2,
+        );
+
+        NarrativeEngine::builder()
+            .seed(seed)
+            .with_grammars(grammars)
+            .with_voices(voices)
+            .with_markov_models(HashMap::from([
+                ("sea".to_string(), sea),
+                ("garden".to_string(), garden),
+            ]))
+            .build()
+            .unwrap()
+    }
+
+    fn unit_binding(corpus: &str) -> crate::core::voice::MarkovBinding {
+        crate::core::voice::MarkovBinding {
+            corpus_id: corpus.to_string(),
+            weight: 1.0,
+            tags: Vec::new(),
+        }
+    }
+
+    const SEA_WORDS: [&str; 3] = ["ship", "sailed", "crew"];
+    const GARDEN_WORDS: [&str; 3] = ["roses", "garden", "fountain"];
+
+    #[test]
+    fn voice_markov_bindings_choose_corpus() {
+        let mut voices = VoiceRegistry::new();
+        for (id, corpus) in [(10, "sea"), (11, "garden")] {
+            voices.register(Voice {
+                name: corpus.to_string(),
+                markov_bindings: vec![unit_binding(corpus)],
+                ..test_voice(id)
+            });
+        }
+
+        let mut engine = sea_garden_engine(voices, 7);
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let sailor = engine.narrate_as(&event, VoiceId(10), &world).unwrap();
+        let gardener = engine.narrate_as(&event, VoiceId(11), &world).unwrap();
+        let mentions = |text: &str, words: &[&str]| {
+            let lower = text.to_lowercase();
+            words.iter().any(|w| lower.contains(w))
+        };
+        assert!(mentions(&sailor, &SEA_WORDS), "{}", sailor);
+        assert!(!mentions(&sailor, &GARDEN_WORDS), "{}", sailor);
+        assert!(mentions(&gardener, &GARDEN_WORDS), "{}", gardener);
+        assert!(!mentions(&gardener, &SEA_WORDS), "{}", gardener);
+    }
+
+    #[test]
+    fn voice_with_two_bindings_blends_corpora() {
+        let mut voices = VoiceRegistry::new();
+        for (id, bindings) in [
+            (10, vec![unit_binding("sea")]),
+            (11, vec![unit_binding("garden")]),
+            (12, vec![unit_binding("sea"), unit_binding("garden")]),
+        ] {
+            voices.register(Voice {
+                markov_bindings: bindings,
+                ..test_voice(id)
+            });
+        }
+
+        let engine = sea_garden_engine(voices, 0);
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let events = vec![event; 30];
+        let narrate = |voice: u64| -> Vec<String> {
+            engine
+                .narrate_batch(&events, Some(VoiceId(voice)), 0, &world)
+                .into_iter()
+                .map(Result::unwrap)
+                .collect()
+        };
+        let (sailor, gardener, blended) = (narrate(10), narrate(11), narrate(12));
+
+        let mentions = |texts: &[String], words: &[&str]| {
+            texts.iter().any(|text| {
+                let lower = text.to_lowercase();
+                words.iter().any(|w| lower.contains(w))
+            })
+        };
+        assert!(mentions(&blended, &SEA_WORDS));
+        assert!(mentions(&blended, &GARDEN_WORDS));
+        assert!(blended != sailor && blended != gardener);
+        // Blending is as reproducible as single-corpus generation
+        assert_eq!(blended, narrate(12));
+    }
+
+    #[test]
+    fn narrate_random_is_reproducible() {
+        let (entities, _) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let mut engine = build_test_engine();
+        let supported = engine.supported_functions();
+        assert!(supported.contains(&NarrativeFunction::Confrontation));
+        assert!(!supported.contains(&NarrativeFunction::Loss));
+
+        for seed in 0..20 {
+            let (event, text) = engine.narrate_random(&world, seed).unwrap();
+            assert!(supported.contains(&event.narrative_fn));
+            assert!(!text.is_empty());
+
+            let (again, again_text) = build_test_engine().narrate_random(&world, seed).unwrap();
+            assert_eq!(again.narrative_fn, event.narrative_fn);
+            assert_eq!(again.mood, event.mood);
+            assert_eq!(again.stakes, event.stakes);
+            assert_eq!(again.content_seed(), event.content_seed());
+            assert_eq!(again_text, text);
+        }
+
+        let empty = NarrativeEngine::builder().build().unwrap();
+        assert!(empty.supported_functions().is_empty());
+
+        // An entry rule with no alternatives can't narrate
+        let mut hollow = NarrativeEngine::builder()
+            .with_grammars(
+                GrammarSet::parse_ron(
+                    r#"{ "loss_opening": Rule(requires: [], alternatives: []) }"#,
+                )
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert!(hollow.supported_functions().is_empty());
+        assert!(matches!(
+            hollow.narrate_random(&world, 0),
+            Err(PipelineError::NoSupportedFunctions)
+        ));
+    }
+
+    #[test]
+    fn select_opening_by_tags_picks_among_matching_rules() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+            "confrontation_opening": Rule(
+                requires: [],
+                excludes: [],
+                alternatives: [(weight: 1, text: "Plain opening.")],
+            ),
+            "confrontation_tense_opening": Rule(
+                requires: ["mood:tense"],
+                excludes: [],
+                alternatives: [(weight: 1, text: "Tense opening.")],
+            ),
+            "confrontation_warm_opening": Rule(
+                requires: ["mood:warm"],
+                excludes: [],
+                alternatives: [(weight: 1, text: "Warm opening.")],
+            ),
+        }"#,
+        )
+        .unwrap();
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+
+        let by_tags = NarrativeEngine::builder()
+            .with_grammars(grammars.clone())
+            .select_opening_by_tags(true)
+            .build()
+            .unwrap();
+        let seen: std::collections::HashSet<String> = (0..30)
+            .map(|seed| by_tags.narrate_seeded(&event, seed, &world).unwrap())
+            .collect();
+        assert!(seen.contains("Plain opening."));
+        assert!(seen.contains("Tense opening."));
+        assert!(!seen.contains("Warm opening."));
+
+        // Default: exact `{fn}_opening` only
+        let by_name = NarrativeEngine::builder()
+            .with_grammars(grammars)
+            .build()
+            .unwrap();
+        for seed in 0..10 {
+            assert_eq!(
+                by_name.narrate_seeded(&event, seed, &world).unwrap(),
+                "Plain opening."
+            );
+        }
+    }
+
+    #[test]
+    fn metadata_tags_gate_rules() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+            "confrontation_opening": Rule(
+                requires: [],
+                excludes: [],
+                alternatives: [(weight: 1, text: "{weather}")],
+            ),
+            "weather": Rule(
+                requires: [],
+                excludes: [],
+                alternatives: [(weight: 1, text: "The room was still.")],
+            ),
+            "storm_weather": Rule(
+                requires: ["meta:weather:storm"],
+                excludes: [],
+                alternatives: [(weight: 1, text: "Rain lashed the windows.")],
+            ),
+        }"#,
+        )
+        .unwrap();
+        let engine = NarrativeEngine::builder()
+            .with_grammars(grammars)
+            .metadata_as_tags(true)
+            .build()
+            .unwrap();
+        let (entities, mut event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let confrontation = NarrativeFunction::Confrontation;
+
+        let matching = |ctx: &SelectionContext<'_>| {
+            engine
+                .grammars
+                .find_matching_rules(ctx)
+                .iter()
+                .any(|r| r.name == "storm_weather")
+        };
+        assert!(!matching(&engine.build_context(
+            &event,
+            &world,
+            &confrontation
+        )));
+
+        event
+            .metadata
+            .insert("weather".to_string(), Value::String("storm".to_string()));
+        event
+            .metadata
+            .insert("witnessed".to_string(), Value::Bool(true));
+        event.metadata.insert("guests".to_string(), Value::Int(6));
+        let ctx = engine.build_context(&event, &world, &confrontation);
+        assert!(matching(&ctx));
+        assert!(ctx.tags.contains("meta:witnessed"));
+        assert!(!ctx.tags.iter().any(|t| t.starts_with("meta:guests")));
+    }
+
+    #[test]
+    fn outcome_tag_gates_closing_rules() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+            "loss_closing": Rule(
+                requires: ["outcome:failure"],
+                excludes: [],
+                alternatives: [(weight: 1, text: "Nothing could be saved.")],
+            ),
+        }"#,
+        )
+        .unwrap();
+        let engine = NarrativeEngine::builder()
+            .with_grammars(grammars)
+            .build()
+            .unwrap();
+        let (entities, mut event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let loss = NarrativeFunction::Loss;
+        let closing_matches = |event: &Event| {
+            let ctx = engine.build_context(event, &world, &loss);
+            let matched = !engine.grammars.find_matching_rules(&ctx).is_empty();
+            (matched, ctx.tags.iter().any(|t| t.starts_with("outcome:")))
+        };
+
+        assert_eq!(closing_matches(&event), (false, false));
+        event.outcome = Some(Outcome::Partial);
+        assert_eq!(closing_matches(&event), (false, true));
+        event.outcome = Some(Outcome::Failure);
+        assert_eq!(closing_matches(&event), (true, true));
+    }
+
+    #[test]
+    fn validate_event_reports_missing_entity() {
+        let (entities, mut event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let mut engine = build_test_engine();
+        assert!(engine.validate_event(&event, &world).is_ok());
+
+        event.participants[1].entity_id = EntityId(99);
+        let err = engine.validate_event(&event, &world).unwrap_err();
+        assert!(matches!(err, PipelineError::EntityNotFound(EntityId(99))));
+        assert!(err.to_string().contains("99"));
+
+        // Lenient by default: narration still succeeds
+        assert!(engine.narrate(&event, &world).is_ok());
+
+        let mut strict = NarrativeEngine::builder()
+            .with_grammars(engine.grammars.clone())
+            .strict_entities(true)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            strict.narrate(&event, &world),
+            Err(PipelineError::EntityNotFound(EntityId(99)))
+        ));
+    }
+
+    #[test]
+    fn narrate_seeded_ignores_call_order() {
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let mut engine = build_test_engine();
+
+        let seed = event.content_seed();
+        let first = engine.narrate_seeded(&event, seed, &world).unwrap();
+        for _ in 0..3 {
+            engine.narrate(&event, &world).unwrap();
+        }
+        let again = engine.narrate_seeded(&event, seed, &world).unwrap();
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn narrate_batch_seeds_by_index() {
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let engine = build_test_engine();
+        let events = vec![event.clone(); 8];
+
+        let batch = engine.narrate_batch(&events, None, 100, &world);
+        for (i, text) in batch.into_iter().enumerate() {
+            let expected = engine
+                .narrate_seeded(&event, 100 + i as u64, &world)
+                .unwrap();
+            assert_eq!(text.unwrap(), expected);
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn narrate_batch_parallel_matches_serial() {
+        let (entities, event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let engine = build_test_engine();
+        let events = vec![event; 200];
+
+        let serial: Vec<String> = engine
+            .narrate_batch(&events, None, 7, &world)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        let parallel: Vec<String> = engine
+            .narrate_batch_parallel(&events, None, 7, &world)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn suggest_object_prefers_rival_for_confrontation() {
+        let (mut entities, _) = make_test_world();
+        // James (2) is a bystander; Victor (3) is Margaret's rival
+        let mut victor = entities[&EntityId(2)].clone();
+        victor.id = EntityId(3);
+        victor.name = "Victor".to_string();
+        entities.insert(EntityId(3), victor);
+        entities.get_mut(&EntityId(1)).unwrap().relationships.push(
+            crate::schema::relationship::Relationship::new(
+                EntityId(1),
+                EntityId(3),
+                "rival".to_string(),
+                0.9,
+                Default::default(),
+            ),
+        );
+        let world = WorldState {
+            entities: &entities,
+        };
+        // Without a polarity map the rival only counts as familiarity
+        let engine = build_test_engine();
+        assert_eq!(
+            engine.suggest_object(EntityId(1), &NarrativeFunction::Alliance, &world),
+            Some(EntityId(3))
+        );
+
+        let engine = NarrativeEngine::builder()
+            .with_grammars(GrammarSet::default())
+            .relationship_polarities(HashMap::from([("rival".to_string(), -1.0)]))
+            .build()
+            .unwrap();
+        assert_eq!(
+            engine.suggest_object(EntityId(1), &NarrativeFunction::Confrontation, &world),
+            Some(EntityId(3))
+        );
+        // A friendly function steers away from the rival
+        assert_eq!(
+            engine.suggest_object(EntityId(1), &NarrativeFunction::Alliance, &world),
+            Some(EntityId(2))
+        );
+        assert_eq!(
+            engine.suggest_object(EntityId(99), &NarrativeFunction::Confrontation, &world),
+            None
+        );
+    }
+
+    #[test]
+    fn duplicate_roles_and_participant_cap_detected() {
+        let (entities, mut event) = make_test_world();
+        let world = WorldState {
+            entities: &entities,
+        };
+        let mut engine = build_test_engine();
+
+        event.participants[1].role = "subject".to_string();
+        assert!(matches!(
+            engine.validate_event(&event, &world),
+            Err(PipelineError::DuplicateRole(ref role)) if role == "subject"
+        ));
+        assert!(matches!(
+            engine.narrate(&event, &world),
+            Err(PipelineError::DuplicateRole(_))
+        ));
+
+        let (_, event) = make_test_world();
+        let mut capped = NarrativeEngine::builder()
+            .with_grammars(engine.grammars.clone())
+            .max_participants(1)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            capped.narrate(&event, &world),
+            Err(PipelineError::TooManyParticipants { count: 2, max: 1 })
+        ));
+    }
+
     #[test]
     fn builder_with_seed() {
         let engine = NarrativeEngine::builder().seed(12345).build().unwrap();
         assert_eq!(engine.seed, 12345);
+        assert_eq!(engine.seed(), 12345);
+        assert_eq!(engine.generation_count(), 0);
     }
 }
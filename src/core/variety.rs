@@ -1,39 +1,196 @@
 /// Variety pass — post-processing transforms for text quality.
 ///
-/// Includes synonym rotation, quirk injection, and repetition remediation.
+/// Includes synonym rotation, preferred-word substitution, quirk injection,
+/// sentence length shaping, rhetorical questions, and repetition
+/// remediation.
 use rand::rngs::StdRng;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
 use super::context::{NarrativeContext, RepetitionIssue};
 use super::voice::ResolvedVoice;
 
+#[derive(Debug, thiserror::Error)]
+pub enum SynonymError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("RON deserialization error: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// Word → alternatives used by synonym rotation, preferred-word
+/// substitution, and repetition remediation. Keys are lowercase.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SynonymTable {
+    entries: HashMap<String, Vec<String>>,
+}
+
+impl SynonymTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in table of common overused words.
+    pub fn builtin() -> Self {
+        let mut table = Self::new();
+        for (word, alternatives) in build_synonym_table() {
+            table.insert(word, &alternatives);
+        }
+        table
+    }
+
+    /// Parse a RON map of `"word": ["alternative", ...]` entries.
+    pub fn parse_ron(input: &str) -> Result<Self, SynonymError> {
+        let raw: HashMap<String, Vec<String>> = ron::from_str(input)?;
+        let mut table = Self::new();
+        for (word, alternatives) in raw {
+            table.entries.insert(word.to_lowercase(), alternatives);
+        }
+        Ok(table)
+    }
+
+    /// Load a synonym table from a RON file. See `parse_ron`.
+    pub fn load_from_ron(path: &Path) -> Result<Self, SynonymError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse_ron(&contents)
+    }
+
+    /// Set the alternatives for `word`, replacing any existing entry.
+    pub fn insert(&mut self, word: &str, alternatives: &[&str]) {
+        self.entries.insert(
+            word.to_lowercase(),
+            alternatives.iter().map(|a| a.to_string()).collect(),
+        );
+    }
+
+    /// Alternatives for a word (case-insensitive).
+    pub fn get(&self, word: &str) -> Option<&[String]> {
+        self.entries
+            .get(&word.to_lowercase())
+            .map(|alternatives| alternatives.as_slice())
+    }
+
+    /// Add every entry from `other`; where both define a word, `other` wins.
+    pub fn extend(&mut self, other: &SynonymTable) {
+        for (word, alternatives) in &other.entries {
+            self.entries.insert(word.clone(), alternatives.clone());
+        }
+    }
+
+    /// Every word with alternatives, sorted.
+    fn words(&self) -> Vec<&str> {
+        let mut words: Vec<&str> = self.entries.keys().map(String::as_str).collect();
+        words.sort();
+        words
+    }
+}
+
+/// A synonym table seen through a voice's `synonym_overrides`, which win
+/// where both define a word. Override keys are lowercase, as
+/// `ResolvedVoice` stores them.
+#[derive(Debug, Clone, Copy)]
+struct Synonyms<'a> {
+    table: &'a SynonymTable,
+    overrides: Option<&'a HashMap<String, Vec<String>>>,
+}
+
+impl<'a> From<&'a SynonymTable> for Synonyms<'a> {
+    fn from(table: &'a SynonymTable) -> Self {
+        Self {
+            table,
+            overrides: None,
+        }
+    }
+}
+
+impl<'a> Synonyms<'a> {
+    /// Alternatives for a word (case-insensitive).
+    fn get(&self, word: &str) -> Option<&'a [String]> {
+        let lower = word.to_lowercase();
+        self.overrides
+            .and_then(|overrides| overrides.get(&lower))
+            .or_else(|| self.table.entries.get(&lower))
+            .map(Vec::as_slice)
+    }
+
+    /// Every word with alternatives, sorted.
+    fn words(&self) -> Vec<&'a str> {
+        let mut words = self.table.words();
+        if let Some(overrides) = self.overrides {
+            words.extend(overrides.keys().map(String::as_str));
+            words.sort();
+            words.dedup();
+        }
+        words
+    }
+}
+
 /// The variety pass applied to generated text before final output.
 pub struct VarietyPass;
 
 impl VarietyPass {
     /// Apply all variety transforms in order:
     /// 1. Synonym rotation (for avoided words)
-    /// 2. Quirk injection
-    /// 3. Repetition remediation
+    /// 2. Preferred-word substitution
+    /// 3. Quirk injection
+    /// 4. Sentence length shaping (to `structure_prefs.avg_sentence_length`,
+    ///    if `structure_prefs.shape_sentences` is set)
+    /// 5. Rhetorical question (at `structure_prefs.question_frequency`)
+    /// 6. Repetition remediation
+    ///
+    /// Synonyms come from `synonyms` (normally the built-in table merged
+    /// with any custom one), extended by the voice's `synonym_overrides`,
+    /// which win where both define a word. Words that get lowercased behind a
+    /// lead-in keep their capital only if they belong to one of `names`.
     pub fn apply(
         text: &str,
         voice: &ResolvedVoice,
         ctx: &NarrativeContext,
+        synonyms: &SynonymTable,
+        names: &[&str],
         rng: &mut StdRng,
     ) -> String {
+        let synonyms = Synonyms {
+            table: synonyms,
+            overrides: Some(&voice.synonym_overrides),
+        };
         let mut result = text.to_string();
 
         // 1. Synonym rotation for avoided words
-        result = rotate_avoided_words(&result, &voice.vocabulary.avoided, rng);
+        result = rotate_avoided_words(&result, &voice.vocabulary.avoided, synonyms, rng);
+
+        // 2. Swap toward the voice's preferred words
+        result = prefer_vocabulary(
+            &result,
+            &voice.vocabulary.preferred,
+            &voice.vocabulary.avoided,
+            synonyms,
+        );
 
-        // 2. Quirk injection
+        // 3. Quirk injection
         result = inject_quirks(&result, &voice.quirks, rng);
 
-        // 3. Repetition remediation
+        // 4. Fit sentences to the voice's preferred length range
+        if voice.structure_prefs.shape_sentences {
+            result =
+                shape_sentence_lengths(&result, voice.structure_prefs.avg_sentence_length, names);
+        }
+
+        // 5. Occasionally turn the closing statement into a question
+        result = inject_question(
+            &result,
+            voice.structure_prefs.question_frequency,
+            names,
+            rng,
+        );
+
+        // 6. Repetition remediation
         let issues = ctx.check_repetition(&result);
         if !issues.is_empty() {
-            result = remediate_repetition(&result, &issues, rng);
+            result = remediate_repetition(&result, &issues, synonyms, names, rng);
         }
 
         result
@@ -44,20 +201,19 @@ impl VarietyPass {
 fn rotate_avoided_words(
     text: &str,
     avoided: &rustc_hash::FxHashSet<String>,
+    synonyms: Synonyms<'_>,
     rng: &mut StdRng,
 ) -> String {
     if avoided.is_empty() {
         return text.to_string();
     }
 
-    let synonyms = build_synonym_table();
     let mut result = text.to_string();
 
     for word in avoided {
-        let word_lower = word.to_lowercase();
-        if let Some(alternatives) = synonyms.get(word_lower.as_str()) {
+        if let Some(alternatives) = synonyms.get(word) {
             if !alternatives.is_empty() {
-                let replacement = alternatives[rng.gen_range(0..alternatives.len())];
+                let replacement = &alternatives[rng.gen_range(0..alternatives.len())];
                 // Case-preserving replacement
                 result = replace_word_preserving_case(&result, word, replacement);
             }
@@ -67,6 +223,38 @@ fn rotate_avoided_words(
     result
 }
 
+/// Replace words that have a synonym in the voice's preferred set with
+/// that synonym. Words that are themselves preferred are left alone, and
+/// avoided synonyms are never chosen.
+fn prefer_vocabulary(
+    text: &str,
+    preferred: &rustc_hash::FxHashSet<String>,
+    avoided: &rustc_hash::FxHashSet<String>,
+    synonyms: Synonyms<'_>,
+) -> String {
+    if preferred.is_empty() {
+        return text.to_string();
+    }
+
+    let mut result = text.to_string();
+    // Sorted so overlapping entries resolve the same way every run
+    for word in synonyms.words() {
+        if preferred.contains(word) {
+            continue;
+        }
+        let target = synonyms
+            .get(word)
+            .unwrap_or_default()
+            .iter()
+            .find(|alt| preferred.contains(*alt) && !avoided.contains(*alt));
+        if let Some(target) = target {
+            result = replace_word_preserving_case(&result, word, target);
+        }
+    }
+
+    result
+}
+
 /// Replace a word in text, preserving the original's case pattern.
 fn replace_word_preserving_case(text: &str, target: &str, replacement: &str) -> String {
     let mut result = String::new();
@@ -127,6 +315,40 @@ fn inject_quirks(text: &str, quirks: &[super::voice::Quirk], rng: &mut StdRng) -
     result
 }
 
+/// With probability `frequency`, rephrase the final sentence as a
+/// rhetorical question: "She knew." → "Was it true that she knew?"
+/// Only plain statements of three or more words are rephrased.
+fn inject_question(text: &str, frequency: f32, names: &[&str], rng: &mut StdRng) -> String {
+    const LEAD_INS: &[&str] = &[
+        "Was it true that ",
+        "Could it be that ",
+        "Was it possible that ",
+    ];
+
+    if frequency <= 0.0 || rng.gen::<f32>() >= frequency {
+        return text.to_string();
+    }
+    let mut sentences = split_sentences(text);
+    let Some(last) = sentences.pop() else {
+        return text.to_string();
+    };
+    if !last.ends_with('.') || last.contains('"') || last.split_whitespace().count() < 3 {
+        return text.to_string();
+    }
+
+    let lead_in = LEAD_INS[rng.gen_range(0..LEAD_INS.len())];
+    let question = format!(
+        "{}?",
+        prepend_lead_in(last.trim_end_matches('.'), lead_in, names)
+    );
+    sentences
+        .into_iter()
+        .map(str::to_string)
+        .chain(std::iter::once(question))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Find a natural point to insert a quirk phrase.
 fn find_insertion_point(text: &str) -> Option<usize> {
     // Prefer inserting before a period (but not after the last sentence)
@@ -154,20 +376,24 @@ fn find_insertion_point(text: &str) -> Option<usize> {
 }
 
 /// Apply minimal fixes for detected repetition issues.
-fn remediate_repetition(text: &str, issues: &[RepetitionIssue], rng: &mut StdRng) -> String {
+fn remediate_repetition(
+    text: &str,
+    issues: &[RepetitionIssue],
+    synonyms: Synonyms<'_>,
+    names: &[&str],
+    rng: &mut StdRng,
+) -> String {
     let mut result = text.to_string();
-    let synonyms = build_synonym_table();
 
     for issue in issues {
         match issue {
             RepetitionIssue::RepeatedOpening(_) => {
-                result = swap_opening(&result, rng);
+                result = swap_opening(&result, names, rng);
             }
             RepetitionIssue::OverusedWord { word, .. } => {
-                let word_lower = word.to_lowercase();
-                if let Some(alternatives) = synonyms.get(word_lower.as_str()) {
+                if let Some(alternatives) = synonyms.get(word) {
                     if !alternatives.is_empty() {
-                        let replacement = alternatives[rng.gen_range(0..alternatives.len())];
+                        let replacement = &alternatives[rng.gen_range(0..alternatives.len())];
                         result = replace_word_preserving_case(&result, word, replacement);
                     }
                 }
@@ -182,7 +408,7 @@ fn remediate_repetition(text: &str, issues: &[RepetitionIssue], rng: &mut StdRng
 }
 
 /// Swap the opening of text to avoid repeated starts.
-fn swap_opening(text: &str, rng: &mut StdRng) -> String {
+fn swap_opening(text: &str, names: &[&str], rng: &mut StdRng) -> String {
     let openers = [
         "Meanwhile, ",
         "Just then, ",
@@ -196,95 +422,174 @@ fn swap_opening(text: &str, rng: &mut StdRng) -> String {
     let words: Vec<&str> = text.splitn(4, ' ').collect();
     if words.len() >= 3 {
         let opener = openers[rng.gen_range(0..openers.len())];
-        let first_word = words[0];
-        // Only lowercase common words (articles, pronouns, etc.)
-        // Keep proper nouns (names) capitalized
-        let adjusted = if is_proper_noun(first_word) {
-            first_word.to_string()
-        } else {
-            first_word[..1].to_lowercase() + &first_word[1..]
-        };
-        let rest = &text[first_word.len()..];
-        format!("{}{}{}", opener, adjusted, rest)
+        prepend_lead_in(text, opener, names)
     } else {
         text.to_string()
     }
 }
 
-/// Heuristic: a word is likely a proper noun if it starts uppercase
-/// and is NOT a common sentence-starting word.
-fn is_proper_noun(word: &str) -> bool {
-    let first = match word.chars().next() {
-        Some(c) => c,
-        None => return false,
+/// Prefix `text` with a lead-in phrase such as "Meanwhile, ", lowercasing
+/// the original first word unless it is one of the entity `names`.
+pub(crate) fn prepend_lead_in(text: &str, lead_in: &str, names: &[&str]) -> String {
+    let first_word = text.split(' ').next().unwrap_or("");
+    let mut chars = first_word.chars();
+    let Some(first) = chars.next() else {
+        return format!("{}{}", lead_in, text);
     };
-    if !first.is_uppercase() {
-        return false;
-    }
-    // Common words that start sentences but aren't proper nouns
-    let common_starters = [
-        "The",
-        "A",
-        "An",
-        "This",
-        "That",
-        "These",
-        "Those",
-        "It",
-        "There",
-        "Here",
-        "They",
-        "We",
-        "He",
-        "She",
-        "Every",
-        "Each",
-        "Some",
-        "No",
-        "All",
-        "Any",
-        "Something",
-        "Nothing",
-        "Everything",
-        "Everyone",
-        "Somewhere",
-        "Nowhere",
-    ];
-    !common_starters.contains(&word)
+    // Keep entity names capitalized; everything else was only capitalized
+    // for starting the sentence
+    let adjusted = if is_proper_noun(first_word, names) {
+        first_word.to_string()
+    } else {
+        first.to_lowercase().collect::<String>() + chars.as_str()
+    };
+    let rest = &text[first_word.len()..];
+    format!("{}{}{}", lead_in, adjusted, rest)
+}
+
+/// Whether `word` should keep its capital mid-sentence: the pronoun "I",
+/// or a word of one of the entity `names` (possibly possessive).
+fn is_proper_noun(word: &str, names: &[&str]) -> bool {
+    let bare = |w: &str| {
+        w.trim_end_matches(|c: char| !c.is_alphanumeric())
+            .to_string()
+    };
+    let word = bare(word);
+    let word = word.strip_suffix("'s").unwrap_or(&word);
+    word == "I"
+        || word.starts_with("I'")
+        || names
+            .iter()
+            .flat_map(|name| name.split_whitespace())
+            .any(|part| bare(part) == word)
 }
 
 /// Vary sentence structure to break monotony.
 fn vary_sentence_structure(text: &str, _rng: &mut StdRng) -> String {
-    // Simple heuristic: split at "and" or "but" conjunctions
-    let mut result = String::new();
-    let sentences: Vec<&str> = text.split(". ").collect();
-
-    for (i, sentence) in sentences.iter().enumerate() {
-        if sentence.contains(" and ") && sentence.split_whitespace().count() > 8 {
-            // Split long sentences at "and"
-            let parts: Vec<&str> = sentence.splitn(2, " and ").collect();
-            if parts.len() == 2 {
-                result.push_str(parts[0]);
-                result.push_str(". ");
-                // Capitalize the second part
-                let second = parts[1].trim();
-                if let Some(first_char) = second.chars().next() {
-                    result.push(first_char.to_uppercase().next().unwrap());
-                    result.push_str(&second[first_char.len_utf8()..]);
-                }
+    split_long_sentences(text, 8)
+}
+
+/// Split every sentence longer than `max_words` at a conjunction.
+fn split_long_sentences(text: &str, max_words: usize) -> String {
+    split_sentences(text)
+        .into_iter()
+        .flat_map(|sentence| {
+            if sentence.split_whitespace().count() > max_words {
+                split_at_conjunction(sentence)
             } else {
-                result.push_str(sentence);
+                vec![sentence.to_string()]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split long sentences and merge runs of very short ones so sentences
+/// fall within a voice's `(min, max)` word range where possible.
+fn shape_sentence_lengths(text: &str, (min, max): (u32, u32), names: &[&str]) -> String {
+    let (min, max) = (min as usize, max as usize);
+    let split = split_long_sentences(text, max);
+    let sentences = split_sentences(&split);
+
+    let mut shaped: Vec<String> = Vec::with_capacity(sentences.len());
+    let mut i = 0;
+    while i < sentences.len() {
+        if i + 1 < sentences.len() {
+            if let Some(merged) =
+                merge_short_sentences(sentences[i], sentences[i + 1], min, max, names)
+            {
+                shaped.push(merged);
+                i += 2;
+                continue;
             }
-        } else {
-            result.push_str(sentence);
         }
+        shaped.push(sentences[i].to_string());
+        i += 1;
+    }
+    shaped.join(" ")
+}
 
-        if i < sentences.len() - 1 {
-            result.push_str(". ");
+/// Split text into sentences after `.`, `!`, or `?` followed by
+/// whitespace. Terminators stay with their sentence.
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if matches!(c, '.' | '!' | '?') {
+            if let Some(&(next_i, next)) = chars.peek() {
+                if next.is_whitespace() {
+                    sentences.push(text[start..=i].trim());
+                    start = next_i;
+                }
+            }
         }
     }
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
 
-    result
+/// Split one sentence at the " and " nearest its middle, leaving both
+/// halves at least two words long. Quoted speech is never split.
+fn split_at_conjunction(sentence: &str) -> Vec<String> {
+    if sentence.contains('"') {
+        return vec![sentence.to_string()];
+    }
+    let total = sentence.split_whitespace().count();
+    let best = sentence
+        .match_indices(" and ")
+        .map(|(pos, _)| pos)
+        .filter(|&pos| {
+            let left = sentence[..pos].split_whitespace().count();
+            left >= 2 && total - left > 2
+        })
+        .min_by_key(|&pos| {
+            let left = sentence[..pos].split_whitespace().count();
+            left.abs_diff(total / 2)
+        });
+    let Some(pos) = best else {
+        return vec![sentence.to_string()];
+    };
+
+    let first = sentence[..pos].trim_end_matches(',');
+    let second = sentence[pos + " and ".len()..].trim();
+    let mut chars = second.chars();
+    let capitalized = match chars.next() {
+        Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    };
+    vec![format!("{}.", first), capitalized]
+}
+
+/// Join two adjacent plain statements with ", and" when both are shorter
+/// than `min` words and the result stays within `max`.
+fn merge_short_sentences(
+    first: &str,
+    second: &str,
+    min: usize,
+    max: usize,
+    names: &[&str],
+) -> Option<String> {
+    let first_words = first.split_whitespace().count();
+    let second_words = second.split_whitespace().count();
+    let mergeable =
+        |s: &str, words: usize| (2..min).contains(&words) && s.ends_with('.') && !s.contains('"');
+    // Skip seconds that already have an "and", to avoid "X, and Y, and Z."
+    if !mergeable(first, first_words)
+        || !mergeable(second, second_words)
+        || second.contains(" and ")
+        || first_words + second_words + 1 > max
+    {
+        return None;
+    }
+    Some(format!(
+        "{}, {}",
+        first.trim_end_matches('.'),
+        prepend_lead_in(second, "and ", names)
+    ))
 }
 
 /// Build a hardcoded synonym table for common overused words.
@@ -435,6 +740,7 @@ mod tests {
                 pattern: "you see".to_string(),
                 frequency: 1.0, // Always inject for testing
             }],
+            synonym_overrides: HashMap::new(),
         }
     }
 
@@ -442,7 +748,12 @@ mod tests {
     fn synonym_rotation_replaces_avoided() {
         let avoided: FxHashSet<String> = ["said"].iter().map(|s| s.to_string()).collect();
         let mut rng = StdRng::seed_from_u64(42);
-        let result = rotate_avoided_words("She said nothing.", &avoided, &mut rng);
+        let result = rotate_avoided_words(
+            "She said nothing.",
+            &avoided,
+            Synonyms::from(&SynonymTable::builtin()),
+            &mut rng,
+        );
         assert_ne!(result, "She said nothing.");
         assert!(!result.contains("said"));
     }
@@ -451,11 +762,106 @@ mod tests {
     fn synonym_rotation_preserves_case() {
         let avoided: FxHashSet<String> = ["said"].iter().map(|s| s.to_string()).collect();
         let mut rng = StdRng::seed_from_u64(42);
-        let result = rotate_avoided_words("Said nothing.", &avoided, &mut rng);
+        let result = rotate_avoided_words(
+            "Said nothing.",
+            &avoided,
+            Synonyms::from(&SynonymTable::builtin()),
+            &mut rng,
+        );
         // First character should still be uppercase
         assert!(result.starts_with(|c: char| c.is_uppercase()));
     }
 
+    #[test]
+    fn preferred_vocabulary_replaces_synonyms() {
+        let preferred: FxHashSet<String> = ["affirmative"].iter().map(|s| s.to_string()).collect();
+        let avoided = FxHashSet::default();
+        let synonyms = SynonymTable::parse_ron(r#"{ "yes": ["affirmative", "indeed"] }"#).unwrap();
+        let result = prefer_vocabulary(
+            "Yes, she said yes.",
+            &preferred,
+            &avoided,
+            (&synonyms).into(),
+        );
+        assert_eq!(result, "Affirmative, she said affirmative.");
+
+        // Nothing preferred, nothing changed
+        let result =
+            prefer_vocabulary("Yes, she said yes.", &avoided, &avoided, (&synonyms).into());
+        assert_eq!(result, "Yes, she said yes.");
+    }
+
+    #[test]
+    fn custom_synonyms_override_builtin() {
+        let custom =
+            SynonymTable::parse_ron(r#"{ "Walked": ["ambled"], "hull": ["keel"] }"#).unwrap();
+        let mut voice = make_test_voice();
+        voice.quirks.clear();
+        voice.vocabulary.avoided = ["walked", "hull"].iter().map(|s| s.to_string()).collect();
+        let ctx = NarrativeContext::default();
+        let mut synonyms = SynonymTable::builtin();
+        synonyms.extend(&custom);
+
+        for seed in 0..10 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let result = VarietyPass::apply(
+                "He walked along the hull.",
+                &voice,
+                &ctx,
+                &synonyms,
+                &[],
+                &mut rng,
+            );
+            assert!(result.contains("ambled along the keel"), "{}", result);
+        }
+
+        // Entries the custom table doesn't mention keep their built-ins
+        assert_eq!(synonyms.get("WALKED"), Some(&["ambled".to_string()][..]));
+        assert_eq!(synonyms.get("said").map(<[String]>::len), Some(4));
+    }
+
+    #[test]
+    fn voice_override_with_empty_list_keeps_word() {
+        let mut voice = make_test_voice();
+        voice.quirks.clear();
+        voice.structure_prefs.question_frequency = 0.0;
+        voice
+            .synonym_overrides
+            .insert("said".to_string(), Vec::new());
+        let ctx = NarrativeContext::default();
+
+        for seed in 0..10 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let result = VarietyPass::apply(
+                "She said nothing.",
+                &voice,
+                &ctx,
+                &SynonymTable::builtin(),
+                &[],
+                &mut rng,
+            );
+            assert_eq!(result, "She said nothing.");
+        }
+    }
+
+    #[test]
+    fn voice_override_replaces_global_synonyms() {
+        let mut voice = make_test_voice();
+        voice.quirks.clear();
+        voice.structure_prefs.question_frequency = 0.0;
+        voice
+            .synonym_overrides
+            .insert("said".to_string(), vec!["remarked".to_string()]);
+        let mut synonyms = SynonymTable::builtin();
+        synonyms.extend(&SynonymTable::parse_ron(r#"{ "said": ["barked"] }"#).unwrap());
+        let ctx = NarrativeContext::default();
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let result =
+            VarietyPass::apply("She said nothing.", &voice, &ctx, &synonyms, &[], &mut rng);
+        assert_eq!(result, "She remarked nothing.");
+    }
+
     #[test]
     fn quirk_injection_with_full_frequency() {
         let quirks = vec![Quirk {
@@ -512,6 +918,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn question_injection_statistical() {
+        let text = "The guests fell silent. Margaret set down her glass.";
+
+        let mut questions = 0;
+        for seed in 0..1000 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let result = inject_question(text, 0.3, &["Margaret"], &mut rng);
+            if result.ends_with('?') {
+                assert!(result.starts_with("The guests fell silent. "));
+                assert!(result.contains("that Margaret set down her glass?"));
+                questions += 1;
+            }
+        }
+        assert!(
+            (240..360).contains(&questions),
+            "Expected ~30% questions, got {}/1000",
+            questions
+        );
+
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(inject_question(text, 0.0, &["Margaret"], &mut rng), text);
+    }
+
     #[test]
     fn full_variety_pass() {
         let voice = make_test_voice();
@@ -522,6 +952,8 @@ mod tests {
             "She said nothing and looked away. He walked to the door slowly.",
             &voice,
             &ctx,
+            &SynonymTable::builtin(),
+            &[],
             &mut rng,
         );
         // Should have replaced some avoided words and injected quirk
@@ -539,12 +971,108 @@ mod tests {
             &[RepetitionIssue::RepeatedOpening(
                 "the evening was".to_string(),
             )],
+            Synonyms::from(&SynonymTable::builtin()),
+            &[],
             &mut rng,
         );
         // Opening should have changed
         assert!(!result.starts_with("The evening"));
     }
 
+    fn voice_with_lengths(range: (u32, u32)) -> ResolvedVoice {
+        let mut voice = make_test_voice();
+        voice.vocabulary.avoided.clear();
+        voice.quirks.clear();
+        voice.structure_prefs.avg_sentence_length = range;
+        voice.structure_prefs.shape_sentences = true;
+        voice.structure_prefs.question_frequency = 0.0;
+        voice
+    }
+
+    #[test]
+    fn short_sentence_voice_splits_long_sentence() {
+        let text = "The old captain walked to the rail and the crew watched him from the deck.";
+        assert_eq!(text.split_whitespace().count(), 15);
+        let ctx = NarrativeContext::default();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let result = VarietyPass::apply(
+            text,
+            &voice_with_lengths((4, 8)),
+            &ctx,
+            &SynonymTable::builtin(),
+            &[],
+            &mut rng,
+        );
+        assert_eq!(
+            result,
+            "The old captain walked to the rail. The crew watched him from the deck."
+        );
+
+        let result = VarietyPass::apply(
+            text,
+            &voice_with_lengths((20, 30)),
+            &ctx,
+            &SynonymTable::builtin(),
+            &[],
+            &mut rng,
+        );
+        assert_eq!(result, text);
+    }
+
+    #[test]
+    fn long_sentence_voice_merges_short_sentences() {
+        let result =
+            shape_sentence_lengths("The door creaked. Margaret froze.", (8, 20), &["Margaret"]);
+        assert_eq!(result, "The door creaked, and Margaret froze.");
+
+        // Questions and dialogue are left alone
+        let text = "Who was there? Nobody answered.";
+        assert_eq!(shape_sentence_lengths(text, (8, 20), &[]), text);
+    }
+
+    #[test]
+    fn sentence_shaping_is_opt_in() {
+        let text = "The door creaked. Margaret froze.";
+        let mut voice = voice_with_lengths((8, 20));
+        voice.structure_prefs.shape_sentences = false;
+        let ctx = NarrativeContext::default();
+        let mut rng = StdRng::seed_from_u64(42);
+        assert_eq!(
+            VarietyPass::apply(
+                text,
+                &voice,
+                &ctx,
+                &SynonymTable::builtin(),
+                &["Margaret"],
+                &mut rng
+            ),
+            text
+        );
+    }
+
+    #[test]
+    fn lead_in_keeps_only_entity_names_capitalized() {
+        let names = ["Margaret", "Dr. Grant"];
+        assert_eq!(
+            prepend_lead_in("Core temperature rising.", "and ", &names),
+            "and core temperature rising."
+        );
+        assert_eq!(
+            prepend_lead_in("Margaret's hands shook.", "and ", &names),
+            "and Margaret's hands shook."
+        );
+        assert_eq!(
+            prepend_lead_in("Grant froze.", "Meanwhile, ", &names),
+            "Meanwhile, Grant froze."
+        );
+        assert_eq!(
+            prepend_lead_in("Her hands shook.", "and ", &names),
+            "and her hands shook."
+        );
+        assert_eq!(prepend_lead_in("I knew.", "and ", &names), "and I knew.");
+    }
+
     #[test]
     fn sentence_structure_variation() {
         let mut rng = StdRng::seed_from_u64(42);
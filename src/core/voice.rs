@@ -1,7 +1,7 @@
 /// Voice system — persona/tone bundles that shape generated text.
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::schema::entity::VoiceId;
 
@@ -22,10 +22,14 @@ pub struct Voice {
     pub structure_prefs: StructurePrefs,
     #[serde(default)]
     pub quirks: Vec<Quirk>,
+    /// Per-voice replacements for overused words, consulted before the
+    /// global synonym table. An empty list means "never rotate this word".
+    #[serde(default)]
+    pub synonym_overrides: HashMap<String, Vec<String>>,
 }
 
 /// Preferred and avoided words for a voice.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct VocabularyPool {
     #[serde(default)]
     pub preferred: FxHashSet<String>,
@@ -34,7 +38,7 @@ pub struct VocabularyPool {
 }
 
 /// Binding a voice to a Markov corpus with weight and tags.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MarkovBinding {
     pub corpus_id: String,
     pub weight: f32,
@@ -43,10 +47,14 @@ pub struct MarkovBinding {
 }
 
 /// Structural preferences for text generation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StructurePrefs {
     /// (min, max) word count range for sentences.
     pub avg_sentence_length: (u32, u32),
+    /// Split and merge sentences to fit `avg_sentence_length`. Off by
+    /// default, so the range only shapes output for voices that opt in.
+    #[serde(default)]
+    pub shape_sentences: bool,
     /// 0.0 = simple, 1.0 = complex clause structure.
     pub clause_complexity: f32,
     /// 0.0..1.0 probability of generating questions.
@@ -57,6 +65,7 @@ impl Default for StructurePrefs {
     fn default() -> Self {
         Self {
             avg_sentence_length: (8, 18),
+            shape_sentences: false,
             clause_complexity: 0.5,
             question_frequency: 0.1,
         }
@@ -64,15 +73,33 @@ impl Default for StructurePrefs {
 }
 
 /// A verbal tic or recurring phrase that gets occasionally inserted.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Quirk {
     pub pattern: String,
     /// Probability of injecting per passage (0.0..1.0).
     pub frequency: f32,
 }
 
+/// Picks a voice from event tags (mood, stakes, function, participant
+/// tags) when no participant supplies one, e.g. an ominous narrator for
+/// `mood:dread`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoiceSelector {
+    /// Tags that must all be present for this selector to match.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    pub voice: VoiceId,
+}
+
+impl VoiceSelector {
+    /// Whether every required tag is in `tags`.
+    pub fn matches(&self, tags: &FxHashSet<String>) -> bool {
+        self.requires.iter().all(|tag| tags.contains(tag))
+    }
+}
+
 /// A fully resolved voice with inheritance chain merged.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ResolvedVoice {
     pub id: VoiceId,
     pub name: String,
@@ -81,6 +108,7 @@ pub struct ResolvedVoice {
     pub markov_bindings: Vec<MarkovBinding>,
     pub structure_prefs: StructurePrefs,
     pub quirks: Vec<Quirk>,
+    pub synonym_overrides: HashMap<String, Vec<String>>,
 }
 
 /// Registry of all loaded voices with inheritance resolution.
@@ -104,18 +132,62 @@ impl VoiceRegistry {
         self.voices.get(&id)
     }
 
+    /// Merge another registry into this one. Voices from `other` replace
+    /// voices in `self` with the same id.
+    pub fn merge(&mut self, other: VoiceRegistry) {
+        for (id, voice) in other.voices {
+            self.voices.insert(id, voice);
+        }
+    }
+
+    /// Iterate over all registered voices, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&VoiceId, &Voice)> {
+        self.voices.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.voices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voices.is_empty()
+    }
+
+    /// Find a voice by name. If several voices share the name, the one
+    /// with the lowest id wins.
+    pub fn get_by_name(&self, name: &str) -> Option<&Voice> {
+        self.voices
+            .values()
+            .filter(|voice| voice.name == name)
+            .min_by_key(|voice| voice.id.0)
+    }
+
+    /// Resolve a voice by name; see `get_by_name` and `resolve`.
+    pub fn resolve_by_name(&self, name: &str) -> Option<ResolvedVoice> {
+        self.resolve(self.get_by_name(name)?.id)
+    }
+
     /// Resolve a voice by walking its inheritance chain and merging properties.
     ///
     /// Child grammar_weights override parent, vocabulary pools union,
     /// markov_bindings concatenate, structure_prefs take child values
-    /// (falling back to parent), quirks concatenate.
+    /// (falling back to parent), quirks concatenate, synonym_overrides
+    /// override parent per word.
+    ///
+    /// A parent that is missing, or that would revisit a voice already in
+    /// the chain (an inheritance cycle), ends the walk; the voice resolves
+    /// from the ancestors collected up to that point.
     pub fn resolve(&self, id: VoiceId) -> Option<ResolvedVoice> {
         let voice = self.voices.get(&id)?;
 
         // Build the inheritance chain (child first, ancestors after)
         let mut chain = vec![voice];
+        let mut visited = HashSet::from([voice.id]);
         let mut current = voice;
         while let Some(parent_id) = current.parent {
+            if !visited.insert(parent_id) {
+                break;
+            }
             if let Some(parent) = self.voices.get(&parent_id) {
                 chain.push(parent);
                 current = parent;
@@ -131,6 +203,7 @@ impl VoiceRegistry {
         let mut markov_bindings = Vec::new();
         let mut structure_prefs = StructurePrefs::default();
         let mut quirks = Vec::new();
+        let mut synonym_overrides = HashMap::new();
 
         for ancestor in chain.iter().rev() {
             // Grammar weights: child overrides parent
@@ -150,6 +223,11 @@ impl VoiceRegistry {
 
             // Quirks: concatenate
             quirks.extend(ancestor.quirks.iter().cloned());
+
+            // Synonym overrides: child overrides parent
+            for (word, alternatives) in &ancestor.synonym_overrides {
+                synonym_overrides.insert(word.to_lowercase(), alternatives.clone());
+            }
         }
 
         Some(ResolvedVoice {
@@ -160,9 +238,124 @@ impl VoiceRegistry {
             markov_bindings,
             structure_prefs,
             quirks,
+            synonym_overrides,
         })
     }
 
+    /// Resolve a weighted mix of voices, e.g. `[(host, 0.6), (gossip, 0.4)]`.
+    /// Weights are normalized to sum to 1.
+    ///
+    /// Each voice is resolved first, then: grammar_weights are averaged by
+    /// blend weight (a rule a voice doesn't weight counts as 1.0),
+    /// vocabularies union, markov_bindings and quirks concatenate with
+    /// their weights and frequencies scaled, and structure_prefs are
+    /// interpolated (shaping is on if any voice opts in). Later voices win synonym_overrides conflicts. The
+    /// blend takes the first voice's id.
+    ///
+    /// Returns `None` if any voice is unknown, the list is empty, or the
+    /// weights don't sum to a positive value.
+    pub fn resolve_blend(&self, blend: &[(VoiceId, f32)]) -> Option<ResolvedVoice> {
+        let total: f32 = blend.iter().map(|(_, weight)| weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let parts: Vec<(ResolvedVoice, f32)> = blend
+            .iter()
+            .map(|(id, weight)| Some((self.resolve(*id)?, weight.max(0.0) / total)))
+            .collect::<Option<_>>()?;
+
+        let mut rule_names: Vec<&String> = parts
+            .iter()
+            .flat_map(|(voice, _)| voice.grammar_weights.keys())
+            .collect();
+        rule_names.sort();
+        rule_names.dedup();
+        let grammar_weights = rule_names
+            .into_iter()
+            .map(|name| {
+                let weight = parts
+                    .iter()
+                    .map(|(voice, share)| {
+                        voice.grammar_weights.get(name).copied().unwrap_or(1.0) * share
+                    })
+                    .sum();
+                (name.clone(), weight)
+            })
+            .collect();
+
+        let mut vocabulary = VocabularyPool::default();
+        let mut markov_bindings = Vec::new();
+        let mut quirks = Vec::new();
+        let mut synonym_overrides = HashMap::new();
+        let (mut min_len, mut max_len) = (0.0, 0.0);
+        let mut shape_sentences = false;
+        let mut clause_complexity = 0.0;
+        let mut question_frequency = 0.0;
+
+        for (voice, share) in &parts {
+            vocabulary
+                .preferred
+                .extend(voice.vocabulary.preferred.iter().cloned());
+            vocabulary
+                .avoided
+                .extend(voice.vocabulary.avoided.iter().cloned());
+
+            markov_bindings.extend(voice.markov_bindings.iter().map(|binding| MarkovBinding {
+                weight: binding.weight * share,
+                ..binding.clone()
+            }));
+            quirks.extend(voice.quirks.iter().map(|quirk| Quirk {
+                frequency: quirk.frequency * share,
+                ..quirk.clone()
+            }));
+            synonym_overrides.extend(voice.synonym_overrides.clone());
+
+            let prefs = &voice.structure_prefs;
+            shape_sentences |= prefs.shape_sentences;
+            min_len += prefs.avg_sentence_length.0 as f32 * share;
+            max_len += prefs.avg_sentence_length.1 as f32 * share;
+            clause_complexity += prefs.clause_complexity * share;
+            question_frequency += prefs.question_frequency * share;
+        }
+
+        let names: Vec<&str> = parts.iter().map(|(voice, _)| voice.name.as_str()).collect();
+        Some(ResolvedVoice {
+            id: parts[0].0.id,
+            name: names.join("+"),
+            grammar_weights,
+            vocabulary,
+            markov_bindings,
+            structure_prefs: StructurePrefs {
+                avg_sentence_length: (min_len.round() as u32, max_len.round() as u32),
+                shape_sentences,
+                clause_complexity,
+                question_frequency,
+            },
+            quirks,
+            synonym_overrides,
+        })
+    }
+
+    /// List voices whose resolved `markov_bindings` (including inherited
+    /// bindings) reference `corpus_id`, sorted by id.
+    pub fn voices_binding_corpus(&self, corpus_id: &str) -> Vec<VoiceId> {
+        let mut ids: Vec<VoiceId> = self
+            .voices
+            .keys()
+            .filter(|&&id| {
+                self.resolve(id).is_some_and(|voice| {
+                    voice
+                        .markov_bindings
+                        .iter()
+                        .any(|binding| binding.corpus_id == corpus_id)
+                })
+            })
+            .copied()
+            .collect();
+        ids.sort_by_key(|id| id.0);
+        ids
+    }
+
     /// Parse voices from a RON string. The string should contain a list of Voice definitions.
     pub fn parse_from_ron(&mut self, input: &str) -> Result<(), VoiceError> {
         let voices: Vec<Voice> = ron::from_str(input)?;
@@ -177,6 +370,21 @@ impl VoiceRegistry {
         let contents = std::fs::read_to_string(path)?;
         self.parse_from_ron(&contents)
     }
+
+    /// Parse voices from a JSON string. The string should contain an array of Voice definitions.
+    pub fn parse_json(&mut self, input: &str) -> Result<(), VoiceError> {
+        let voices: Vec<Voice> = serde_json::from_str(input)?;
+        for voice in voices {
+            self.register(voice);
+        }
+        Ok(())
+    }
+
+    /// Load voices from a JSON file. The file should contain an array of Voice definitions.
+    pub fn load_from_json(&mut self, path: &std::path::Path) -> Result<(), VoiceError> {
+        let contents = std::fs::read_to_string(path)?;
+        self.parse_json(&contents)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -185,6 +393,8 @@ pub enum VoiceError {
     Io(#[from] std::io::Error),
     #[error("RON deserialization error: {0}")]
     Ron(#[from] ron::error::SpannedError),
+    #[error("JSON deserialization error: {0}")]
+    Json(#[from] serde_json::Error),
     #[error("voice not found: {0:?}")]
     NotFound(VoiceId),
 }
@@ -215,6 +425,7 @@ mod tests {
             }],
             structure_prefs: StructurePrefs {
                 avg_sentence_length: (5, 12),
+                shape_sentences: false,
                 clause_complexity: 0.3,
                 question_frequency: 0.05,
             },
@@ -222,6 +433,7 @@ mod tests {
                 pattern: "if you will".to_string(),
                 frequency: 0.1,
             }],
+            synonym_overrides: HashMap::from([("said".to_string(), vec!["reported".to_string()])]),
         }
     }
 
@@ -247,6 +459,7 @@ mod tests {
             }],
             structure_prefs: StructurePrefs {
                 avg_sentence_length: (6, 15),
+                shape_sentences: false,
                 clause_complexity: 0.4,
                 question_frequency: 0.08,
             },
@@ -254,6 +467,7 @@ mod tests {
                 pattern: "by the bow".to_string(),
                 frequency: 0.15,
             }],
+            synonym_overrides: HashMap::from([("said".to_string(), Vec::new())]),
         }
     }
 
@@ -266,6 +480,101 @@ mod tests {
         assert!(registry.get(VoiceId(99)).is_none());
     }
 
+    #[test]
+    fn iterate_registered_voices() {
+        let mut registry = VoiceRegistry::new();
+        assert!(registry.is_empty());
+        registry.register(make_parent_voice());
+        registry.register(make_child_voice());
+        let mut third = make_parent_voice();
+        third.id = VoiceId(3);
+        third.name = "quartermaster".to_string();
+        registry.register(third);
+
+        assert_eq!(registry.len(), 3);
+        let mut names: Vec<&str> = registry.iter().map(|(_, v)| v.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["military", "quartermaster", "ship_captain"]);
+        assert!(registry.iter().all(|(id, voice)| *id == voice.id));
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        let mut registry = VoiceRegistry::new();
+        registry.register(make_parent_voice());
+        registry.register(make_child_voice());
+        let mut duplicate = make_parent_voice();
+        duplicate.id = VoiceId(5000);
+        registry.register(duplicate);
+
+        assert_eq!(
+            registry.get_by_name("ship_captain").map(|v| v.id),
+            Some(VoiceId(2))
+        );
+        assert_eq!(
+            registry.get_by_name("military").map(|v| v.id),
+            Some(VoiceId(1))
+        );
+        assert!(registry.get_by_name("pirate").is_none());
+
+        let resolved = registry.resolve_by_name("ship_captain").unwrap();
+        assert!(resolved.vocabulary.preferred.contains("sir"));
+    }
+
+    #[test]
+    fn resolve_blend_interpolates() {
+        let mut formal = make_parent_voice();
+        formal.id = VoiceId(10);
+        let mut casual = make_child_voice();
+        casual.id = VoiceId(11);
+        casual.parent = None;
+        let mut registry = VoiceRegistry::new();
+        registry.register(formal);
+        registry.register(casual);
+
+        let blend = registry
+            .resolve_blend(&[(VoiceId(10), 1.0), (VoiceId(11), 1.0)])
+            .unwrap();
+        assert_eq!(blend.id, VoiceId(10));
+        assert_eq!(blend.name, "military+ship_captain");
+        // (5, 12) and (6, 15) halfway
+        assert_eq!(blend.structure_prefs.avg_sentence_length, (6, 14));
+        assert!((blend.structure_prefs.clause_complexity - 0.35).abs() < 1e-6);
+        // 0.5 and 0.8 averaged; unweighted rules count as 1.0
+        assert!((blend.grammar_weights["greeting"] - 0.65).abs() < 1e-6);
+        assert!((blend.grammar_weights["action_detail"] - 1.5).abs() < 1e-6);
+        assert!(blend.vocabulary.preferred.contains("sir"));
+        assert!(blend.vocabulary.preferred.contains("aye"));
+        assert_eq!(blend.markov_bindings[1].weight, 0.75);
+        assert_eq!(blend.quirks[0].frequency, 0.05);
+
+        assert!(registry.resolve_blend(&[]).is_none());
+        assert!(registry.resolve_blend(&[(VoiceId(10), 0.0)]).is_none());
+        assert!(registry
+            .resolve_blend(&[(VoiceId(10), 1.0), (VoiceId(99), 1.0)])
+            .is_none());
+    }
+
+    #[test]
+    fn resolve_terminates_on_inheritance_cycle() {
+        let mut parent = make_parent_voice();
+        parent.parent = Some(VoiceId(2));
+        let mut registry = VoiceRegistry::new();
+        registry.register(parent);
+        registry.register(make_child_voice());
+
+        let resolved = registry.resolve(VoiceId(2)).unwrap();
+        assert_eq!(resolved.name, "ship_captain");
+        assert_eq!(resolved.markov_bindings.len(), 2);
+        assert_eq!(registry.resolve(VoiceId(1)).unwrap().quirks.len(), 2);
+
+        // A voice listing itself as parent
+        let mut own_parent = make_parent_voice();
+        own_parent.parent = Some(VoiceId(1));
+        registry.register(own_parent);
+        assert_eq!(registry.resolve(VoiceId(1)).unwrap().quirks.len(), 1);
+    }
+
     #[test]
     fn resolve_single_voice() {
         let mut registry = VoiceRegistry::new();
@@ -310,6 +619,9 @@ mod tests {
 
         // Quirks: concatenated
         assert_eq!(resolved.quirks.len(), 2);
+
+        // Synonym overrides: child's empty list overrides parent's
+        assert_eq!(resolved.synonym_overrides.get("said"), Some(&Vec::new()));
     }
 
     #[test]
@@ -395,4 +707,54 @@ mod tests {
         assert!(resolved.vocabulary.preferred.contains("indeed")); // from parent
         assert!(resolved.vocabulary.preferred.contains("apparently")); // from child
     }
+
+    #[test]
+    fn json_and_ron_voices_match() {
+        let mut from_ron = VoiceRegistry::new();
+        from_ron
+            .load_from_ron(std::path::Path::new("tests/fixtures/test_voices.ron"))
+            .unwrap();
+        let mut from_json = VoiceRegistry::new();
+        from_json
+            .load_from_json(std::path::Path::new("tests/fixtures/test_voices.json"))
+            .unwrap();
+
+        for id in [VoiceId(1), VoiceId(2)] {
+            assert_eq!(from_ron.resolve(id), from_json.resolve(id));
+        }
+    }
+
+    #[test]
+    fn invalid_json_returns_error() {
+        let mut registry = VoiceRegistry::new();
+        let result = registry.parse_json("{ not json");
+        assert!(matches!(result, Err(VoiceError::Json(_))));
+    }
+
+    #[test]
+    fn voices_binding_corpus_filters_by_corpus() {
+        let mut registry = VoiceRegistry::new();
+        let mut sailor = make_parent_voice();
+        sailor.id = VoiceId(10);
+        sailor.markov_bindings = vec![MarkovBinding {
+            corpus_id: "nautical_prose".to_string(),
+            weight: 1.0,
+            tags: Vec::new(),
+        }];
+        let mut clerk = make_parent_voice();
+        clerk.id = VoiceId(11);
+        clerk.markov_bindings = vec![MarkovBinding {
+            corpus_id: "office_memos".to_string(),
+            weight: 1.0,
+            tags: Vec::new(),
+        }];
+        registry.register(sailor);
+        registry.register(clerk);
+
+        assert_eq!(
+            registry.voices_binding_corpus("nautical_prose"),
+            vec![VoiceId(10)]
+        );
+        assert!(registry.voices_binding_corpus("missing").is_empty());
+    }
 }
@@ -1,11 +1,12 @@
-/// Political Intrigue genre template.
-///
-/// Oriented around power dynamics and information asymmetry. Formality
-/// gradients, public vs. private speech, coded language.
+//! Political Intrigue genre template.
+//!
+//! Oriented around power dynamics and information asymmetry. Formality
+//! gradients, public vs. private speech, coded language.
 
 #[cfg(test)]
 mod tests {
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn placeholder() {
         assert!(true);
     }
@@ -1,11 +1,12 @@
-/// Social Drama genre template.
-///
-/// Oriented around interpersonal dynamics. Sentence structures emphasize
-/// body language, subtext, dialogue beats, and emotional undercurrents.
+//! Social Drama genre template.
+//!
+//! Oriented around interpersonal dynamics. Sentence structures emphasize
+//! body language, subtext, dialogue beats, and emotional undercurrents.
 
 #[cfg(test)]
 mod tests {
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn placeholder() {
         assert!(true);
     }
@@ -1,11 +1,12 @@
-/// Survival Thriller genre template.
-///
-/// Oriented around environment and threat. Short, punchy sentences
-/// dominate at high tension; longer atmospheric sentences at low tension.
+//! Survival Thriller genre template.
+//!
+//! Oriented around environment and threat. Short, punchy sentences
+//! dominate at high tension; longer atmospheric sentences at low tension.
 
 #[cfg(test)]
 mod tests {
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn placeholder() {
         assert!(true);
     }
@@ -14,7 +14,7 @@ pub struct VoiceId(pub u64);
 
 /// Pronoun set for an entity, used by the grammar expansion system
 /// to resolve `{possessive}` and other pronoun template references.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub enum Pronouns {
     /// she/her/her/hers/herself
     SheHer,
@@ -25,67 +25,87 @@ pub enum Pronouns {
     TheyThem,
     /// it/its/its/its/itself
     ItIts,
+    /// Any other set, e.g. ze/hir/hir/hirs/hirself.
+    Custom {
+        subject: String,
+        object: String,
+        possessive: String,
+        possessive_standalone: String,
+        reflexive: String,
+    },
 }
 
 impl Pronouns {
     /// Nominative/subject form: "she", "he", "they", "it".
-    pub fn subject(&self) -> &'static str {
+    pub fn subject(&self) -> &str {
         match self {
             Self::SheHer => "she",
             Self::HeHim => "he",
             Self::TheyThem => "they",
             Self::ItIts => "it",
+            Self::Custom { subject, .. } => subject,
         }
     }
 
     /// Accusative/object form: "her", "him", "them", "it".
-    pub fn object(&self) -> &'static str {
+    pub fn object(&self) -> &str {
         match self {
             Self::SheHer => "her",
             Self::HeHim => "him",
             Self::TheyThem => "them",
             Self::ItIts => "it",
+            Self::Custom { object, .. } => object,
         }
     }
 
     /// Possessive determiner: "her", "his", "their", "its".
-    pub fn possessive(&self) -> &'static str {
+    pub fn possessive(&self) -> &str {
         match self {
             Self::SheHer => "her",
             Self::HeHim => "his",
             Self::TheyThem => "their",
             Self::ItIts => "its",
+            Self::Custom { possessive, .. } => possessive,
         }
     }
 
     /// Possessive standalone: "hers", "his", "theirs", "its".
-    pub fn possessive_standalone(&self) -> &'static str {
+    pub fn possessive_standalone(&self) -> &str {
         match self {
             Self::SheHer => "hers",
             Self::HeHim => "his",
             Self::TheyThem => "theirs",
             Self::ItIts => "its",
+            Self::Custom {
+                possessive_standalone,
+                ..
+            } => possessive_standalone,
         }
     }
 
     /// Reflexive: "herself", "himself", "themselves", "itself".
-    pub fn reflexive(&self) -> &'static str {
+    pub fn reflexive(&self) -> &str {
         match self {
             Self::SheHer => "herself",
             Self::HeHim => "himself",
             Self::TheyThem => "themselves",
             Self::ItIts => "itself",
+            Self::Custom { reflexive, .. } => reflexive,
         }
     }
 }
 
 /// A dynamic value that can be stored in entity properties.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Value {
     String(String),
     Float(f64),
     Int(i64),
     Bool(bool),
+    /// Rendered in templates as comma-separated elements.
+    List(Vec<Value>),
+    /// Nested attributes, reached with a dotted path (`{entity.stats.health}`).
+    Map(HashMap<String, Value>),
 }
 
 /// An entity is anything that can participate in a narrative event:
@@ -93,7 +113,7 @@ pub enum Value {
 ///
 /// The engine does not interpret tag semantics — it uses tags solely
 /// for grammar rule matching.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Entity {
     pub id: EntityId,
     pub name: String,
@@ -105,6 +125,43 @@ pub struct Entity {
 }
 
 impl Entity {
+    /// Create an entity with no tags, relationships, properties, or voice.
+    pub fn new(id: EntityId, name: &str, pronouns: Pronouns) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            pronouns,
+            tags: FxHashSet::default(),
+            relationships: Vec::new(),
+            voice_id: None,
+            properties: HashMap::new(),
+        }
+    }
+
+    /// Add a tag.
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.insert(tag.to_string());
+        self
+    }
+
+    /// Add several tags.
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags.extend(tags.iter().map(|t| t.to_string()));
+        self
+    }
+
+    /// Set the entity's default voice.
+    pub fn with_voice(mut self, voice_id: VoiceId) -> Self {
+        self.voice_id = Some(voice_id);
+        self
+    }
+
+    /// Set a property, replacing any existing value for `key`.
+    pub fn with_property(mut self, key: &str, value: Value) -> Self {
+        self.properties.insert(key.to_string(), value);
+        self
+    }
+
     /// Returns true if this entity has the given tag.
     pub fn has_tag(&self, tag: &str) -> bool {
         self.tags.contains(tag)
@@ -114,6 +171,18 @@ impl Entity {
     pub fn has_all_tags(&self, tags: &[&str]) -> bool {
         tags.iter().all(|tag| self.tags.contains(*tag))
     }
+
+    /// The first outgoing relationship toward `target`, if any.
+    pub fn relationship_to(&self, target: EntityId) -> Option<&Relationship> {
+        self.relationships_to(target).next()
+    }
+
+    /// Every outgoing relationship toward `target`, in order.
+    pub fn relationships_to(&self, target: EntityId) -> impl Iterator<Item = &Relationship> {
+        self.relationships
+            .iter()
+            .filter(move |rel| rel.target == target)
+    }
 }
 
 #[cfg(test)]
@@ -141,6 +210,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn structured_values_round_trip() {
+        let value = Value::Map(HashMap::from([
+            ("health".to_string(), Value::Int(80)),
+            (
+                "items".to_string(),
+                Value::List(vec![
+                    Value::String("lantern".to_string()),
+                    Value::Bool(false),
+                ]),
+            ),
+        ]));
+        let ron_text = ron::to_string(&value).unwrap();
+        assert_eq!(ron::from_str::<Value>(&ron_text).unwrap(), value);
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn relationship_lookup() {
+        let rel = |target: u64, rel_type: &str| Relationship {
+            source: EntityId(1),
+            target: EntityId(target),
+            rel_type: rel_type.to_string(),
+            intensity: 0.5,
+            tags: FxHashSet::default(),
+        };
+        let mut entity = make_entity(&[]);
+        entity.relationships = vec![rel(2, "rival"), rel(3, "ally"), rel(2, "sibling")];
+
+        assert_eq!(
+            entity.relationship_to(EntityId(2)).unwrap().rel_type,
+            "rival"
+        );
+        let types: Vec<&str> = entity
+            .relationships_to(EntityId(2))
+            .map(|r| r.rel_type.as_str())
+            .collect();
+        assert_eq!(types, ["rival", "sibling"]);
+
+        assert!(entity.relationship_to(EntityId(9)).is_none());
+        assert_eq!(entity.relationships_to(EntityId(9)).count(), 0);
+    }
+
+    #[test]
+    fn builder_matches_struct_literal() {
+        let built = Entity::new(EntityId(1), "Margaret", Pronouns::SheHer)
+            .with_tags(&["host", "formal"])
+            .with_voice(VoiceId(10))
+            .with_property("title", Value::String("Duchess".to_string()))
+            .with_property("age", Value::Int(45))
+            .with_property("composure", Value::Float(0.85))
+            .with_property("is_host", Value::Bool(true));
+        assert_eq!(built, make_entity(&["host", "formal"]));
+
+        let bare = Entity::new(EntityId(2), "James", Pronouns::HeHim);
+        assert!(bare.tags.is_empty());
+        assert!(bare.properties.is_empty());
+        assert_eq!(bare.voice_id, None);
+    }
+
+    #[test]
+    fn with_tag_accumulates() {
+        let entity = Entity::new(EntityId(1), "Margaret", Pronouns::SheHer)
+            .with_tag("host")
+            .with_tag("anxious")
+            .with_tag("host");
+        assert_eq!(entity.tags.len(), 2);
+        assert!(entity.has_all_tags(&["host", "anxious"]));
+    }
+
+    #[test]
+    fn custom_pronouns_round_trip() {
+        let ze = Pronouns::Custom {
+            subject: "ze".to_string(),
+            object: "hir".to_string(),
+            possessive: "hir".to_string(),
+            possessive_standalone: "hirs".to_string(),
+            reflexive: "hirself".to_string(),
+        };
+        assert_eq!(ze.subject(), "ze");
+        assert_eq!(ze.possessive_standalone(), "hirs");
+        assert_eq!(ze.reflexive(), "hirself");
+
+        let ron_text = ron::to_string(&ze).unwrap();
+        let parsed: Pronouns = ron::from_str(&ron_text).unwrap();
+        assert_eq!(parsed, ze);
+    }
+
     #[test]
     fn entity_creation() {
         let entity = make_entity(&["host", "anxious", "wealthy"]);
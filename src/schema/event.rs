@@ -1,9 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::str::FromStr;
+use thiserror::Error;
 
 use super::entity::{EntityId, Value};
 use super::narrative_fn::NarrativeFunction;
 
+/// Returned when parsing an unknown mood or stakes name.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown {kind}: {input}")]
+pub struct ParseEventFieldError {
+    pub kind: &'static str,
+    pub input: String,
+}
+
 /// The emotional tone of an event.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mood {
@@ -18,6 +28,18 @@ pub enum Mood {
 }
 
 impl Mood {
+    /// Every mood, in declaration order.
+    pub const ALL: [Mood; 8] = [
+        Self::Neutral,
+        Self::Tense,
+        Self::Warm,
+        Self::Dread,
+        Self::Euphoric,
+        Self::Somber,
+        Self::Chaotic,
+        Self::Intimate,
+    ];
+
     /// Returns the tag string for this mood (e.g., "mood:tense").
     pub fn tag(&self) -> &'static str {
         match self {
@@ -31,6 +53,42 @@ impl Mood {
             Self::Intimate => "mood:intimate",
         }
     }
+
+    /// Returns a normalized intensity value (0.0 = calm, 1.0 = overwhelming).
+    pub fn intensity(&self) -> f32 {
+        match self {
+            Self::Neutral => 0.3,
+            Self::Tense => 0.8,
+            Self::Warm => 0.3,
+            Self::Dread => 0.8,
+            Self::Euphoric => 0.7,
+            Self::Somber => 0.4,
+            Self::Chaotic => 0.9,
+            Self::Intimate => 0.4,
+        }
+    }
+}
+
+impl FromStr for Mood {
+    type Err = ParseEventFieldError;
+
+    /// Parses a mood name case-insensitively (e.g. "tense", "Dread").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "neutral" => Ok(Self::Neutral),
+            "tense" => Ok(Self::Tense),
+            "warm" => Ok(Self::Warm),
+            "dread" => Ok(Self::Dread),
+            "euphoric" => Ok(Self::Euphoric),
+            "somber" => Ok(Self::Somber),
+            "chaotic" => Ok(Self::Chaotic),
+            "intimate" => Ok(Self::Intimate),
+            _ => Err(ParseEventFieldError {
+                kind: "mood",
+                input: s.to_string(),
+            }),
+        }
+    }
 }
 
 /// The level of consequences at play.
@@ -44,6 +102,15 @@ pub enum Stakes {
 }
 
 impl Stakes {
+    /// Every stakes level, lowest first.
+    pub const ALL: [Stakes; 5] = [
+        Self::Trivial,
+        Self::Low,
+        Self::Medium,
+        Self::High,
+        Self::Critical,
+    ];
+
     /// Returns the tag string for this stakes level (e.g., "stakes:high").
     pub fn tag(&self) -> &'static str {
         match self {
@@ -54,6 +121,36 @@ impl Stakes {
             Self::Critical => "stakes:critical",
         }
     }
+
+    /// Returns a normalized intensity value (0.0 = trivial, 1.0 = critical).
+    pub fn intensity(&self) -> f32 {
+        match self {
+            Self::Trivial => 0.0,
+            Self::Low => 0.25,
+            Self::Medium => 0.5,
+            Self::High => 0.75,
+            Self::Critical => 1.0,
+        }
+    }
+}
+
+impl FromStr for Stakes {
+    type Err = ParseEventFieldError;
+
+    /// Parses a stakes level case-insensitively (e.g. "high", "Critical").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "trivial" => Ok(Self::Trivial),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            "critical" => Ok(Self::Critical),
+            _ => Err(ParseEventFieldError {
+                kind: "stakes",
+                input: s.to_string(),
+            }),
+        }
+    }
 }
 
 /// The result of an event.
@@ -65,6 +162,28 @@ pub enum Outcome {
     Ambiguous,
 }
 
+impl Outcome {
+    /// Returns the tag string for this outcome (e.g., "outcome:failure").
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::Success => "outcome:success",
+            Self::Failure => "outcome:failure",
+            Self::Partial => "outcome:partial",
+            Self::Ambiguous => "outcome:ambiguous",
+        }
+    }
+
+    /// Shift applied to the narrative function's valence (-1.0..=1.0).
+    pub fn valence_modifier(&self) -> f32 {
+        match self {
+            Self::Success => 0.4,
+            Self::Failure => -0.4,
+            Self::Partial => 0.1,
+            Self::Ambiguous => 0.0,
+        }
+    }
+}
+
 /// A lightweight reference to an entity participating in an event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityRef {
@@ -86,6 +205,89 @@ pub struct Event {
     pub metadata: HashMap<String, Value>,
 }
 
+impl Event {
+    /// A stable seed derived from the event's content: event type,
+    /// participants, location, mood, stakes, outcome, narrative function,
+    /// and metadata (in key order). Structurally identical events always
+    /// produce the same seed, across runs and platforms.
+    pub fn content_seed(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        hasher.write_str(&self.event_type);
+        for participant in self.participants.iter().chain(self.location.as_ref()) {
+            hasher.write_u64(participant.entity_id.0);
+            hasher.write_str(&participant.role);
+        }
+        hasher.write_str(self.mood.tag());
+        hasher.write_str(self.stakes.tag());
+        hasher.write_str(&format!("{:?}", self.outcome));
+        hasher.write_str(self.narrative_fn.name());
+
+        let mut keys: Vec<&String> = self.metadata.keys().collect();
+        keys.sort();
+        for key in keys {
+            hasher.write_str(key);
+            hasher.write_value(&self.metadata[key]);
+        }
+        hasher.finish()
+    }
+}
+
+/// FNV-1a, used instead of `DefaultHasher` whose output may change
+/// between Rust releases.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Length-prefixed so adjacent strings can't run together.
+    fn write_str(&mut self, s: &str) {
+        self.write_u64(s.len() as u64);
+        self.write_bytes(s.as_bytes());
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.write_bytes(&n.to_le_bytes());
+    }
+
+    /// Lists and maps are length-prefixed; map entries go in key order.
+    fn write_value(&mut self, value: &Value) {
+        match value {
+            Value::String(s) => self.write_str(s),
+            Value::Float(f) => self.write_u64(f.to_bits()),
+            Value::Int(i) => self.write_u64(*i as u64),
+            Value::Bool(b) => self.write_u64(*b as u64),
+            Value::List(items) => {
+                self.write_u64(items.len() as u64);
+                for item in items {
+                    self.write_value(item);
+                }
+            }
+            Value::Map(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                self.write_u64(keys.len() as u64);
+                for key in keys {
+                    self.write_str(key);
+                    self.write_value(&map[key]);
+                }
+            }
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,10 +341,66 @@ mod tests {
         assert_eq!(Stakes::High.tag(), "stakes:high");
     }
 
+    #[test]
+    fn mood_from_str() {
+        for mood in Mood::ALL {
+            let name = mood.tag().trim_start_matches("mood:");
+            assert_eq!(name.parse::<Mood>(), Ok(mood));
+            assert_eq!(name.to_uppercase().parse::<Mood>(), Ok(mood));
+        }
+        let err = "gloomy".parse::<Mood>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown mood: gloomy");
+    }
+
+    #[test]
+    fn stakes_from_str() {
+        for stakes in Stakes::ALL {
+            let name = stakes.tag().trim_start_matches("stakes:");
+            assert_eq!(name.parse::<Stakes>(), Ok(stakes));
+        }
+        assert_eq!("High".parse::<Stakes>(), Ok(Stakes::High));
+        assert!("extreme".parse::<Stakes>().is_err());
+    }
+
+    #[test]
+    fn intensity_ordering() {
+        assert!(Stakes::Critical.intensity() > Stakes::Trivial.intensity());
+        assert!(Mood::Chaotic.intensity() > Mood::Warm.intensity());
+    }
+
+    #[test]
+    fn content_seed_is_structural() {
+        let make = || Event {
+            event_type: "toast".to_string(),
+            participants: vec![EntityRef {
+                entity_id: EntityId(1),
+                role: "subject".to_string(),
+            }],
+            location: None,
+            mood: Mood::Warm,
+            stakes: Stakes::Low,
+            outcome: None,
+            narrative_fn: NarrativeFunction::Alliance,
+            metadata: HashMap::from([
+                ("drink".to_string(), Value::String("champagne".to_string())),
+                ("guests".to_string(), Value::Int(6)),
+            ]),
+        };
+
+        assert_eq!(make().content_seed(), make().content_seed());
+
+        let mut changed = make();
+        changed.mood = Mood::Tense;
+        assert_ne!(make().content_seed(), changed.content_seed());
+    }
+
     #[test]
     fn outcome_variants() {
         assert_eq!(Outcome::Success, Outcome::Success);
         assert_ne!(Outcome::Success, Outcome::Failure);
+        assert_eq!(Outcome::Failure.tag(), "outcome:failure");
+        assert!(Outcome::Success.valence_modifier() > Outcome::Partial.valence_modifier());
+        assert!(Outcome::Failure.valence_modifier() < Outcome::Ambiguous.valence_modifier());
     }
 
     #[test]
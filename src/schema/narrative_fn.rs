@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
 
 /// The core narrative function taxonomy.
 ///
@@ -8,7 +11,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// Each variant returns normalized pacing, valence, and intensity values
 /// that the grammar system uses to shape output.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum NarrativeFunction {
     /// Hidden information becomes known.
     Revelation,
@@ -35,6 +38,20 @@ pub enum NarrativeFunction {
 }
 
 impl NarrativeFunction {
+    /// Every built-in function, i.e. all variants except `Custom`.
+    pub const BUILTIN: [NarrativeFunction; 10] = [
+        Self::Revelation,
+        Self::Escalation,
+        Self::Confrontation,
+        Self::Betrayal,
+        Self::Alliance,
+        Self::Discovery,
+        Self::Loss,
+        Self::ComicRelief,
+        Self::Foreshadowing,
+        Self::StatusChange,
+    ];
+
     /// Returns a normalized pacing value (0.0 = slow/deliberate, 1.0 = fast/urgent).
     pub fn pacing(&self) -> f32 {
         match self {
@@ -102,6 +119,31 @@ impl NarrativeFunction {
             Self::Custom(name) => name.as_str(),
         }
     }
+
+    /// Inverse of `name()`: the built-in variant with this exact name, or
+    /// `Custom(name)` for anything else.
+    pub fn from_name(name: &str) -> NarrativeFunction {
+        Self::BUILTIN
+            .into_iter()
+            .find(|f| f.name() == name)
+            .unwrap_or_else(|| Self::Custom(name.to_string()))
+    }
+}
+
+impl fmt::Display for NarrativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for NarrativeFunction {
+    type Err = Infallible;
+
+    /// Parses a snake_case function name case-insensitively. Unknown names
+    /// become `Custom` (lowercased) rather than an error.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_name(&s.to_lowercase()))
+    }
 }
 
 #[cfg(test)]
@@ -223,4 +265,30 @@ mod tests {
             "my_fn"
         );
     }
+
+    #[test]
+    fn from_name_round_trips() {
+        for f in NarrativeFunction::BUILTIN {
+            assert_eq!(NarrativeFunction::from_name(f.name()), f);
+            assert_eq!(f.to_string(), f.name());
+        }
+        let custom = NarrativeFunction::Custom("Trade".to_string());
+        assert_eq!(NarrativeFunction::from_name(custom.name()), custom);
+        assert_eq!(custom.to_string(), "Trade");
+    }
+
+    #[test]
+    fn from_str_parses_builtins_and_custom() {
+        for f in NarrativeFunction::BUILTIN {
+            assert_eq!(f.name().parse::<NarrativeFunction>(), Ok(f.clone()));
+        }
+        assert_eq!(
+            "Comic_Relief".parse::<NarrativeFunction>(),
+            Ok(NarrativeFunction::ComicRelief)
+        );
+        assert_eq!(
+            "Trade".parse::<NarrativeFunction>(),
+            Ok(NarrativeFunction::Custom("trade".to_string()))
+        );
+    }
 }
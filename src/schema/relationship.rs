@@ -6,7 +6,7 @@ use super::entity::EntityId;
 /// A typed, directional edge between two entities with a numerical
 /// intensity value. The engine uses these to select appropriate language
 /// without understanding the game's specific relationship semantics.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Relationship {
     pub source: EntityId,
     pub target: EntityId,
@@ -135,3 +135,55 @@ fn no_broken_rule_references_in_templates() {
         }
     }
 }
+
+#[test]
+fn linter_reports_indirect_cycle_once() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_grammar_linter"))
+        .arg("tests/fixtures/cyclic_grammar.ron")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let cycle_errors: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.starts_with("ERROR: Rule cycle"))
+        .collect();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        cycle_errors,
+        ["ERROR: Rule cycle loop_a -> loop_b -> loop_c -> loop_a has no non-recursive alternative (infinite recursion)"]
+    );
+}
+
+#[test]
+fn linter_finds_no_orphans_in_shipped_grammars() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_grammar_linter"))
+        .arg("genre_data/")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let unreachable: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.contains("is not reachable"))
+        .collect();
+
+    assert!(unreachable.is_empty(), "{:?}", unreachable);
+}
+
+#[test]
+fn linter_warns_about_orphaned_rule() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_grammar_linter"))
+        .arg("tests/fixtures/orphan_grammar.ron")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let unreachable: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.contains("is not reachable"))
+        .collect();
+
+    assert_eq!(
+        unreachable,
+        ["WARNING: Rule 'forgotten_toast' is not reachable from any narrative function entry rule"]
+    );
+}
@@ -1,6 +1,7 @@
 /// Markov chain generation integration tests.
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn markov_placeholder() {
     // Will be populated in Prompt 5
     assert!(true);
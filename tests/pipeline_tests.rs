@@ -127,6 +127,184 @@ fn genre_blending_social_drama_and_survival_thriller() {
 }
 
 #[test]
+fn compound_revelation_and_betrayal() {
+    let grammars =
+        GrammarSet::load_from_ron(std::path::Path::new("genre_data/social_drama/grammar.ron"))
+            .unwrap();
+
+    let mut entities = HashMap::new();
+    entities.insert(
+        EntityId(1),
+        Entity {
+            id: EntityId(1),
+            name: "Margaret".to_string(),
+            pronouns: Pronouns::SheHer,
+            tags: ["host".to_string()].into_iter().collect(),
+            relationships: Vec::new(),
+            voice_id: None,
+            properties: HashMap::new(),
+        },
+    );
+    entities.insert(
+        EntityId(2),
+        Entity {
+            id: EntityId(2),
+            name: "James".to_string(),
+            pronouns: Pronouns::HeHim,
+            tags: ["guest".to_string()].into_iter().collect(),
+            relationships: Vec::new(),
+            voice_id: None,
+            properties: HashMap::new(),
+        },
+    );
+    let world = WorldState {
+        entities: &entities,
+    };
+
+    let event = Event {
+        event_type: "confession".to_string(),
+        participants: vec![
+            EntityRef {
+                entity_id: EntityId(1),
+                role: "subject".to_string(),
+            },
+            EntityRef {
+                entity_id: EntityId(2),
+                role: "object".to_string(),
+            },
+        ],
+        location: None,
+        mood: Mood::Tense,
+        stakes: Stakes::High,
+        outcome: None,
+        narrative_fn: NarrativeFunction::Revelation,
+        metadata: HashMap::new(),
+    };
+
+    // Distinctive fragments from each function's opening alternatives
+    let revelation_markers = [
+        "set down her glass",
+        "cleared her throat",
+        "looked at each of them",
+        "broke the silence",
+        "change everything",
+        "drew a breath",
+        "meaning to say",
+    ];
+    let betrayal_markers = [
+        "ground shift",
+        "then all at once",
+        "searching for some sign",
+        "crystal shattering",
+        "smile that gave it away",
+        "how could you?",
+        "voice cracked",
+    ];
+
+    for seed in 0..10 {
+        let mut engine = NarrativeEngine::builder()
+            .seed(seed)
+            .with_grammars(grammars.clone())
+            .build()
+            .unwrap();
+        let text = engine
+            .narrate_compound(
+                &event,
+                &[NarrativeFunction::Revelation, NarrativeFunction::Betrayal],
+                &world,
+            )
+            .unwrap();
+        let lower = text.to_lowercase();
+
+        assert!(!text.is_empty());
+        assert!(
+            revelation_markers.iter().any(|m| lower.contains(m)),
+            "Expected revelation text in: {}",
+            text
+        );
+        assert!(
+            betrayal_markers.iter().any(|m| lower.contains(m)),
+            "Expected betrayal text in: {}",
+            text
+        );
+    }
+}
+
+#[test]
+fn expansion_cache_leaves_genre_output_unchanged() {
+    let mut grammars = GrammarSet::default();
+    for genre in ["social_drama", "survival_thriller"] {
+        let path = format!("genre_data/{}/grammar.ron", genre);
+        grammars.merge(GrammarSet::load_from_ron(std::path::Path::new(&path)).unwrap());
+    }
+
+    let mut entities = HashMap::new();
+    for (id, name, pronouns) in [
+        (1, "Margaret", Pronouns::SheHer),
+        (2, "James", Pronouns::HeHim),
+    ] {
+        entities.insert(
+            EntityId(id),
+            Entity {
+                id: EntityId(id),
+                name: name.to_string(),
+                pronouns,
+                tags: Default::default(),
+                relationships: Vec::new(),
+                voice_id: None,
+                properties: HashMap::new(),
+            },
+        );
+    }
+    let world = WorldState {
+        entities: &entities,
+    };
+    let fns = [
+        NarrativeFunction::Revelation,
+        NarrativeFunction::Confrontation,
+        NarrativeFunction::Betrayal,
+        NarrativeFunction::Escalation,
+        NarrativeFunction::Discovery,
+    ];
+
+    let narrate_all = |cached: bool| {
+        let mut engine = NarrativeEngine::builder()
+            .seed(7)
+            .with_grammars(grammars.clone())
+            .cache_expansions(cached)
+            .build()
+            .unwrap();
+        let mut passages = Vec::new();
+        for round in 0..20 {
+            let event = Event {
+                event_type: "beat".to_string(),
+                participants: vec![
+                    EntityRef {
+                        entity_id: EntityId(1),
+                        role: "subject".to_string(),
+                    },
+                    EntityRef {
+                        entity_id: EntityId(2),
+                        role: "object".to_string(),
+                    },
+                ],
+                location: None,
+                mood: Mood::Tense,
+                stakes: Stakes::High,
+                outcome: None,
+                narrative_fn: fns[round % fns.len()].clone(),
+                metadata: HashMap::new(),
+            };
+            passages.push(engine.narrate(&event, &world).unwrap());
+        }
+        passages
+    };
+
+    assert_eq!(narrate_all(true), narrate_all(false));
+}
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
 fn pipeline_placeholder() {
     // Kept for backwards compatibility
     assert!(true);
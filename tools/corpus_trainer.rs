@@ -1,22 +1,58 @@
 /// Corpus Trainer — trains Markov models from text corpora.
 ///
-/// Usage: corpus_trainer --input <file.txt> --output <model.ron> --ngram <2|3|4>
+/// Usage: corpus_trainer --input <file.txt>[:tag] [--input ...] --output <model.ron> --ngram <2|3|4> [--min-count <n>] [--format ron|bin] [--dry-run]
+///        corpus_trainer --input <model.ron> --output <model.ron> --rename-tag <old:new> [--format ron|bin]
+use narrative_engine::core::markov::{
+    load_model, load_model_bin, save_model, save_model_bin, MarkovError, MarkovModel, MarkovTrainer,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::env;
+use std::path::Path;
 use std::process;
 
+const USAGE: &str = "Usage: corpus_trainer --input <file.txt>[:tag] [--input ...] --output <model.ron> --ngram <2|3|4> [--min-count <n>] [--format ron|bin] [--dry-run]\n       corpus_trainer --input <model.ron> --output <model.ron> --rename-tag <old:new> [--format ron|bin]";
+
+/// Seed and count for dry-run sample generations.
+const DRY_RUN_SEED: u64 = 42;
+const DRY_RUN_SAMPLES: usize = 3;
+
+/// On-disk model format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Ron,
+    Bin,
+}
+
+impl Format {
+    fn save(self, model: &MarkovModel, path: &Path) -> Result<(), MarkovError> {
+        match self {
+            Format::Ron => save_model(model, path),
+            Format::Bin => save_model_bin(model, path),
+        }
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    let mut input = None;
+    let mut inputs: Vec<String> = Vec::new();
     let mut output = None;
     let mut ngram = 2usize;
+    let mut renames: Vec<(String, String)> = Vec::new();
+    let mut format = Format::Ron;
+    let mut min_count = 1u32;
+    let mut dry_run = false;
 
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
             "--input" => {
                 i += 1;
-                input = Some(args[i].clone());
+                inputs.push(args.get(i).cloned().unwrap_or_else(|| {
+                    eprintln!("Error: --input expects a path");
+                    process::exit(1);
+                }));
             }
             "--output" => {
                 i += 1;
@@ -29,10 +65,42 @@ fn main() {
                     process::exit(1);
                 });
             }
+            "--min-count" => {
+                i += 1;
+                min_count = args
+                    .get(i)
+                    .and_then(|arg| arg.parse().ok())
+                    .unwrap_or_else(|| {
+                        eprintln!("Error: --min-count must be a positive integer");
+                        process::exit(1);
+                    });
+            }
+            "--format" => {
+                i += 1;
+                format = match args.get(i).map(String::as_str) {
+                    Some("ron") => Format::Ron,
+                    Some("bin") => Format::Bin,
+                    _ => {
+                        eprintln!("Error: --format must be ron or bin");
+                        process::exit(1);
+                    }
+                };
+            }
+            "--rename-tag" => {
+                i += 1;
+                match args.get(i).and_then(|arg| arg.split_once(':')) {
+                    Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+                        renames.push((from.to_string(), to.to_string()));
+                    }
+                    _ => {
+                        eprintln!("Error: --rename-tag expects <old:new>");
+                        process::exit(1);
+                    }
+                }
+            }
+            "--dry-run" => dry_run = true,
             "--help" | "-h" => {
-                println!(
-                    "Usage: corpus_trainer --input <file.txt> --output <model.ron> --ngram <2|3|4>"
-                );
+                println!("{}", USAGE);
                 process::exit(0);
             }
             other => {
@@ -43,30 +111,60 @@ fn main() {
         i += 1;
     }
 
-    let input_path = input.unwrap_or_else(|| {
+    if inputs.is_empty() {
         eprintln!("Error: --input is required");
-        eprintln!("Usage: corpus_trainer --input <file.txt> --output <model.ron> --ngram <2|3|4>");
+        eprintln!("{}", USAGE);
         process::exit(1);
-    });
+    }
 
     let output_path = output.unwrap_or_else(|| {
         eprintln!("Error: --output is required");
-        eprintln!("Usage: corpus_trainer --input <file.txt> --output <model.ron> --ngram <2|3|4>");
+        eprintln!("{}", USAGE);
         process::exit(1);
     });
 
+    if !renames.is_empty() {
+        if dry_run {
+            eprintln!("Error: --dry-run only applies to training");
+            process::exit(1);
+        }
+        if inputs.len() > 1 {
+            eprintln!("Error: --rename-tag takes a single --input model");
+            process::exit(1);
+        }
+        rename_tags(&inputs[0], &output_path, &renames, format);
+        return;
+    }
+
     if !(2..=4).contains(&ngram) {
         eprintln!("Error: --ngram must be 2, 3, or 4");
         process::exit(1);
     }
 
-    let text = std::fs::read_to_string(&input_path).unwrap_or_else(|e| {
-        eprintln!("Error reading input file '{}': {}", input_path, e);
-        process::exit(1);
-    });
-
-    println!("Training {}-gram model from '{}'...", ngram, input_path);
-    let model = narrative_engine::core::markov::MarkovTrainer::train(&text, ngram);
+    let sources: Vec<(String, Option<String>)> = inputs
+        .iter()
+        .map(|input| {
+            let (path, tag) = split_input_tag(input);
+            let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("Error reading input file '{}': {}", path, e);
+                process::exit(1);
+            });
+            match tag {
+                Some(tag) => println!("Training {}-gram model from '{}' [{}]...", ngram, path, tag),
+                None => println!("Training {}-gram model from '{}'...", ngram, path),
+            }
+            (text, tag.map(str::to_string))
+        })
+        .collect();
+    let sources: Vec<(&str, Option<&str>)> = sources
+        .iter()
+        .map(|(text, tag)| (text.as_str(), tag.as_deref()))
+        .collect();
+    let mut model = MarkovTrainer::train_sources(&sources, ngram);
+    model.prune(min_count);
+    if min_count > 1 {
+        println!("Pruned transitions seen fewer than {} times", min_count);
+    }
 
     let transition_count: usize = model.transitions.values().map(|v| v.len()).sum();
     println!(
@@ -82,7 +180,99 @@ fn main() {
         );
     }
 
-    narrative_engine::core::markov::save_model(&model, std::path::Path::new(&output_path))
+    match save_unless_dry_run(&model, Path::new(&output_path), format, dry_run) {
+        Ok(Some(report)) => {
+            print!("{}", report);
+            println!("Dry run: '{}' not written", output_path);
+        }
+        Ok(None) => println!("Model saved to '{}'", output_path),
+        Err(e) => {
+            eprintln!("Error saving model to '{}': {}", output_path, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Save the model, or with `dry_run` leave `output` untouched and return a
+/// report instead: `MarkovModel::stats` plus sample generations.
+fn save_unless_dry_run(
+    model: &MarkovModel,
+    output: &Path,
+    format: Format,
+    dry_run: bool,
+) -> Result<Option<String>, MarkovError> {
+    if dry_run {
+        return Ok(Some(dry_run_report(model)));
+    }
+    format.save(model, output)?;
+    Ok(None)
+}
+
+/// Stats plus a few generations from the global table and each tag, at a
+/// fixed seed so reruns on the same corpus are comparable.
+fn dry_run_report(model: &MarkovModel) -> String {
+    let stats = model.stats();
+    let mut report = format!(
+        "\n=== Dry Run: {}-gram model ===\n\
+         Prefixes: {}\n\
+         Transitions: {}\n\
+         Vocabulary: {}\n\
+         Sentence openers: {}\n",
+        stats.n, stats.prefixes, stats.transitions, stats.vocabulary, stats.sentence_openers
+    );
+    for (tag, prefixes) in &stats.tags {
+        report.push_str(&format!("Tag '{}': {} prefixes\n", tag, prefixes));
+    }
+
+    let tables = std::iter::once(None).chain(stats.tags.iter().map(|(tag, _)| Some(tag.as_str())));
+    for tag in tables {
+        report.push_str(&format!("\nSamples ({}):\n", tag.unwrap_or("global")));
+        let mut rng = StdRng::seed_from_u64(DRY_RUN_SEED);
+        for _ in 0..DRY_RUN_SAMPLES {
+            match model.generate(&mut rng, tag, 4, 15) {
+                Ok(text) => report.push_str(&format!("  {}\n", text)),
+                Err(e) => report.push_str(&format!("  (no output: {})\n", e)),
+            }
+        }
+    }
+    report
+}
+
+/// Split `file.txt:tag` into its path and tag. A suffix that looks like
+/// part of a path (contains a separator) is not treated as a tag.
+fn split_input_tag(input: &str) -> (&str, Option<&str>) {
+    match input.rsplit_once(':') {
+        Some((path, tag)) if !path.is_empty() && !tag.is_empty() && !tag.contains(['/', '\\']) => {
+            (path, Some(tag))
+        }
+        _ => (input, None),
+    }
+}
+
+/// Load an existing model (binary if it ends in `.bin`, otherwise RON),
+/// rename tags in place, and save it.
+fn rename_tags(input_path: &str, output_path: &str, renames: &[(String, String)], format: Format) {
+    let path = Path::new(input_path);
+    let loaded = if path.extension().and_then(|s| s.to_str()) == Some("bin") {
+        load_model_bin(path)
+    } else {
+        load_model(path)
+    };
+    let mut model = loaded.unwrap_or_else(|e| {
+        eprintln!("Error loading model '{}': {}", input_path, e);
+        process::exit(1);
+    });
+
+    for (from, to) in renames {
+        if model.rename_tag(from, to) {
+            println!("Renamed tag '{}' -> '{}'", from, to);
+        } else {
+            eprintln!("Warning: tag '{}' not found in model", from);
+        }
+    }
+
+    format
+        .save(&model, Path::new(output_path))
         .unwrap_or_else(|e| {
             eprintln!("Error saving model to '{}': {}", output_path, e);
             process::exit(1);
@@ -90,3 +280,37 @@ fn main() {
 
     println!("Model saved to '{}'", output_path);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dry_run_reports_without_writing() {
+        let model = MarkovTrainer::train_sources(
+            &[
+                ("The door slammed shut. Footsteps echoed.", Some("tense")),
+                ("The fire crackled. Laughter filled the room.", None),
+            ],
+            2,
+        );
+        let output =
+            std::env::temp_dir().join(format!("corpus_trainer_dry_run_{}.ron", std::process::id()));
+        let _ = std::fs::remove_file(&output);
+
+        let report = save_unless_dry_run(&model, &output, Format::Ron, true)
+            .unwrap()
+            .expect("dry run returns a report");
+        assert!(!output.exists());
+        assert!(report.contains(&format!("Prefixes: {}", model.stats().prefixes)));
+        assert!(report.contains("Samples (global)"));
+        assert!(report.contains("Samples (tense)"));
+
+        assert_eq!(
+            save_unless_dry_run(&model, &output, Format::Ron, false).unwrap(),
+            None
+        );
+        assert!(output.exists());
+        std::fs::remove_file(&output).unwrap();
+    }
+}
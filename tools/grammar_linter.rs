@@ -1,7 +1,9 @@
 /// Grammar Linter — validates grammar rule coverage and quality.
 ///
-/// Usage: grammar_linter <grammar_dir> [--models-dir <dir>]
+/// Usage: grammar_linter <grammar_dir> [--models-dir <dir>] [--format text|json]
+///                       [--roles <role,...>]
 use narrative_engine::core::grammar::GrammarSet;
+use serde::Serialize;
 use std::collections::HashSet;
 use std::path::Path;
 use std::process;
@@ -10,18 +12,42 @@ fn main() {
     let args: Vec<String> = std::env::args().collect();
 
     if args.len() < 2 || args[1] == "--help" || args[1] == "-h" {
-        println!("Usage: grammar_linter <grammar_dir> [--models-dir <dir>]");
+        println!(
+            "Usage: grammar_linter <grammar_dir> [--models-dir <dir>] [--format text|json] \
+             [--roles <role,...>]"
+        );
         process::exit(0);
     }
 
     let grammar_dir = &args[1];
     let mut models_dir = None;
+    let mut json = false;
+    let mut roles: Vec<String> = Vec::new();
 
     let mut i = 2;
     while i < args.len() {
         if args[i] == "--models-dir" && i + 1 < args.len() {
             i += 1;
             models_dir = Some(args[i].clone());
+        } else if args[i] == "--format" && i + 1 < args.len() {
+            i += 1;
+            match args[i].as_str() {
+                "json" => json = true,
+                "text" => json = false,
+                other => {
+                    eprintln!("ERROR: Unknown format '{}' (expected text or json)", other);
+                    process::exit(1);
+                }
+            }
+        } else if args[i] == "--roles" && i + 1 < args.len() {
+            i += 1;
+            roles.extend(
+                args[i]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|role| !role.is_empty())
+                    .map(String::from),
+            );
         }
         i += 1;
     }
@@ -30,22 +56,44 @@ fn main() {
     let mut grammars = GrammarSet::default();
     let grammar_path = Path::new(grammar_dir);
 
+    let mut brace_errors = Vec::new();
+    let mut load_failures = Vec::new();
+
     if grammar_path.is_file() {
+        lint_braces_in_file(grammar_path, &mut brace_errors);
         match GrammarSet::load_from_ron(grammar_path) {
             Ok(gs) => grammars.merge(gs),
             Err(e) => {
-                eprintln!("ERROR: Failed to load grammar file: {}", e);
+                let failure =
+                    Finding::new("load_failed", format!("Failed to load grammar file: {}", e));
+                if json {
+                    brace_errors.push(failure);
+                    print_json_report(&Report::new(brace_errors, Vec::new()));
+                    process::exit(1);
+                }
+                eprintln!("ERROR: {}", failure);
+                for error in &brace_errors {
+                    eprintln!("ERROR: {}", error);
+                }
                 process::exit(1);
             }
         }
     } else if grammar_path.is_dir() {
-        load_grammars_recursive(grammar_path, &mut grammars);
+        load_grammars_recursive(
+            grammar_path,
+            &mut grammars,
+            &mut brace_errors,
+            &mut load_failures,
+            !json,
+        );
     } else {
         eprintln!("ERROR: Path '{}' does not exist", grammar_dir);
         process::exit(1);
     }
 
-    println!("Loaded {} grammar rules", grammars.rules.len());
+    if !json {
+        println!("Loaded {} grammar rules", grammars.rules.len());
+    }
 
     // Load model corpus IDs if provided
     let model_ids: HashSet<String> = if let Some(ref dir) = models_dir {
@@ -55,7 +103,18 @@ fn main() {
     };
 
     // Run linting
-    let (errors, warnings) = lint_grammars(&grammars, &model_ids);
+    let role_refs: Vec<&str> = roles.iter().map(String::as_str).collect();
+    let (mut errors, mut warnings) = lint_grammars(&grammars, &model_ids, &role_refs);
+    errors.extend(brace_errors);
+    // Files that aren't grammars (e.g. voices.ron) fail to load too, so
+    // these don't fail the lint
+    warnings.extend(load_failures);
+
+    if json {
+        let report = Report::new(errors, warnings);
+        print_json_report(&report);
+        process::exit(if report.errors.is_empty() { 0 } else { 1 });
+    }
 
     // Print report
     println!("\n=== Grammar Lint Report ===\n");
@@ -85,20 +144,89 @@ fn main() {
     }
 }
 
-fn load_grammars_recursive(dir: &Path, grammars: &mut GrammarSet) {
+/// A single lint result. `kind` is a stable identifier for tooling.
+#[derive(Debug, Serialize)]
+struct Finding {
+    kind: &'static str,
+    message: String,
+}
+
+impl Finding {
+    fn new(kind: &'static str, message: String) -> Self {
+        Self { kind, message }
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// The `--format json` report.
+#[derive(Debug, Serialize)]
+struct Report {
+    errors: Vec<Finding>,
+    warnings: Vec<Finding>,
+    summary: Summary,
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+    errors: usize,
+    warnings: usize,
+}
+
+impl Report {
+    fn new(errors: Vec<Finding>, warnings: Vec<Finding>) -> Self {
+        let summary = Summary {
+            errors: errors.len(),
+            warnings: warnings.len(),
+        };
+        Self {
+            errors,
+            warnings,
+            summary,
+        }
+    }
+}
+
+fn print_json_report(report: &Report) {
+    match serde_json::to_string_pretty(report) {
+        Ok(text) => println!("{}", text),
+        Err(e) => {
+            eprintln!("ERROR: Failed to serialize report: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn load_grammars_recursive(
+    dir: &Path,
+    grammars: &mut GrammarSet,
+    brace_errors: &mut Vec<Finding>,
+    load_failures: &mut Vec<Finding>,
+    verbose: bool,
+) {
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if path.is_dir() {
-                load_grammars_recursive(&path, grammars);
+                load_grammars_recursive(&path, grammars, brace_errors, load_failures, verbose);
             } else if path.extension().and_then(|s| s.to_str()) == Some("ron") {
+                lint_braces_in_file(&path, brace_errors);
                 match GrammarSet::load_from_ron(&path) {
                     Ok(gs) => {
-                        println!("  Loaded: {}", path.display());
+                        if verbose {
+                            println!("  Loaded: {}", path.display());
+                        }
                         grammars.merge(gs);
                     }
                     Err(e) => {
-                        eprintln!("  ERROR loading {}: {}", path.display(), e);
+                        load_failures.push(Finding::new(
+                            "load_failed",
+                            format!("Failed to load {}: {}", path.display(), e),
+                        ));
                     }
                 }
             }
@@ -106,6 +234,27 @@ fn load_grammars_recursive(dir: &Path, grammars: &mut GrammarSet) {
     }
 }
 
+/// Flag likely unescaped braces in a grammar file's raw template text.
+/// Files that aren't grammar RON (e.g. voices) are skipped silently.
+fn lint_braces_in_file(path: &Path, brace_errors: &mut Vec<Finding>) {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return;
+    };
+    if let Ok(hints) = GrammarSet::lint_ron_braces(&contents) {
+        for (rule, hint) in hints {
+            brace_errors.push(Finding::new(
+                "unescaped_brace",
+                format!(
+                    "{}: rule '{}' has a likely unescaped brace: {}",
+                    path.display(),
+                    rule,
+                    hint
+                ),
+            ));
+        }
+    }
+}
+
 fn load_model_ids(dir: &str) -> HashSet<String> {
     let mut ids = HashSet::new();
     if let Ok(entries) = std::fs::read_dir(dir) {
@@ -118,7 +267,11 @@ fn load_model_ids(dir: &str) -> HashSet<String> {
     ids
 }
 
-fn lint_grammars(grammars: &GrammarSet, model_ids: &HashSet<String>) -> (Vec<String>, Vec<String>) {
+fn lint_grammars(
+    grammars: &GrammarSet,
+    model_ids: &HashSet<String>,
+    roles: &[&str],
+) -> (Vec<Finding>, Vec<Finding>) {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
 
@@ -136,7 +289,7 @@ fn lint_grammars(grammars: &GrammarSet, model_ids: &HashSet<String>) -> (Vec<Str
         "status_change",
     ];
 
-    let _moods = [
+    let moods = [
         "mood:neutral",
         "mood:tense",
         "mood:warm",
@@ -147,7 +300,7 @@ fn lint_grammars(grammars: &GrammarSet, model_ids: &HashSet<String>) -> (Vec<Str
         "mood:intimate",
     ];
 
-    let _stakes = [
+    let stakes = [
         "stakes:trivial",
         "stakes:low",
         "stakes:medium",
@@ -155,15 +308,61 @@ fn lint_grammars(grammars: &GrammarSet, model_ids: &HashSet<String>) -> (Vec<Str
         "stakes:critical",
     ];
 
+    // Required tags that no event context can produce (likely typos)
+    let intensities = ["intensity:high", "intensity:low"];
+    let outcomes = [
+        "outcome:success",
+        "outcome:failure",
+        "outcome:partial",
+        "outcome:ambiguous",
+    ];
+    let tones = ["tone:grim", "tone:hopeful"];
+    let mut requirements: Vec<(String, usize)> =
+        grammars.requirement_histogram().into_iter().collect();
+    requirements.sort();
+    for (tag, count) in &requirements {
+        let produced = if tag.starts_with("mood:") {
+            moods.contains(&tag.as_str())
+        } else if tag.starts_with("stakes:") {
+            stakes.contains(&tag.as_str())
+        } else if tag.starts_with("intensity:") {
+            intensities.contains(&tag.as_str())
+        } else if tag.starts_with("outcome:") {
+            outcomes.contains(&tag.as_str())
+        } else if tag.starts_with("tone:") {
+            tones.contains(&tag.as_str())
+        } else if let Some(fn_name) = tag.strip_prefix("fn:") {
+            // Custom functions are fine as long as they have an entry rule
+            fn_names.contains(&fn_name)
+                || grammars.rules.contains_key(&format!("{}_opening", fn_name))
+                || grammars.rules.contains_key(fn_name)
+        } else {
+            // Entity tags are game-defined; nothing to check
+            true
+        };
+        if !produced {
+            warnings.push(Finding::new(
+                "unproducible_tag",
+                format!(
+                    "Tag '{}' is required by {} rule(s) but no event context produces it",
+                    tag, count
+                ),
+            ));
+        }
+    }
+
     // Coverage analysis: check that each fn has at least _opening rule
     for fn_name in &fn_names {
         let opening_rule = format!("{}_opening", fn_name);
         if !grammars.rules.contains_key(&opening_rule) {
             // Not an error if the grammars don't cover this function
             // (partial templates are fine)
-            warnings.push(format!(
-                "No '{}' rule found for narrative function '{}'",
-                opening_rule, fn_name
+            warnings.push(Finding::new(
+                "missing_entry",
+                format!(
+                    "No '{}' rule found for narrative function '{}'",
+                    opening_rule, fn_name
+                ),
             ));
         }
     }
@@ -172,10 +371,13 @@ fn lint_grammars(grammars: &GrammarSet, model_ids: &HashSet<String>) -> (Vec<Str
     for (name, rule) in &grammars.rules {
         // Low variety warning
         if rule.alternatives.len() < 3 {
-            warnings.push(format!(
-                "Rule '{}' has only {} alternatives (minimum 3 recommended)",
-                name,
-                rule.alternatives.len()
+            warnings.push(Finding::new(
+                "low_variety",
+                format!(
+                    "Rule '{}' has only {} alternatives (minimum 3 recommended)",
+                    name,
+                    rule.alternatives.len()
+                ),
             ));
         }
 
@@ -189,56 +391,143 @@ fn lint_grammars(grammars: &GrammarSet, model_ids: &HashSet<String>) -> (Vec<Str
                     } = segment
                     {
                         if !model_ids.contains(corpus.as_str()) {
-                            warnings.push(format!(
-                                "Rule '{}' references Markov corpus '{}' which is not in loaded models",
-                                name, corpus
+                            warnings.push(Finding::new(
+                                "missing_corpus",
+                                format!(
+                                    "Rule '{}' references Markov corpus '{}' \
+                                     which is not in loaded models",
+                                    name, corpus
+                                ),
                             ));
                         }
                     }
                 }
             }
         }
+    }
 
-        // Check for rule references that don't exist
-        for alt in &rule.alternatives {
-            for segment in &alt.template.segments {
-                if let narrative_engine::core::grammar::TemplateSegment::RuleRef(ref_name) = segment
-                {
-                    if !grammars.rules.contains_key(ref_name.as_str()) {
-                        errors.push(format!(
-                            "Rule '{}' references non-existent rule '{}'",
-                            name, ref_name
-                        ));
-                    }
-                }
-            }
-        }
+    // Check for rule references that don't exist
+    for (name, ref_name) in grammars.dangling_references(roles) {
+        errors.push(Finding::new(
+            "broken_ref",
+            format!(
+                "Rule '{}' references non-existent rule '{}'",
+                name, ref_name
+            ),
+        ));
     }
 
-    // Check for direct self-referencing cycles (simple cycle detection)
-    for (name, rule) in &grammars.rules {
-        for alt in &rule.alternatives {
-            for segment in &alt.template.segments {
-                if let narrative_engine::core::grammar::TemplateSegment::RuleRef(ref_name) = segment
-                {
-                    if ref_name == name {
-                        // Direct self-reference — check if ALL alternatives are self-referencing
-                        let all_self_ref = rule.alternatives.iter().all(|a| {
-                            a.template.segments.iter().any(|s| {
-                                matches!(s, narrative_engine::core::grammar::TemplateSegment::RuleRef(r) if r == name)
-                            })
-                        });
-                        if all_self_ref {
-                            errors.push(format!(
-                                "Rule '{}' has no non-recursive alternative (infinite recursion)",
-                                name
-                            ));
-                        }
-                    }
-                }
-            }
-        }
+    // Rules that no entry point ever reaches are dead weight. A function's
+    // `_body` and `_closing` rules belong to it like its entry rule does,
+    // and any `{fn}_*_opening` variant can be picked by
+    // `select_opening_by_tags`
+    let mut entries: Vec<String> = fn_names
+        .iter()
+        .flat_map(|f| {
+            [
+                format!("{}_opening", f),
+                f.to_string(),
+                format!("{}_body", f),
+                format!("{}_closing", f),
+            ]
+        })
+        .collect();
+    entries.extend(
+        grammars
+            .rules
+            .keys()
+            .filter(|name| {
+                name.ends_with("_opening")
+                    && fn_names
+                        .iter()
+                        .any(|f| name.starts_with(&format!("{}_", f)))
+            })
+            .cloned(),
+    );
+    let entry_refs: Vec<&str> = entries.iter().map(String::as_str).collect();
+    for name in grammars.unreachable_rules(&entry_refs) {
+        warnings.push(Finding::new(
+            "unreachable",
+            format!(
+                "Rule '{}' is not reachable from any narrative function entry rule",
+                name
+            ),
+        ));
+    }
+
+    // Check for reference cycles with no way out (infinite recursion)
+    for cycle in grammars.recursive_cycles() {
+        errors.push(Finding::new(
+            "cycle",
+            format!(
+                "Rule cycle {} has no non-recursive alternative (infinite recursion)",
+                cycle.join(" -> ")
+            ),
+        ));
     }
 
     (errors, warnings)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_report_has_kinds_and_summary() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+            "confrontation_opening": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "{glare}"),
+                (weight: 1, text: "{missing_rule}"),
+                (weight: 1, text: "Silence."),
+            ]),
+            "glare": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "A glare.")]),
+        }"#,
+        )
+        .unwrap();
+        let (errors, warnings) = lint_grammars(&grammars, &HashSet::new(), &[]);
+        let report = Report::new(errors, warnings);
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["summary"]["errors"], 1);
+        assert_eq!(json["errors"][0]["kind"], "broken_ref");
+        assert!(json["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("missing_rule"));
+
+        let warnings = json["warnings"].as_array().unwrap();
+        assert_eq!(json["summary"]["warnings"], warnings.len());
+        assert!(warnings.iter().any(|w| w["kind"] == "low_variety"));
+        assert!(warnings.iter().any(|w| w["kind"] == "missing_entry"));
+    }
+
+    #[test]
+    fn opening_variants_count_as_entries() {
+        let grammars = GrammarSet::parse_ron(
+            r#"{
+            "confrontation_opening": Rule(requires: [], excludes: [], alternatives: [
+                (weight: 1, text: "Silence."),
+            ]),
+            "confrontation_tense_opening": Rule(requires: ["mood:tense"], excludes: [], alternatives: [
+                (weight: 1, text: "{glare}"),
+            ]),
+            "glare": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "A glare.")]),
+            "stray_opening": Rule(requires: [], excludes: [], alternatives: [(weight: 1, text: "Hm.")]),
+        }"#,
+        )
+        .unwrap();
+        let (_, warnings) = lint_grammars(&grammars, &HashSet::new(), &[]);
+        let unreachable: Vec<&str> = warnings
+            .iter()
+            .filter(|w| w.kind == "unreachable")
+            .map(|w| w.message.as_str())
+            .collect();
+
+        assert_eq!(
+            unreachable,
+            ["Rule 'stray_opening' is not reachable from any narrative function entry rule"]
+        );
+    }
+}
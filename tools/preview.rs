@@ -7,17 +7,21 @@
 ///   voice <name>                — set active voice
 ///   entity <name> <tag1,tag2>   — define a named entity
 ///   seed <n>                    — set RNG seed
-///   bulk <n>                    — generate n passages with variety stats
+///   bulk <n> [flags]            — generate n passages with variety stats
+///                                 (--parallel: across threads, --json: JSON report)
+///   context                     — show what the repetition tracker holds
+///   reload                      — reload grammars from --grammars
 ///   help                        — list commands
 ///   quit                        — exit
-use narrative_engine::core::grammar::GrammarSet;
+use narrative_engine::core::grammar::{GrammarSet, TraceEntry};
 use narrative_engine::core::markov::MarkovModel;
-use narrative_engine::core::pipeline::{NarrativeEngine, WorldState};
+use narrative_engine::core::pipeline::{NarrativeEngine, PipelineError, WorldState};
 use narrative_engine::core::voice::VoiceRegistry;
 use narrative_engine::schema::entity::{Entity, EntityId, VoiceId};
 use narrative_engine::schema::event::{EntityRef, Event, Mood, Stakes};
 use narrative_engine::schema::narrative_fn::NarrativeFunction;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, BufRead, Write};
 use std::path::Path;
 
@@ -136,24 +140,24 @@ fn main() {
                     println!("  stakes: trivial, low, medium, high, critical");
                     continue;
                 }
-                let narrative_fn = match parse_narrative_fn(parts[1]) {
-                    Some(f) => f,
-                    None => {
+                let narrative_fn = match parts[1].parse::<NarrativeFunction>() {
+                    Ok(f) if !matches!(f, NarrativeFunction::Custom(_)) => f,
+                    _ => {
                         println!("Unknown narrative function: {}", parts[1]);
                         continue;
                     }
                 };
-                let mood = match parse_mood(parts[2]) {
-                    Some(m) => m,
-                    None => {
-                        println!("Unknown mood: {}", parts[2]);
+                let mood = match parts[2].parse::<Mood>() {
+                    Ok(m) => m,
+                    Err(e) => {
+                        println!("Unknown mood: {}", e.input);
                         continue;
                     }
                 };
-                let stakes = match parse_stakes(parts[3]) {
-                    Some(s) => s,
-                    None => {
-                        println!("Unknown stakes: {}", parts[3]);
+                let stakes = match parts[3].parse::<Stakes>() {
+                    Ok(s) => s,
+                    Err(e) => {
+                        println!("Unknown stakes: {}", e.input);
                         continue;
                     }
                 };
@@ -192,15 +196,15 @@ fn main() {
                 };
 
                 match if let Some(vid) = active_voice_id {
-                    engine.narrate_as(&event, vid, &world)
+                    engine.narrate_as_detailed(&event, vid, &world)
                 } else {
-                    engine.narrate(&event, &world)
+                    engine.narrate_detailed(&event, &world)
                 } {
-                    Ok(text) => {
+                    Ok(narration) => {
                         println!("\n--- Generated Text ---");
-                        println!("{}", text);
+                        println!("{}", narration.text);
                         println!("--- End ---\n");
-                        print_expansion_trace(&event);
+                        print_expansion_trace(&narration.trace);
                     }
                     Err(e) => {
                         println!("ERROR: {}", e);
@@ -224,27 +228,32 @@ fn main() {
                     println!("Active voice cleared.");
                     continue;
                 }
-                // Search voices by name — we need to check all registered voices
-                // Since VoiceRegistry doesn't expose iteration, try common IDs
-                let mut found = false;
-                for id_val in 0..1000 {
-                    let vid = VoiceId(id_val);
-                    if let Some(voice) = voices.get(vid) {
-                        if voice.name == name {
-                            active_voice_id = Some(vid);
-                            println!("Active voice set to '{}' ({:?})", name, vid);
-                            found = true;
-                            break;
-                        }
-                    }
-                }
-                if !found {
+                if let Some(voice) = voices.get_by_name(name) {
+                    active_voice_id = Some(voice.id);
+                    println!("Active voice set to '{}' ({:?})", name, voice.id);
+                } else {
                     println!(
                         "Voice '{}' not found. Try a voice name from the loaded voice files.",
                         name
                     );
                 }
             }
+            "voices" => {
+                if voices.is_empty() {
+                    println!("No voices loaded.");
+                    continue;
+                }
+                let mut listed: Vec<_> = voices.iter().collect();
+                listed.sort_by_key(|(id, _)| id.0);
+                for (id, voice) in listed {
+                    match voice.parent.and_then(|p| voices.get(p)) {
+                        Some(parent) => {
+                            println!("  {} (id={}) parent={}", voice.name, id.0, parent.name)
+                        }
+                        None => println!("  {} (id={})", voice.name, id.0),
+                    }
+                }
+            }
             "entity" => {
                 if parts.len() < 3 {
                     println!("Usage: entity <name> <tag1,tag2,...>");
@@ -304,8 +313,15 @@ fn main() {
                 }
             }
             "bulk" => {
+                let parallel = parts[1..].contains(&"--parallel");
+                let json = parts[1..].contains(&"--json");
+                let parts: Vec<&str> = parts
+                    .iter()
+                    .copied()
+                    .filter(|p| *p != "--parallel" && *p != "--json")
+                    .collect();
                 if parts.len() < 2 {
-                    println!("Usage: bulk <n>");
+                    println!("Usage: bulk <n> [--parallel] [--json]");
                     println!("  Requires at least one entity. Define with 'entity' first.");
                     continue;
                 }
@@ -373,77 +389,82 @@ fn main() {
                     entities: &entities,
                 };
 
+                let events: Vec<Event> = (0..count)
+                    .map(|i| {
+                        let narrative_fn = fns[i % fns.len()].clone();
+                        Event {
+                            event_type: format!("bulk_{}", narrative_fn.name()),
+                            participants: participants.clone(),
+                            location: None,
+                            mood: moods[i % moods.len()],
+                            stakes: Stakes::High,
+                            outcome: None,
+                            narrative_fn,
+                            metadata: HashMap::new(),
+                        }
+                    })
+                    .collect();
+
+                let results = if parallel {
+                    match bulk_parallel(
+                        &bulk_engine,
+                        &events,
+                        active_voice_id,
+                        current_seed,
+                        &world,
+                    ) {
+                        Some(results) => results,
+                        None => continue,
+                    }
+                } else {
+                    events
+                        .iter()
+                        .map(|event| {
+                            if let Some(vid) = active_voice_id {
+                                bulk_engine.narrate_as(event, vid, &world)
+                            } else {
+                                bulk_engine.narrate(event, &world)
+                            }
+                        })
+                        .collect()
+                };
                 let mut passages = Vec::new();
                 let mut errors = 0;
-
-                for i in 0..count {
-                    let narrative_fn = fns[i % fns.len()].clone();
-                    let mood = moods[i % moods.len()];
-
-                    let event = Event {
-                        event_type: format!("bulk_{}", narrative_fn.name()),
-                        participants: participants.clone(),
-                        location: None,
-                        mood,
-                        stakes: Stakes::High,
-                        outcome: None,
-                        narrative_fn,
-                        metadata: HashMap::new(),
-                    };
-
-                    match if let Some(vid) = active_voice_id {
-                        bulk_engine.narrate_as(&event, vid, &world)
-                    } else {
-                        bulk_engine.narrate(&event, &world)
-                    } {
+                for result in results {
+                    match result {
                         Ok(text) => passages.push(text),
                         Err(_) => errors += 1,
                     }
                 }
 
+                let report = BulkReport::new(&passages, errors);
+                if json {
+                    match serde_json::to_string_pretty(&report) {
+                        Ok(text) => println!("{}", text),
+                        Err(e) => println!("Failed to serialize report: {}", e),
+                    }
+                    continue;
+                }
+
                 // Print statistics
                 println!(
                     "\n=== Bulk Generation: {} passages ({} errors) ===\n",
-                    passages.len(),
-                    errors
+                    report.count, report.errors
                 );
-
-                // Unique openings
-                let openings: Vec<String> = passages
-                    .iter()
-                    .map(|p| p.split('.').next().unwrap_or("").trim().to_string())
-                    .collect();
-                let unique_openings: std::collections::HashSet<&String> = openings.iter().collect();
+                if parallel {
+                    println!("Parallel mode: cross-passage repetition checking skipped.\n");
+                }
                 println!(
                     "Unique openings: {} / {}",
-                    unique_openings.len(),
-                    passages.len()
+                    report.unique_openings, report.count
                 );
-
-                // Average length
-                let avg_len: f64 = if passages.is_empty() {
-                    0.0
-                } else {
-                    passages.iter().map(|p| p.len() as f64).sum::<f64>() / passages.len() as f64
-                };
-                println!("Average length: {:.0} chars", avg_len);
-
-                // Word frequency distribution (top 10)
-                let mut word_counts: HashMap<String, u32> = HashMap::new();
-                for passage in &passages {
-                    for word in passage.split_whitespace() {
-                        let clean = word
-                            .trim_matches(|c: char| !c.is_alphanumeric())
-                            .to_lowercase();
-                        if clean.len() > 3 {
-                            *word_counts.entry(clean).or_insert(0) += 1;
-                        }
-                    }
-                }
-                let mut word_freq: Vec<(String, u32)> = word_counts.into_iter().collect();
-                word_freq.sort_by(|a, b| b.1.cmp(&a.1));
+                println!(
+                    "Distinct passages: {} / {}",
+                    report.distinct_passages, report.count
+                );
+                println!("Average length: {:.0} chars", report.avg_length);
                 println!("\nTop 10 words:");
-                for (word, count) in word_freq.iter().take(10) {
+                for (word, count) in &report.top_words {
                     println!("  {}: {}", word, count);
                 }
 
@@ -454,6 +475,30 @@ fn main() {
                 }
                 println!();
             }
+            "reload" => {
+                let Some(ref path) = grammars_path else {
+                    println!("No grammar path given. Start with --grammars <path>.");
+                    continue;
+                };
+                let mut reloaded = GrammarSet::default();
+                load_grammars_from_path(path, &mut reloaded);
+                println!("Reloaded {} grammar rules", reloaded.rules.len());
+                engine.reload_grammars(reloaded.clone());
+                grammars = reloaded;
+            }
+            "context" => {
+                let stats = engine.context_stats();
+                println!("Tracked passages: {}", stats.tracked_passages);
+                println!("Unique openings: {}", stats.unique_openings);
+                if stats.top_words.is_empty() {
+                    println!("No significant words tracked.");
+                } else {
+                    println!("Top words:");
+                    for (word, count) in &stats.top_words {
+                        println!("  {}: {}", word, count);
+                    }
+                }
+            }
             _ => {
                 println!(
                     "Unknown command: '{}'. Type 'help' for available commands.",
@@ -464,6 +509,61 @@ fn main() {
     }
 }
 
+/// Variety statistics for a `bulk` run, printed as-is by `bulk --json`.
+#[derive(Debug, Serialize)]
+struct BulkReport {
+    count: usize,
+    errors: usize,
+    /// Distinct first sentences.
+    unique_openings: usize,
+    avg_length: f64,
+    /// The ten most frequent words longer than three letters, most
+    /// frequent first (ties alphabetical).
+    top_words: Vec<(String, u32)>,
+    /// Fully distinct passages.
+    distinct_passages: usize,
+}
+
+impl BulkReport {
+    fn new(passages: &[String], errors: usize) -> Self {
+        let openings: HashSet<&str> = passages
+            .iter()
+            .map(|p| p.split('.').next().unwrap_or("").trim())
+            .collect();
+        let distinct: HashSet<&String> = passages.iter().collect();
+
+        let avg_length = if passages.is_empty() {
+            0.0
+        } else {
+            passages.iter().map(|p| p.len() as f64).sum::<f64>() / passages.len() as f64
+        };
+
+        let mut word_counts: HashMap<String, u32> = HashMap::new();
+        for passage in passages {
+            for word in passage.split_whitespace() {
+                let clean = word
+                    .trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase();
+                if clean.len() > 3 {
+                    *word_counts.entry(clean).or_insert(0) += 1;
+                }
+            }
+        }
+        let mut top_words: Vec<(String, u32)> = word_counts.into_iter().collect();
+        top_words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top_words.truncate(10);
+
+        BulkReport {
+            count: passages.len(),
+            errors,
+            unique_openings: openings.len(),
+            avg_length,
+            top_words,
+            distinct_passages: distinct.len(),
+        }
+    }
+}
+
 fn print_usage() {
     println!("Preview — interactive generation shell for testing grammars and voices.");
     println!();
@@ -479,9 +579,14 @@ fn print_help() {
     println!("Commands:");
     println!("  event <fn> <mood> <stakes>  Generate from a synthetic event");
     println!("  voice <name>                Set active voice (or 'none' to clear)");
+    println!("  voices                      List loaded voices");
     println!("  entity <name> <tags>        Define a named entity (tags comma-separated)");
     println!("  seed <n>                    Set RNG seed");
-    println!("  bulk <n>                    Generate n passages with variety statistics");
+    println!("  bulk <n> [flags]            Generate n passages with variety statistics");
+    println!("                                --parallel  generate across threads");
+    println!("                                --json      print the statistics as JSON");
+    println!("  context                     Show what the repetition tracker holds");
+    println!("  reload                      Reload grammars from --grammars");
     println!("  help                        Show this help");
     println!("  quit                        Exit");
     println!();
@@ -493,58 +598,20 @@ fn print_help() {
     println!("Stakes: trivial, low, medium, high, critical");
 }
 
-fn parse_narrative_fn(s: &str) -> Option<NarrativeFunction> {
-    match s.to_lowercase().as_str() {
-        "revelation" => Some(NarrativeFunction::Revelation),
-        "escalation" => Some(NarrativeFunction::Escalation),
-        "confrontation" => Some(NarrativeFunction::Confrontation),
-        "betrayal" => Some(NarrativeFunction::Betrayal),
-        "alliance" => Some(NarrativeFunction::Alliance),
-        "discovery" => Some(NarrativeFunction::Discovery),
-        "loss" => Some(NarrativeFunction::Loss),
-        "comic_relief" => Some(NarrativeFunction::ComicRelief),
-        "foreshadowing" => Some(NarrativeFunction::Foreshadowing),
-        "status_change" => Some(NarrativeFunction::StatusChange),
-        _ => None,
-    }
-}
-
-fn parse_mood(s: &str) -> Option<Mood> {
-    match s.to_lowercase().as_str() {
-        "neutral" => Some(Mood::Neutral),
-        "tense" => Some(Mood::Tense),
-        "warm" => Some(Mood::Warm),
-        "dread" => Some(Mood::Dread),
-        "euphoric" => Some(Mood::Euphoric),
-        "somber" => Some(Mood::Somber),
-        "chaotic" => Some(Mood::Chaotic),
-        "intimate" => Some(Mood::Intimate),
-        _ => None,
+/// Print each rule expansion, indented by depth, with the alternative
+/// chosen and the text it produced.
+fn print_expansion_trace(trace: &[TraceEntry]) {
+    for entry in trace {
+        println!(
+            "[Trace] {}{} #{} -> {:?}",
+            "  ".repeat(entry.depth as usize),
+            entry.rule_name,
+            entry.alt_index,
+            entry.output
+        );
     }
 }
 
-fn parse_stakes(s: &str) -> Option<Stakes> {
-    match s.to_lowercase().as_str() {
-        "trivial" => Some(Stakes::Trivial),
-        "low" => Some(Stakes::Low),
-        "medium" => Some(Stakes::Medium),
-        "high" => Some(Stakes::High),
-        "critical" => Some(Stakes::Critical),
-        _ => None,
-    }
-}
-
-fn print_expansion_trace(event: &Event) {
-    println!(
-        "[Trace] fn={} mood={} stakes={}",
-        event.narrative_fn.name(),
-        event.mood.tag(),
-        event.stakes.tag(),
-    );
-    println!("[Trace] Entry rule: {}_opening", event.narrative_fn.name());
-    println!("[Trace] Participants: {}", event.participants.len());
-}
-
 fn build_engine(
     grammars: GrammarSet,
     voices: VoiceRegistry,
@@ -556,10 +623,38 @@ fn build_engine(
         .with_grammars(grammars)
         .with_voices(voices)
         .with_markov_models(markov_models)
+        .trace_expansions(true)
         .build()
         .unwrap()
 }
 
+/// Generate `events` across threads, seeding event `i` with `seed + i`.
+/// Returns `None` (after explaining why) when built without `parallel`.
+#[cfg(feature = "parallel")]
+fn bulk_parallel(
+    engine: &NarrativeEngine,
+    events: &[Event],
+    voice_id: Option<VoiceId>,
+    seed: u64,
+    world: &WorldState<'_>,
+) -> Option<Vec<Result<String, PipelineError>>> {
+    Some(engine.narrate_batch_parallel(events, voice_id, seed, world))
+}
+
+#[cfg(not(feature = "parallel"))]
+fn bulk_parallel(
+    _engine: &NarrativeEngine,
+    _events: &[Event],
+    _voice_id: Option<VoiceId>,
+    _seed: u64,
+    _world: &WorldState<'_>,
+) -> Option<Vec<Result<String, PipelineError>>> {
+    println!(
+        "--parallel needs the 'parallel' feature: cargo run --features parallel --bin preview"
+    );
+    None
+}
+
 fn load_grammars_from_path(path: &str, grammars: &mut GrammarSet) {
     let p = Path::new(path);
     if p.is_file() {
@@ -660,3 +755,37 @@ fn load_models_from_path(path: &str, models: &mut HashMap<String, MarkovModel>)
         eprintln!("Models path is not a directory: {}", path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bulk_report_serializes_expected_keys() {
+        let passages = [
+            "The silence stretched. Nobody spoke.",
+            "The silence stretched. Nobody spoke.",
+            "Margaret smiled. The silence broke.",
+        ]
+        .map(String::from);
+        let report = BulkReport::new(&passages, 2);
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+
+        for key in [
+            "count",
+            "errors",
+            "unique_openings",
+            "avg_length",
+            "top_words",
+            "distinct_passages",
+        ] {
+            assert!(json.get(key).is_some(), "missing key {}", key);
+        }
+        assert_eq!(json["count"], 3);
+        assert_eq!(json["errors"], 2);
+        assert_eq!(json["unique_openings"], 2);
+        assert_eq!(json["distinct_passages"], 2);
+        assert_eq!(json["top_words"][0], serde_json::json!(["silence", 3]));
+    }
+}